@@ -10,8 +10,10 @@ cd FFmpeg
 git checkout release/3.2
 mkdir ~/FFmpeg-build
 cd ~/FFmpeg-build
-../FFmpeg/configure --disable-ffprobe --disable-ffserver --disable-doc --enable-avresample
+# the dlopen job needs shared libraries
+../FFmpeg/configure --disable-ffprobe --disable-ffserver --disable-doc --enable-avresample ${DLOPEN:+--enable-shared}
 make -j
 sudo make install
+sudo ldconfig
 make distclean
 popd
@@ -0,0 +1,9 @@
+// Fails to build when the bindings or the hand-written helpers pull in std without the std
+// feature.
+#![no_std]
+
+extern crate ffmpeg_sys;
+
+pub fn avcodec_major() -> u32 {
+    unsafe { ffmpeg_sys::avcodec_version() >> 16 }
+}
@@ -0,0 +1,20 @@
+#!/bin/bash
+# Moves the FFmpeg install in /usr/local into two prefixes, libavcodec in one and everything
+# else in the other, like Nix and Conan lay out their packages.
+
+set -e
+
+for prefix in /tmp/ffmpeg-a /tmp/ffmpeg-b; do
+	mkdir -p $prefix/include $prefix/lib/pkgconfig
+done
+
+for lib in libavcodec libavdevice libavfilter libavformat libavresample libavutil libswresample libswscale libpostproc; do
+	[[ -e /usr/local/lib/pkgconfig/$lib.pc ]] || continue
+
+	if [[ $lib == libavcodec ]]; then prefix=/tmp/ffmpeg-b; else prefix=/tmp/ffmpeg-a; fi
+
+	sudo mv /usr/local/include/$lib $prefix/include/
+	sudo mv /usr/local/lib/$lib.* $prefix/lib/
+	sed "s|^prefix=.*|prefix=$prefix|" /usr/local/lib/pkgconfig/$lib.pc > $prefix/lib/pkgconfig/$lib.pc
+	sudo rm /usr/local/lib/pkgconfig/$lib.pc
+done
@@ -1,47 +1,111 @@
 extern crate bindgen;
 extern crate cc;
+extern crate ffmpeg_sys_build;
 extern crate num_cpus;
 extern crate pkg_config;
 extern crate regex;
 
+use std::collections::BTreeSet;
 use std::env;
-use std::fs::{self, create_dir, symlink_metadata, File};
-use std::io::{self, BufRead, BufReader, Read, Write};
-use std::path::PathBuf;
-use std::process::Command;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::iter;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::slice;
 use std::str;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Instant;
 
 use bindgen::callbacks::{IntKind, MacroParsingBehavior, ParseCallbacks};
+use bindgen::MacroTypeVariation;
+use ffmpeg_sys_build::int_kind::{self, MacroKind};
+use ffmpeg_sys_build::{
+    blocklist, build_libs, check_output, config_mak, configure_args, deprecations, extralibs,
+    ffprobe_binary, json, libdir, license, paths, release, sha256, shared_lib, target, version_h,
+};
 use regex::Regex;
 
+// what goes into ffmpeg-sys-build-report.json, the cargo directives printed so far and the
+// fields collected along the way as (name, JSON value)
+static DIRECTIVES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+static REPORT: Mutex<Vec<(&'static str, String)>> = Mutex::new(Vec::new());
+
+// every line the build script prints goes through here so the cargo directives end up in the
+// build report. pkg-config and cc print theirs themselves, those are missing
+macro_rules! println {
+    ($($arg:tt)*) => {{
+        let line = format!($($arg)*);
+        if line.starts_with("cargo:") {
+            DIRECTIVES.lock().unwrap().push(line.clone());
+        }
+        print!("{}\n", line);
+    }};
+}
+
+// a part of the build script that only needs the include and link paths, like check.c and the
+// inline shims next to bindgen. With FFMPEG_PARALLEL=1 it runs on its own thread. Either way it
+// writes the lines it has to print to the sink it's given, they are printed once it's joined so
+// the output doesn't depend on the scheduling. The cc crate prints its cargo directives itself,
+// those of a stage can come in any order
+enum Stage<T> {
+    Done(T, Vec<String>),
+    Running(thread::JoinHandle<(T, Vec<String>)>),
+}
+
+fn stage<T, F>(f: F) -> Stage<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut Vec<String>) -> T + Send + 'static,
+{
+    let run = move || {
+        let mut lines = Vec::new();
+        let result = f(&mut lines);
+        (result, lines)
+    };
+
+    if !env::var("FFMPEG_PARALLEL").is_ok_and(|v| v == "1") {
+        let (result, lines) = run();
+        return Stage::Done(result, lines);
+    }
+
+    Stage::Running(thread::spawn(run))
+}
+
+impl<T> Stage<T> {
+    fn join(self) -> T {
+        let (result, lines) = match self {
+            Stage::Done(result, lines) => (result, lines),
+            Stage::Running(handle) => match handle.join() {
+                Ok(joined) => joined,
+                // the panic hook already ran on the stage's thread
+                Err(payload) => panic::resume_unwind(payload),
+            },
+        };
+
+        for line in lines {
+            println!("{}", line);
+        }
+        result
+    }
+}
+
 #[derive(Debug)]
 struct IntCallbacks;
 
 impl ParseCallbacks for IntCallbacks {
-    fn int_macro(&self, _name: &str, value: i64) -> Option<IntKind> {
-        let ch_layout = Regex::new(r"^AV_CH").unwrap();
-        let codec_cap = Regex::new(r"^AV_CODEC_CAP").unwrap();
-        let codec_flag = Regex::new(r"^AV_CODEC_FLAG").unwrap();
-        let error_max_size = Regex::new(r"^AV_ERROR_MAX_STRING_SIZE").unwrap();
-
-        if value >= i64::min_value() as i64 && value <= i64::max_value() as i64
-            && ch_layout.is_match(_name)
-        {
-            Some(IntKind::ULongLong)
-        } else if value >= i32::min_value() as i64 && value <= i32::max_value() as i64
-            && (codec_cap.is_match(_name) || codec_flag.is_match(_name))
-        {
-            Some(IntKind::UInt)
-        } else if error_max_size.is_match(_name) {
-            Some(IntKind::Custom {
+    fn int_macro(&self, name: &str, value: i64) -> Option<IntKind> {
+        int_kind::int_macro_kind(name, value).map(|kind| match kind {
+            MacroKind::Int => IntKind::Int,
+            MacroKind::UInt => IntKind::UInt,
+            MacroKind::ULongLong => IntKind::ULongLong,
+            MacroKind::Usize => IntKind::Custom {
                 name: "usize",
                 is_signed: false,
-            })
-        } else if value >= i32::min_value() as i64 && value <= i32::max_value() as i64 {
-            Some(IntKind::Int)
-        } else {
-            None
-        }
+            },
+        })
     }
 
     fn will_parse_macro(&self, name: &str) -> MacroParsingBehavior {
@@ -54,7 +118,7 @@ impl ParseCallbacks for IntCallbacks {
     }
 }
 
-fn version() -> String {
+fn version_tuple() -> (u8, u8) {
     let major: u8 = env::var("CARGO_PKG_VERSION_MAJOR")
         .unwrap()
         .parse()
@@ -64,6 +128,11 @@ fn version() -> String {
         .parse()
         .unwrap();
 
+    (major, minor)
+}
+
+fn version() -> String {
+    let (major, minor) = version_tuple();
     format!("{}.{}", major, minor)
 }
 
@@ -77,38 +146,600 @@ fn source() -> PathBuf {
 
 fn search() -> PathBuf {
     let mut absolute = env::current_dir().unwrap();
-    absolute.push(&output());
+    absolute.push(output());
     absolute.push("dist");
 
     absolute
 }
 
+const FFMPEG_GIT_URL: &str = "https://github.com/FFmpeg/FFmpeg";
+
+// records which sources the libraries in dist were built from, as "<url> <branch> <commit>"
+fn source_id_path() -> PathBuf {
+    search().join(".ffmpeg-source-id")
+}
+
+// the part of the recorded identity that is known before fetching
+fn requested_source() -> String {
+    format!("{} release/{}", FFMPEG_GIT_URL, version())
+}
+
 fn fetch() -> io::Result<()> {
     println!("Fetch FFmpeg Version {:?} from Git", version());
 
     let target = output().join(format!("ffmpeg-{}", version()));
     if target.exists() {
-        fs::remove_dir_all(target)?;
+        fs::remove_dir_all(&target)?;
     }
     let status = Command::new("git")
-        .current_dir(&output())
+        .current_dir(output())
         .arg("clone")
         .arg("-b")
         .arg(format!("release/{}", version()))
-        .arg("https://github.com/FFmpeg/FFmpeg")
+        .arg(FFMPEG_GIT_URL)
         .arg(format!("ffmpeg-{}", version()))
         .status()?;
+    if !status.success() {
+        return Err(io::Error::other("fetch failed"));
+    }
+
+    let rev_parse = Command::new("git")
+        .current_dir(&target)
+        .arg("rev-parse")
+        .arg("HEAD")
+        .output()?;
+    if !rev_parse.status.success() {
+        return Err(io::Error::other(
+            "couldn't resolve the fetched FFmpeg commit",
+        ));
+    }
+    let commit = String::from_utf8_lossy(&rev_parse.stdout).trim().to_string();
+
+    fs::create_dir_all(search())?;
+    fs::write(source_id_path(), format!("{} {}\n", requested_source(), commit))
+}
+
+// the headers FFmpeg's NVIDIA support is compiled against, when they aren't installed
+const NV_CODEC_HEADERS_TAG: &str = "n9.0.18.1";
+
+fn fetch_nv_codec_headers() -> io::Result<()> {
+    println!("Fetch nv-codec-headers {} from Git", NV_CODEC_HEADERS_TAG);
+
+    let target = output().join("nv-codec-headers");
+    if target.exists() {
+        fs::remove_dir_all(&target)?;
+    }
+    let status = Command::new("git")
+        .current_dir(output())
+        .arg("clone")
+        .arg("--depth=1")
+        .arg("-b")
+        .arg(NV_CODEC_HEADERS_TAG)
+        .arg("https://github.com/FFmpeg/nv-codec-headers")
+        .arg("nv-codec-headers")
+        .status()?;
+    if !status.success() {
+        return Err(io::Error::other("fetching nv-codec-headers failed"));
+    }
+
+    let status = make_command()?
+        .arg("install")
+        .arg(format!("PREFIX={}", search().to_string_lossy()))
+        .current_dir(&target)
+        .status()?;
+    if !status.success() {
+        return Err(io::Error::other("installing nv-codec-headers failed"));
+    }
+
+    Ok(())
+}
+
+// FFmpeg includes the AMF headers as <AMF/...>, the SDK keeps them in amf/public/include
+fn amf_include() -> io::Result<PathBuf> {
+    let sdk = match env::var("AMF_SDK_DIR") {
+        Ok(sdk) => PathBuf::from(sdk),
+        Err(_) => {
+            return Err(io::Error::other(
+                "the `build-amf` feature requires AMF_SDK_DIR to point to the AMF SDK",
+            ))
+        }
+    };
+
+    if sdk.join("AMF/core/Factory.h").exists() {
+        return Ok(sdk);
+    }
+
+    let headers = sdk.join("amf/public/include");
+    if !headers.join("core/Factory.h").exists() {
+        return Err(io::Error::other(format!(
+            "no AMF headers found in {}",
+            sdk.to_string_lossy()
+        )));
+    }
+
+    let include = search().join("include");
+    copy_dir(&headers, &include.join("AMF"))?;
+    Ok(include)
+}
+
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &to.join(entry.file_name()))?;
+        } else {
+            fs::copy(entry.path(), to.join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
+
+// FFmpeg switched from the Intel Media SDK to oneVPL in 6.0
+fn vpl_package() -> &'static str {
+    if version_tuple() >= (6, 0) {
+        "vpl"
+    } else {
+        "libmfx"
+    }
+}
+
+fn target_is_arm() -> bool {
+    let target = env::var("TARGET").unwrap();
+    target.starts_with("arm") || target.starts_with("aarch64")
+}
+
+// cfg!(target_os) in a build script is the OS of the build machine
+fn target_os() -> String {
+    env::var("CARGO_CFG_TARGET_OS").unwrap()
+}
+
+// FFmpeg's makefiles need GNU make, the BSDs and illumos install it as gmake
+fn make_command() -> io::Result<Command> {
+    let host = env::var("HOST").unwrap();
+    if !target::is_bsd_like(&host) {
+        return Ok(Command::new("make"));
+    }
+
+    match Command::new("gmake").arg("--version").output() {
+        Ok(ref output) if output.status.success() => Ok(Command::new("gmake")),
+        _ => Err(io::Error::other(format!(
+            "building FFmpeg on {} needs GNU make as gmake, install it with \
+             `pkg install gmake` on FreeBSD and DragonFly, `pkg_add gmake` on OpenBSD or \
+             `pkgin install gmake` on NetBSD and illumos",
+            host
+        ))),
+    }
+}
+
+// the Broadcom VideoCore libraries and headers of Raspberry Pi OS
+fn rpi_vc_dir() -> PathBuf {
+    env::var("RPI_VC_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("/opt/vc"))
+}
+
+fn feature_enabled(feature: &str) -> bool {
+    env::var(format!("CARGO_FEATURE_{}", feature.to_uppercase().replace('-', "_"))).is_ok()
+}
+
+// FFMPEG_LINK_MODE, the `static` feature picks the default
+fn link_mode() -> String {
+    match env::var("FFMPEG_LINK_MODE") {
+        Ok(mode) => match &*mode {
+            "static" | "dylib" | "auto" => mode,
+            _ => panic!("FFMPEG_LINK_MODE must be one of static, dylib or auto, not `{}`", mode),
+        },
+        Err(_) if env::var("CARGO_FEATURE_STATIC").is_ok() => "static".into(),
+        Err(_) => "dylib".into(),
+    }
+}
+
+// the FFmpeg libraries behind a feature of the same name and the libraries configure requires
+// for each (the `_deps` of configure), each one before the ones it depends on. avutil is
+// always linked, every library depends on it
+const COMPONENTS: &[(&str, &[&str])] = &[
+    ("avdevice", &["avformat", "avcodec"]),
+    ("avfilter", &[]),
+    ("avformat", &["avcodec"]),
+    ("avcodec", &[]),
+    ("avresample", &[]),
+    ("postproc", &[]),
+    ("swscale", &[]),
+    ("swresample", &[]),
+];
+
+// the programs and the libraries configure requires for them, it silently skips a program
+// whose libraries are disabled
+const PROGRAMS: &[(&str, &[&str])] = &[
+    ("ffmpeg", &["avcodec", "avfilter", "avformat"]),
+    ("ffplay", &["avcodec", "avformat", "swresample", "swscale"]),
+    ("ffprobe", &["avcodec", "avformat"]),
+];
+
+// the FFmpeg libraries of the enabled features, each one before the ones it depends on. the
+// build from source, FFMPEG_DIR and pkg-config all link these
+fn ffmpeg_libs() -> Vec<&'static str> {
+    COMPONENTS
+        .iter()
+        .map(|&(lib, _)| lib)
+        .filter(|&lib| feature_enabled(lib))
+        .chain(iter::once("avutil"))
+        .collect()
+}
+
+// fails on a feature set no discovery mode can satisfy, before anything is probed or built
+fn check_components() {
+    for &(component, deps) in COMPONENTS {
+        if !feature_enabled(component) {
+            continue;
+        }
+        if let Some(dep) = deps.iter().find(|&&dep| !feature_enabled(dep)) {
+            panic!("the `{}` feature requires the `{}` feature", component, dep);
+        }
+    }
+
+    if !feature_enabled("build") {
+        return;
+    }
+
+    for &(program, deps) in PROGRAMS {
+        if !feature_enabled(program) {
+            continue;
+        }
+        if let Some(dep) = deps.iter().find(|&&dep| !feature_enabled(dep)) {
+            panic!("the `{}` feature requires the `{}` feature", program, dep);
+        }
+    }
+
+    // avfilter builds without them, but configure drops the filters that need them
+    if feature_enabled("avfilter") {
+        for &(lib, filters) in &[
+            ("swscale", "scale, zscale and the other scaling"),
+            ("swresample", "aresample and the other resampling"),
+        ] {
+            if !feature_enabled(lib) {
+                println!(
+                    "cargo:warning=without the `{}` feature, libavfilter is built without the \
+                     {} filters",
+                    lib, filters
+                );
+            }
+        }
+    }
+}
+
+fn has_static_lib(dirs: &[PathBuf], lib: &str) -> bool {
+    dirs.iter().any(|dir| dir.join(format!("lib{}.a", lib)).is_file())
+}
+
+// the variable pkg-config takes its search path from, the targeted ones take precedence over
+// PKG_CONFIG_PATH
+fn pkg_config_path_var() -> String {
+    let target = env::var("TARGET").unwrap();
+    let kind = if target == env::var("HOST").unwrap() {
+        "HOST"
+    } else {
+        "TARGET"
+    };
+
+    [
+        format!("PKG_CONFIG_PATH_{}", target),
+        format!("PKG_CONFIG_PATH_{}", target.replace('-', "_")),
+        format!("{}_PKG_CONFIG_PATH", kind),
+    ]
+    .iter()
+    .find(|var| env::var_os(var).is_some())
+    .cloned()
+    .unwrap_or_else(|| "PKG_CONFIG_PATH".into())
+}
+
+// the major version in the file names of a shared library in FFMPEG_DIR, libavutil.so.58,
+// libavutil.58.dylib or avutil-58.dll
+fn shared_lib_major(ffmpeg_dir: &Path, lib: &str) -> Option<String> {
+    for dir in &[ffmpeg_dir.join("lib"), ffmpeg_dir.join("bin")] {
+        for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if let Some(major) = shared_lib::major(lib, &name) {
+                return Some(major.to_owned());
+            }
+        }
+    }
+    None
+}
+
+// the shared library of lib in the first of dirs that has one, with every file of the install
+// that resolves to it, the versioned sonames included. Windows installs the DLLs, named
+// <lib>-<major>.dll, into the bin directory next to lib
+fn shared_lib_files(dirs: &[PathBuf], lib: &str) -> Vec<PathBuf> {
+    let target = env::var("TARGET").unwrap();
+    let name = shared_lib::link_name(&target, lib);
+
+    for dir in dirs {
+        let dir = match name {
+            Some(_) => dir.clone(),
+            None => dir.parent().map_or(dir.clone(), |prefix| prefix.join("bin")),
+        };
+        let dir = match fs::canonicalize(&dir) {
+            Ok(dir) => dir,
+            Err(_) => continue,
+        };
+        // the unversioned name of the linker picks the version, the DLLs come in one only
+        let real = match name {
+            Some(ref name) => match fs::canonicalize(dir.join(name)) {
+                Ok(real) => Some(real),
+                Err(_) => continue,
+            },
+            None => None,
+        };
+
+        let mut files: Vec<_> = fs::read_dir(&dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                shared_lib::is_file_of(&target, lib, &path.file_name().unwrap().to_string_lossy())
+            })
+            .filter(|path| real.is_none() || fs::canonicalize(path).ok() == real)
+            .collect();
+        if !files.is_empty() {
+            files.sort();
+            return files;
+        }
+    }
+    Vec::new()
+}
+
+fn report_link_kind(lib: &str, kind: &str) {
+    println!("linking {} as {}", lib, kind);
+    println!("cargo:{}_link_mode={}", lib, kind);
+}
+
+// the TLS backend libavformat was configured with, from config.h for builds from source and
+// from the private libraries of libavformat otherwise
+fn tls_backend() -> Option<&'static str> {
+    if env::var("CARGO_FEATURE_BUILD").is_ok() {
+        let config_h = fs::read_to_string(source().join("config.h")).ok()?;
+        return [
+            ("gnutls", "GNUTLS"),
+            ("openssl", "OPENSSL"),
+            ("schannel", "SCHANNEL"),
+            ("securetransport", "SECURETRANSPORT"),
+        ]
+        .iter()
+        .find(|&&(_, config)| config_h.contains(&format!("#define CONFIG_{} 1", config)))
+        .map(|&(backend, _)| backend);
+    }
+
+    let (libs, frameworks) = if let (Ok(ffmpeg_dir), None) = (
+        env::var("FFMPEG_DIR"),
+        env::var_os("FFMPEG_PKG_CONFIG_PATH"),
+    ) {
+        let pc = PathBuf::from(ffmpeg_dir).join("lib/pkgconfig/libavformat.pc");
+        let private = fs::read_to_string(pc)
+            .ok()?
+            .lines()
+            .find(|line| line.starts_with("Libs.private:"))?
+            .to_owned();
+        let flags: Vec<_> = private.split_whitespace().collect();
+        (
+            flags
+                .iter()
+                .filter(|flag| flag.starts_with("-l"))
+                .map(|flag| flag[2..].to_owned())
+                .collect::<Vec<_>>(),
+            flags
+                .windows(2)
+                .filter(|pair| pair[0] == "-framework")
+                .map(|pair| pair[1].to_owned())
+                .collect::<Vec<_>>(),
+        )
+    } else {
+        let avformat = pkg_config::Config::new()
+            .statik(true)
+            .cargo_metadata(false)
+            .probe("libavformat")
+            .ok()?;
+        (avformat.libs, avformat.frameworks)
+    };
+
+    if libs.iter().any(|lib| lib == "gnutls") {
+        Some("gnutls")
+    } else if libs.iter().any(|lib| lib == "ssl") {
+        Some("openssl")
+    } else if libs.iter().any(|lib| lib == "secur32") {
+        Some("schannel")
+    } else if frameworks.iter().any(|framework| framework == "Security") {
+        Some("securetransport")
+    } else {
+        None
+    }
+}
+
+fn link_tls_backend(backend: &str) {
+    match backend {
+        // pkg-config knows the private dependencies (nettle, gmp, ...) of static builds
+        "gnutls" | "openssl" => {
+            if pkg_config::Config::new().statik(true).probe(backend).is_err() {
+                let libs: &[&str] = if backend == "gnutls" {
+                    &["gnutls"]
+                } else {
+                    &["ssl", "crypto"]
+                };
+                for lib in libs {
+                    println!("cargo:rustc-link-lib={}", lib);
+                }
+            }
+        }
+        "schannel" => {
+            for lib in &["secur32", "ncrypt", "crypt32"] {
+                println!("cargo:rustc-link-lib={}", lib);
+            }
+        }
+        "securetransport" => {
+            for framework in &["Security", "CoreFoundation"] {
+                println!("cargo:rustc-link-lib=framework={}", framework);
+            }
+        }
+        _ => unreachable!(),
+    }
+}
+
+// FFMPEG_SANITIZER, or the sanitizer the Rust code is built with
+fn sanitizer() -> Option<String> {
+    if let Ok(sanitizer) = env::var("FFMPEG_SANITIZER") {
+        match &*sanitizer {
+            "address" | "thread" | "undefined" => return Some(sanitizer),
+            _ => panic!(
+                "FFMPEG_SANITIZER must be one of address, thread or undefined, not `{}`",
+                sanitizer
+            ),
+        }
+    }
+
+    rust_sanitizer().filter(|sanitizer| sanitizer == "address" || sanitizer == "thread")
+}
+
+fn rust_sanitizer() -> Option<String> {
+    let rustflags = if let Ok(flags) = env::var("CARGO_ENCODED_RUSTFLAGS") {
+        flags.replace('\x1f', " ")
+    } else {
+        env::var("RUSTFLAGS").unwrap_or_default()
+    };
+    Regex::new(r"-Z\s*sanitizer=(\w+)")
+        .unwrap()
+        .captures(&rustflags)
+        .map(|captures| captures[1].to_owned())
+}
 
-    if status.success() {
-        Ok(())
+// compiler flags shared by the FFmpeg build and everything compiled against its headers
+fn sanitizer_cflags() -> Vec<String> {
+    if let Some(sanitizer) = sanitizer() {
+        vec![format!("-fsanitize={}", sanitizer), "-fno-omit-frame-pointer".into()]
+    } else if env::var("FFMPEG_FRAME_POINTERS").is_ok_and(|v| v == "1") {
+        vec!["-fno-omit-frame-pointer".into()]
     } else {
-        Err(io::Error::new(io::ErrorKind::Other, "fetch failed"))
+        Vec::new()
+    }
+}
+
+// every library of the enabled features that is missing or too new for the FFmpeg release, in a
+// single error instead of one build attempt per library
+fn check_build_libs() -> io::Result<()> {
+    let libs = build_libs::packages(&env::var("TARGET").unwrap(), vpl_package());
+    let mut problems = build_libs::unsupported(&libs, version_tuple(), feature_enabled);
+    for &(feature, package, _) in libs.iter() {
+        if !feature_enabled(feature) {
+            continue;
+        }
+        if let Err(e) = pkg_config::Config::new().cargo_metadata(false).probe(package) {
+            problems.push(format!(
+                "the `{}` feature requires {} to be installed and visible to pkg-config: {}",
+                feature, package, e
+            ));
+        }
+    }
+
+    if !problems.is_empty() {
+        return Err(io::Error::other(problems.join("\n")));
+    }
+
+    // a static rav1e carries its own Rust runtime, which the program linking ffmpeg-sys
+    // defines as well
+    if feature_enabled("build-lib-rav1e") {
+        if let Ok(libdir) = pkg_config::get_variable("rav1e", "libdir") {
+            let archive = Path::new(&libdir).join("librav1e.a");
+            let nm = env::var("NM").unwrap_or_else(|_| "nm".to_string());
+
+            match Command::new(&nm).arg("-g").arg("--defined-only").arg(&archive).output() {
+                Ok(out) if out.status.success() => {
+                    let stdout = String::from_utf8_lossy(&out.stdout);
+                    let symbols = target::rust_runtime_symbols(&stdout);
+                    if !symbols.is_empty() {
+                        return Err(io::Error::other(format!(
+                            "{} defines the Rust runtime symbols {}, which collide with the ones \
+                             of the program linking ffmpeg-sys; install rav1e as a shared \
+                             library (cargo cinstall --library-type=cdylib)",
+                            archive.display(),
+                            symbols.join(", ")
+                        )));
+                    }
+                }
+                // only a shared rav1e is installed
+                _ if !archive.exists() => {}
+                _ => println!(
+                    "cargo:warning=could not list the symbols of {} with {}, not checking it for \
+                     Rust runtime symbols",
+                    archive.display(),
+                    nm
+                ),
+            }
+        }
+    }
+
+    // srt links its own crypto library, which doesn't have to match the one FFmpeg uses. The
+    // static probes below list the private libraries too, they only fail on a broken .pc file
+    if feature_enabled("build-lib-srt") {
+        let probe = pkg_config::Config::new().statik(true).cargo_metadata(false).probe("srt");
+        if let Ok(srt) = probe {
+            let uses = |name: &str| srt.libs.iter().any(|lib| lib == name);
+            let mismatch = if uses("gnutls") && feature_enabled("build-lib-openssl") {
+                Some("gnutls")
+            } else if uses("crypto") && feature_enabled("build-lib-gnutls") {
+                Some("openssl")
+            } else {
+                None
+            };
+
+            if let Some(backend) = mismatch {
+                println!(
+                    "cargo:warning=srt is built against {}, both TLS libraries will be linked",
+                    backend
+                );
+            }
+        }
+    }
+
+    // librtmp is LGPL, but an OpenSSL backend can't be combined with a GPLv2 build
+    if feature_enabled("build-lib-rtmp")
+        && feature_enabled("build-license-gpl")
+        && !feature_enabled("build-license-version3")
+        && !feature_enabled("build-license-nonfree")
+    {
+        let probe = pkg_config::Config::new().statik(true).cargo_metadata(false).probe("librtmp");
+        if let Ok(rtmp) = probe {
+            if rtmp.libs.iter().any(|lib| lib == "ssl" || lib == "crypto") {
+                return Err(io::Error::other(
+                    "librtmp is built against OpenSSL, which requires the \
+                     `build-license-version3` or `build-license-nonfree` feature together with \
+                     `build-license-gpl`",
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// dist as the MSYS shell configure runs in sees it. cygpath knows the mount table, without it
+// C:\a\b is /c/a/b, the default mapping of MSYS2
+fn msys_prefix() -> String {
+    if let Ok(output) = Command::new("cygpath").arg("-u").arg(search()).output() {
+        if output.status.success() {
+            return String::from_utf8_lossy(&output.stdout).trim().to_owned();
+        }
     }
+
+    paths::msys_path(&search().to_string_lossy())
 }
 
 fn build() -> io::Result<()> {
     println!("Start build");
     let mut args = Vec::new();
+    // the libdir is explicit, configure takes the default of the toolchain otherwise, which is
+    // lib64 on some distributions
+    let prefix;
 
     if env::var("TARGET").unwrap().contains("windows") {
         let target = env::var("TARGET").unwrap();
@@ -119,21 +750,21 @@ fn build() -> io::Result<()> {
             args.push("--target-os=win64".into());
             args.push("--arch=x86_64".into());
         }
-        args.push(format!(
-            "--prefix=/{}",
-            search()
-                .to_string_lossy()
-                .replace(':', "")
-                .replace('\\', "/")
-                .replace(' ', "\\ ")
-                .replace('"', "\\\"")
-        ));
+        prefix = msys_prefix();
+        let escaped = prefix.replace(' ', "\\ ").replace('"', "\\\"");
+        args.push(format!("--prefix={}", escaped));
+        args.push(format!("--libdir={}/lib", escaped));
     } else {
-        args.push(format!("--prefix={}", search().to_string_lossy()));
+        prefix = search().to_string_lossy().into_owned();
+        args.push(format!("--prefix={}", prefix));
+        args.push(format!("--libdir={}/lib", prefix));
     }
 
     if env::var("TARGET").unwrap() != env::var("HOST").unwrap() {
         args.push(format!("--cross-prefix={}-", env::var("TARGET").unwrap()));
+        if let Some(os) = target::configure_target_os(&env::var("TARGET").unwrap()) {
+            args.push(format!("--target-os={}", os));
+        }
     }
 
     // control debug build
@@ -151,115 +782,152 @@ fn build() -> io::Result<()> {
 
     args.push("--enable-pic".into());
 
-    macro_rules! switch {
-        ($conf:expr, $feat:expr, $name:expr) => {
-            if env::var(concat!("CARGO_FEATURE_", $feat)).is_ok() {
-                $conf.push(concat!("--enable-", $name).into());
-            } else {
-                $conf.push(concat!("--disable-", $name).into());
+    // keep absolute paths out of the debug info and __FILE__, and don't let configure
+    // describe the build machine in the version string
+    if !env::var("TARGET").unwrap().contains("windows") {
+        // -ffile-prefix-map came with GCC 8 and clang 10, older ones have its two halves
+        let out_dir = output().to_string_lossy().into_owned();
+        let file_prefix_map = format!("-ffile-prefix-map={}=.", out_dir);
+        if cc::Build::new()
+            .is_flag_supported(&file_prefix_map)
+            .unwrap_or(false)
+        {
+            args.push(format!("--extra-cflags={}", file_prefix_map));
+        } else {
+            args.push(format!("--extra-cflags=-fdebug-prefix-map={}=.", out_dir));
+            if cc::Build::new()
+                .is_flag_supported(format!("-fmacro-prefix-map={}=.", out_dir))
+                .unwrap_or(false)
+            {
+                args.push(format!("--extra-cflags=-fmacro-prefix-map={}=.", out_dir));
             }
-        };
+        }
     }
+    args.push("--extra-version=ffmpeg-sys".into());
 
-    macro_rules! enable {
-        ($conf:expr, $feat:expr, $name:expr) => {
-            if env::var(concat!("CARGO_FEATURE_", $feat)).is_ok() {
-                $conf.push(concat!("--enable-", $name).into());
-            }
-        };
+    // instrument FFmpeg like the Rust code, or keep the frame pointers for profilers
+    if let (Some(sanitizer), None) = (rust_sanitizer(), sanitizer()) {
+        println!("cargo:warning=FFmpeg isn't built with the {} sanitizer", sanitizer);
+    }
+    let cflags = sanitizer_cflags();
+    if !cflags.is_empty() {
+        args.push(format!("--extra-cflags={}", cflags.join(" ")));
+    }
+    if let Some(sanitizer) = sanitizer() {
+        args.push(format!("--extra-ldflags=-fsanitize={}", sanitizer));
+    }
+
+    // FFMPEG_CPU picks the CPU the code is optimized for, native (or host) being the build machine
+    if let Ok(cpu) = env::var("FFMPEG_CPU") {
+        let cpu = if cpu == "native" { "host".to_owned() } else { cpu };
+        if cpu == "host" && env::var("TARGET").unwrap() != env::var("HOST").unwrap() {
+            panic!("FFMPEG_CPU={} optimizes for the build machine, which isn't the target", cpu);
+        }
+        args.push(format!("--cpu={}", cpu));
+    }
+    // only the code paths of that CPU, instead of picking them at runtime
+    if env::var("FFMPEG_DISABLE_RUNTIME_CPUDETECT").is_ok_and(|v| v == "1") {
+        args.push("--disable-runtime-cpudetect".into());
+    }
+    // full LTO of FFmpeg takes very long, thin LTO needs clang and a configure from FFmpeg 6.0 on,
+    // older ones only take a plain --enable-lto
+    match env::var("FFMPEG_LTO").as_ref().map(|v| v.as_str()) {
+        Ok("thin") if version_tuple() < (6, 0) => {
+            return Err(io::Error::other(format!(
+                "FFMPEG_LTO=thin requires FFmpeg 6.0 or newer, not {}, use FFMPEG_LTO=full",
+                version()
+            )));
+        }
+        Ok("thin") => args.push("--enable-lto=thin".into()),
+        Ok("full") | Ok("1") => args.push("--enable-lto".into()),
+        Ok("0") | Err(_) => {}
+        Ok(lto) => panic!("FFMPEG_LTO must be thin, full, 1 or 0, not {}", lto),
+    }
+
+    // one section per function and object, which the linker can drop when nothing references them
+    if feature_enabled("gc-sections") {
+        let target = env::var("TARGET").unwrap();
+        if target.contains("msvc") {
+            args.push("--extra-cflags=-Gy".into());
+            args.push("--extra-cflags=-Gw".into());
+        } else {
+            args.push("--extra-cflags=-ffunction-sections".into());
+            args.push("--extra-cflags=-fdata-sections".into());
+        }
+        args.push(format!("--extra-ldflags={}", gc_sections_link_arg()));
+    }
+
+    // the libraries, programs and external libraries of the features, from the tables of
+    // build/configure_args.rs
+    args.extend(configure_args::feature_flags(feature_enabled));
+
+    if env::var("CARGO_FEATURE_BUILD_VPL").is_ok() {
+        if vpl_package() == "vpl" {
+            args.push("--enable-libvpl".into());
+        } else {
+            println!(
+                "cargo:warning=FFmpeg {} only supports the Intel Media SDK, using libmfx",
+                version()
+            );
+            args.push("--enable-libmfx".into());
+        }
+    }
+
+    if target_is_arm() {
+        args.extend(configure_args::enable_flags(configure_args::RASPBERRY_PI, feature_enabled));
+
+        if env::var("CARGO_FEATURE_BUILD_MMAL").is_ok() {
+            let vc = rpi_vc_dir();
+            args.push(format!("--extra-cflags=-I{}", vc.join("include").to_string_lossy()));
+            args.push(format!("--extra-ldflags=-L{}", vc.join("lib").to_string_lossy()));
+        }
+    } else if configure_args::RASPBERRY_PI
+        .iter()
+        .any(|&(feature, _)| feature_enabled(feature))
+    {
+        println!(
+            "cargo:warning=the Raspberry Pi features are ignored for {}",
+            env::var("TARGET").unwrap()
+        );
     }
 
-    // macro_rules! disable {
-    //     ($conf:expr, $feat:expr, $name:expr) => (
-    //         if env::var(concat!("CARGO_FEATURE_", $feat)).is_err() {
-    //             $conf.arg(concat!("--disable-", $name));
-    //         }
-    //     )
-    // }
-
-    // the binary using ffmpeg-sys must comply with GPL
-    switch!(args, "BUILD_LICENSE_GPL", "gpl");
-
-    // the binary using ffmpeg-sys must comply with (L)GPLv3
-    switch!(args, "BUILD_LICENSE_VERSION3", "version3");
-
-    // the binary using ffmpeg-sys cannot be redistributed
-    switch!(args, "BUILD_LICENSE_NONFREE", "nonfree");
-
-    // configure building libraries based on features
-    switch!(args, "AVCODEC", "avcodec");
-    switch!(args, "AVDEVICE", "avdevice");
-    switch!(args, "AVFILTER", "avfilter");
-    switch!(args, "AVFORMAT", "avformat");
-    switch!(args, "AVRESAMPLE", "avresample");
-    switch!(args, "POSTPROC", "postproc");
-    switch!(args, "SWRESAMPLE", "swresample");
-    switch!(args, "SWSCALE", "swscale");
-
-    // configure building programs based on features
-    switch!(args, "FFMPEG", "ffmpeg");
-    switch!(args, "FFPLAY", "ffplay");
-    switch!(args, "FFPROBE", "ffprobe");
-
-    // configure external SSL libraries
-    enable!(args, "BUILD_LIB_GNUTLS", "gnutls");
-    enable!(args, "BUILD_LIB_OPENSSL", "openssl");
-    enable!(args, "BUILD_LIB_SCHANNEL", "schannel");
-    enable!(args, "BUILD_LIB_SECURETRANSPORT", "securetransport");
-
-    // configure external filters
-    enable!(args, "BUILD_LIB_FONTCONFIG", "fontconfig");
-    enable!(args, "BUILD_LIB_FREI0R", "frei0r");
-    enable!(args, "BUILD_LIB_LADSPA", "ladspa");
-    enable!(args, "BUILD_LIB_ASS", "libass");
-    enable!(args, "BUILD_LIB_FREETYPE", "libfreetype");
-    enable!(args, "BUILD_LIB_FRIBIDI", "libfribidi");
-    enable!(args, "BUILD_LIB_OPENCV", "libopencv");
-
-    // configure external encoders/decoders
-    enable!(args, "BUILD_LIB_AACPLUS", "libaacplus");
-    enable!(args, "BUILD_LIB_CELT", "libcelt");
-    enable!(args, "BUILD_LIB_DCADEC", "libdcadec");
-    enable!(args, "BUILD_LIB_FAAC", "libfaac");
-    enable!(args, "BUILD_LIB_FDK_AAC", "libfdk-aac");
-    enable!(args, "BUILD_LIB_GSM", "libgsm");
-    enable!(args, "BUILD_LIB_ILBC", "libilbc");
-    enable!(args, "BUILD_LIB_VAZAAR", "libvazaar");
-    enable!(args, "BUILD_LIB_MP3LAME", "libmp3lame");
-    enable!(args, "BUILD_LIB_OPENCORE_AMRNB", "libopencore-amrnb");
-    enable!(args, "BUILD_LIB_OPENCORE_AMRWB", "libopencore-amrwb");
-    enable!(args, "BUILD_LIB_OPENH264", "libopenh264");
-    enable!(args, "BUILD_LIB_OPENH265", "libopenh265");
-    enable!(args, "BUILD_LIB_OPENJPEG", "libopenjpeg");
-    enable!(args, "BUILD_LIB_OPUS", "libopus");
-    enable!(args, "BUILD_LIB_SCHROEDINGER", "libschroedinger");
-    enable!(args, "BUILD_LIB_SHINE", "libshine");
-    enable!(args, "BUILD_LIB_SNAPPY", "libsnappy");
-    enable!(args, "BUILD_LIB_SPEEX", "libspeex");
-    enable!(args, "BUILD_LIB_STAGEFRIGHT_H264", "libstagefright-h264");
-    enable!(args, "BUILD_LIB_THEORA", "libtheora");
-    enable!(args, "BUILD_LIB_TWOLAME", "libtwolame");
-    enable!(args, "BUILD_LIB_UTVIDEO", "libutvideo");
-    enable!(args, "BUILD_LIB_VO_AACENC", "libvo-aacenc");
-    enable!(args, "BUILD_LIB_VO_AMRWBENC", "libvo-amrwbenc");
-    enable!(args, "BUILD_LIB_VORBIS", "libvorbis");
-    enable!(args, "BUILD_LIB_VPX", "libvpx");
-    enable!(args, "BUILD_LIB_WAVPACK", "libwavpack");
-    enable!(args, "BUILD_LIB_WEBP", "libwebp");
-    enable!(args, "BUILD_LIB_X264", "libx264");
-    enable!(args, "BUILD_LIB_X265", "libx265");
-    enable!(args, "BUILD_LIB_AVS", "libavs");
-    enable!(args, "BUILD_LIB_XVID", "libxvid");
-
-    // other external libraries
-    enable!(args, "BUILD_NVENC", "nvenc");
-
-    // configure external protocols
-    enable!(args, "BUILD_LIB_SMBCLIENT", "libsmbclient");
-    enable!(args, "BUILD_LIB_SSH", "libssh");
-
-    // configure misc build options
-    enable!(args, "BUILD_PIC", "pic");
+    // AMF is loaded at runtime, only its headers are needed
+    if env::var("CARGO_FEATURE_BUILD_AMF").is_ok() {
+        args.push("--enable-amf".into());
+        args.push(format!("--extra-cflags=-I{}", amf_include()?.to_string_lossy()));
+    }
+
+    let nvidia = ["build-nvenc", "build-nvdec", "build-cuvid"]
+        .iter()
+        .any(|feature| feature_enabled(feature));
+    if nvidia {
+        args.push("--enable-ffnvcodec".into());
+
+        if pkg_config::Config::new()
+            .cargo_metadata(false)
+            .probe("ffnvcodec")
+            .is_err()
+        {
+            fetch_nv_codec_headers()?;
+        }
+    }
+
+    if env::var("CARGO_FEATURE_AVFORMAT").is_ok()
+        && env::var("CARGO_FEATURE_BUILD_LIB_XML2").is_err()
+    {
+        println!(
+            "cargo:warning=the DASH demuxer needs libxml2, enable the `build-lib-xml2` feature"
+        );
+    }
+
+    check_build_libs()?;
+
+    // FFmpeg embeds the configure arguments, they shouldn't depend on the order of the features
+    args.sort();
+    args.dedup();
+
+    // kept for tests/configure.rs, which checks every flag against `./configure --help`
+    fs::write(output().join("configure-args"), args.join("\n"))?;
 
     let mut configure = if env::var("TARGET").unwrap().contains("windows") {
         let mut arg = String::from("./configure ");
@@ -272,54 +940,381 @@ fn build() -> io::Result<()> {
         configure.args(args);
         configure
     };
-    configure.current_dir(&source());
+    configure.current_dir(source());
 
-    // run ./configure
+    // pick up libraries installed into the prefix, like nv-codec-headers
+    let mut pkg_config_paths = vec![search().join("lib").join("pkgconfig")];
+    if let Some(paths) = env::var_os("PKG_CONFIG_PATH") {
+        pkg_config_paths.extend(env::split_paths(&paths));
+    }
+    configure.env("PKG_CONFIG_PATH", env::join_paths(pkg_config_paths).unwrap());
+
+    // the compilers take __DATE__ and __TIME__ from it
+    let source_date_epoch = env::var_os("SOURCE_DATE_EPOCH");
+    if let Some(ref epoch) = source_date_epoch {
+        configure.env("SOURCE_DATE_EPOCH", epoch);
+    }
+
+    // run ./configure
     let output = configure
         .output()
-        .expect(&format!("{:?} failed", configure));
+        .unwrap_or_else(|_| panic!("{:?} failed", configure));
     if !output.status.success() {
         println!("configure: {}", String::from_utf8_lossy(&output.stdout));
 
-        return Err(io::Error::new(
-            io::ErrorKind::Other,
-            format!(
-                "configure failed {}",
-                String::from_utf8_lossy(&output.stderr)
-            ),
-        ));
+        return Err(io::Error::other(format!(
+            "configure failed {}",
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+
+    // the configuration string avcodec_configuration() returns repeats the arguments, whose
+    // --prefix and prefix maps are under OUT_DIR
+    let out_dir = self::output().to_string_lossy().into_owned();
+    let original = fs::read_to_string(source().join("config.h"))?;
+    let config_h: Vec<String> = original
+        .lines()
+        .map(|line| {
+            if line.starts_with("#define FFMPEG_CONFIGURATION ") {
+                line.replace(&out_dir, ".")
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    let config_h = config_h.join("\n") + "\n";
+    // a new timestamp on config.h makes make rebuild everything
+    if config_h != original {
+        fs::write(source().join("config.h"), config_h)?;
     }
 
     // run make
-    if !try!(
-        Command::new("make")
-            .arg("-j")
-            .arg(num_cpus::get().to_string())
-            .current_dir(&source())
-            .status()
-    ).success()
-    {
-        return Err(io::Error::new(io::ErrorKind::Other, "make failed"));
+    let mut make = make_command()?;
+    make.arg("-j")
+        .arg(num_cpus::get().to_string())
+        .current_dir(source());
+    if let Some(ref epoch) = source_date_epoch {
+        make.env("SOURCE_DATE_EPOCH", epoch);
+    }
+    if !make.status()?.success() {
+        return Err(io::Error::other("make failed"));
     }
 
     // run make install
-    if !try!(
-        Command::new("make")
-            .current_dir(&source())
-            .arg("install")
-            .status()
-    ).success()
-    {
-        return Err(io::Error::new(io::ErrorKind::Other, "make install failed"));
+    let mut make_install = make_command()?;
+    make_install.current_dir(source()).arg("install");
+    if let Some(ref epoch) = source_date_epoch {
+        make_install.env("SOURCE_DATE_EPOCH", epoch);
+    }
+    if !make_install.status()?.success() {
+        return Err(io::Error::other("make install failed"));
+    }
+
+    // a prefix MSYS maps somewhere else succeeds to install into a directory nothing links from
+    if libdir::find_lib_dir(&search(), &ffmpeg_libs()).is_none() {
+        let missing: Vec<_> = ffmpeg_libs()
+            .into_iter()
+            .filter(|&lib| libdir::find_lib_dir(&search(), &[lib]).is_none())
+            .map(|lib| format!("lib{}.a", lib))
+            .collect();
+        return Err(io::Error::other(format!(
+            "make install with --prefix={} didn't install {} into {}",
+            prefix,
+            missing.join(", "),
+            libdir::LIB_DIRS.join(" or ")
+        )));
     }
 
     Ok(())
 }
 
+// written once `make install` finished, a dist without it is the remains of an interrupted build
+fn features_hash_path() -> PathBuf {
+    search().join(".ffmpeg-features")
+}
+
+// the cargo features and the environment variables the dist was configured with. The digest is
+// written to disk, unlike the one of DefaultHasher it stays the same across Rust releases
+fn features_hash() -> String {
+    let mut features: Vec<_> = env::vars()
+        .filter(|(key, _)| {
+            key.starts_with("CARGO_FEATURE_")
+                || [
+                    "FFMPEG_CPU",
+                    "FFMPEG_DISABLE_RUNTIME_CPUDETECT",
+                    "FFMPEG_LTO",
+                    "FFMPEG_SANITIZER",
+                    "FFMPEG_FRAME_POINTERS",
+                    "SOURCE_DATE_EPOCH",
+                ]
+                .contains(&key.as_str())
+        })
+        .collect();
+    features.sort();
+
+    // environment variables can't contain NUL, which keeps the pairs apart
+    let mut data = String::new();
+    for (key, value) in &features {
+        data.push_str(&format!("{}={}\0", key, value));
+    }
+    sha256::sha256_hex(data.as_bytes())
+}
+
+// whether the source tree is a checkout of the requested release branch, a tree without a
+// recorded identity or from another branch is fetched again
+fn source_up_to_date() -> bool {
+    source().join("configure").exists()
+        && fs::read_to_string(source_id_path()).is_ok_and(|id| {
+            let source = id.trim().rsplit_once(' ').map(|(source, _)| source);
+            source == Some(requested_source().as_str())
+        })
+}
+
+// whether config.mak was generated with the arguments build() last ran configure with
+fn config_mak_matches() -> bool {
+    match (
+        fs::read_to_string(source().join("ffbuild/config.mak")),
+        fs::read_to_string(output().join("configure-args")),
+    ) {
+        (Ok(config_mak), Ok(args)) => config_mak::configured_with(&config_mak, &args),
+        _ => false,
+    }
+}
+
+// whether dist holds a finished build of the requested sources with the current features
+fn dist_complete() -> bool {
+    let up_to_date = source_up_to_date();
+    let configured = fs::read_to_string(features_hash_path())
+        .is_ok_and(|hash| hash.trim() == features_hash())
+        && config_mak_matches();
+    let installed = libdir::find_lib_dir(&search(), &ffmpeg_libs()).is_some();
+
+    up_to_date && configured && installed
+}
+
+// fetches and builds FFmpeg into dist unless it's already there, returns the source identity.
+// Other features only run configure again and leave the rest to make, only another release
+// branch or FFMPEG_FORCE_CLEAN=1 start over from a fresh clone
+fn build_dist() -> String {
+    let clean = env::var("FFMPEG_FORCE_CLEAN").is_ok_and(|value| value == "1");
+    let force = env::var("FFMPEG_FORCE_REBUILD").is_ok_and(|value| value == "1");
+    if clean || !source_up_to_date() {
+        // fetch clones the sources again, which also drops the build state of an earlier attempt
+        if search().exists() {
+            fs::remove_dir_all(search()).expect("failed to remove the outdated dist");
+        }
+        fs::create_dir_all(output()).expect("failed to create build directory");
+        fetch().unwrap();
+    }
+
+    if clean || force || !dist_complete() {
+        // an interrupted build must not pass for one of the previous features
+        if features_hash_path().exists() {
+            fs::remove_file(features_hash_path()).expect("failed to remove the features hash");
+        }
+        // the Display of the error, check_build_libs puts each missing library on its own line
+        if let Err(e) = build() {
+            panic!("{}", e);
+        }
+        if !config_mak_matches() {
+            panic!(
+                "{} doesn't reflect the arguments in {}, run the build again with \
+                 FFMPEG_FORCE_CLEAN=1",
+                source().join("ffbuild/config.mak").to_string_lossy(),
+                output().join("configure-args").to_string_lossy()
+            );
+        }
+        fs::write(features_hash_path(), features_hash()).expect("failed to record the features");
+    }
+
+    let id = fs::read_to_string(source_id_path()).expect("failed to read the FFmpeg source id");
+    println!("cargo:ffmpeg_source_id={}", id.trim());
+    id.trim().to_string()
+}
+
+// the linker flag that drops unreferenced sections
+fn gc_sections_link_arg() -> &'static str {
+    let target = env::var("TARGET").unwrap();
+    if target.contains("msvc") {
+        "/OPT:REF"
+    } else if target.contains("apple") {
+        "-Wl,-dead_strip"
+    } else {
+        "-Wl,--gc-sections"
+    }
+}
+
+// the AV_CH_* masks src/avutil/channel_layout.rs defines in place of the ones of the headers
+fn channel_masks() -> Vec<&'static str> {
+    include_str!("src/avutil/channel_layout.rs")
+        .lines()
+        .filter_map(|line| line.strip_prefix("pub const "))
+        .filter_map(|line| line.split(':').next())
+        .filter(|name| name.starts_with("AV_CH_"))
+        .collect()
+}
+
+// the `ffprobe` feature without `build`: the pinned static ffprobe of the host is downloaded to
+// OUT_DIR/bin instead of configuring FFmpeg for it
+fn download_ffprobe() {
+    let host = env::var("HOST").unwrap();
+    let binary = ffprobe_binary::binary_for(&host).unwrap_or_else(|| {
+        panic!(
+            "there is no official static ffprobe binary for {}, enable the `build` feature to \
+             build ffprobe from source instead",
+            host
+        )
+    });
+    let pinned = ffprobe_binary::pinned_sha256(ffprobe_binary::PINNED, binary.archive());
+    let sha256 = pinned.unwrap_or_else(|| {
+        panic!(
+            "no checksum is pinned for {} in build/ffprobe.sha256, run `cargo xtask pin-ffprobe` \
+             or enable the `build` feature",
+            binary.archive()
+        )
+    });
+
+    let bin_dir = output().join("bin");
+    let ffprobe = bin_dir.join(if host.contains("windows") { "ffprobe.exe" } else { "ffprobe" });
+    // the checksum of the archive the binary came from, downloading again only when it changes
+    let stamp = bin_dir.join(".ffprobe-sha256");
+    if !ffprobe.exists() || fs::read_to_string(&stamp).ok().as_deref() != Some(sha256) {
+        if let Err(e) = fetch_ffprobe(binary, sha256, &ffprobe) {
+            panic!(
+                "failed to download ffprobe from {}: {}\n\
+                 it needs curl and tar, or enable the `build` feature to build it from source",
+                binary.url, e
+            );
+        }
+        fs::write(&stamp, sha256).expect("Couldn't write the ffprobe checksum");
+    }
+
+    println!("cargo:ffprobe_bin={}", ffprobe.to_string_lossy());
+    report("ffprobe", json::string(&ffprobe.to_string_lossy()));
+    fs::write(
+        output().join("ffprobe.rs"),
+        format!(
+            "/// The pinned static ffprobe the build script downloaded for the host.\n\
+             pub const FFPROBE_PATH: &str = {:?};\n",
+            ffprobe.to_string_lossy()
+        ),
+    )
+    .expect("Couldn't write ffprobe.rs");
+}
+
+fn fetch_ffprobe(binary: &ffprobe_binary::Binary, sha256: &str, ffprobe: &Path) -> io::Result<()> {
+    println!("Download ffprobe from {}", binary.url);
+
+    let download = output().join("ffprobe-download");
+    if download.exists() {
+        fs::remove_dir_all(&download)?;
+    }
+    fs::create_dir_all(&download)?;
+
+    let archive = download.join(binary.archive());
+    let status = Command::new("curl")
+        .args(["--fail", "--silent", "--show-error", "--location", "--output"])
+        .arg(&archive)
+        .arg(binary.url)
+        .status()?;
+    if !status.success() {
+        return Err(io::Error::other("curl failed"));
+    }
+
+    let actual = sha256::sha256_hex(&fs::read(&archive)?);
+    if actual != sha256 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("the archive has the sha256 {}, {} is pinned", actual, sha256),
+        ));
+    }
+
+    // GNU tar extracts the .tar.xz of Linux, the bsdtar of macOS and Windows the .zip
+    let status = Command::new("tar")
+        .arg("-xf")
+        .arg(&archive)
+        .arg("-C")
+        .arg(&download)
+        .arg(binary.member)
+        .status()?;
+    if !status.success() {
+        return Err(io::Error::other("extracting the archive failed"));
+    }
+
+    fs::create_dir_all(ffprobe.parent().unwrap())?;
+    // keeps the executable bit on unix
+    fs::copy(download.join(binary.member), ffprobe)?;
+    fs::remove_dir_all(&download)
+}
+
+// exports the paths of the programs of the enabled features, which stay in the dist they were
+// installed into
+fn report_programs() {
+    // configure silently skips programs whose dependencies are missing
+    let config_mak = fs::read_to_string(source().join("ffbuild/config.mak")).unwrap();
+
+    let binaries = vec![
+        ("ffmpeg", "FFMPEG"),
+        ("ffplay", "FFPLAY"),
+        ("ffprobe", "FFPROBE"),
+    ];
+    let mut built = false;
+    for (name, feature) in binaries {
+        if env::var(format!("CARGO_FEATURE_{}", feature)).is_ok() {
+            if !config_mak.lines().any(|line| line == format!("CONFIG_{}=yes", feature)) {
+                panic!(
+                    "configure did not enable {}, see {} for details",
+                    name,
+                    source().join("ffbuild/config.log").to_string_lossy()
+                );
+            }
+
+            let bin = if env::var("TARGET").unwrap().contains("windows") {
+                PathBuf::from(name).with_extension("exe")
+            } else {
+                PathBuf::from(name)
+            };
+            println!("cargo:{}_bin={}", name, search().join("bin").join(&bin).to_string_lossy());
+            built = true;
+        }
+    }
+
+    if built {
+        report_datadir(&search());
+    }
+}
+
+// the arguments configure was run with, written by build()
+fn report_configure_args() {
+    let args = fs::read_to_string(output().join("configure-args")).unwrap_or_default();
+    report("configure_args", json::array(&args.lines().collect::<Vec<_>>()));
+}
+
+// the datadir of an installed FFmpeg, the programs of the prefix find their presets there
+fn report_datadir(prefix: &Path) {
+    println!("cargo:ffmpeg_prefix={}", prefix.to_string_lossy());
+
+    let datadir = prefix.join("share/ffmpeg");
+    if datadir.is_dir() {
+        println!("cargo:ffmpeg_datadir={}", datadir.to_string_lossy());
+    }
+}
+
+fn check_value(stdout: &str, key: &str) -> u64 {
+    check_output::number(stdout, key)
+        .unwrap_or_else(|| panic!("{} not found in the output of check.c", key))
+}
+
+fn format_version(version: u64) -> String {
+    format!("{}.{}.{}", version >> 16, (version >> 8) & 0xff, version & 0xff)
+}
+
 fn check_features(
     include_paths: Vec<PathBuf>,
+    link_paths: Option<Vec<PathBuf>>,
     infos: &Vec<(&'static str, Option<&'static str>, &'static str)>,
-) {
+    lines: &mut Vec<String>,
+) -> (Vec<(&'static str, bool, bool)>, Option<String>) {
     let mut includes_code = String::new();
     let mut main_code = String::new();
 
@@ -333,7 +1328,7 @@ fn check_features(
         let include = format!("#include <{}>", header);
         if includes_code.find(&include).is_none() {
             includes_code.push_str(&include);
-            includes_code.push_str(&"\n");
+            includes_code.push('\n');
         }
         includes_code.push_str(&format!(
             r#"
@@ -353,10 +1348,34 @@ fn check_features(
         ));
     }
 
-    let version_check_info = [("avcodec", 56, 60, 0, 80)];
-    for &(lib, begin_version_major, end_version_major, begin_version_minor, end_version_minor) in
-        version_check_info.iter()
+    let version_check_info = [
+        ("libavcodec/avcodec.h", Some("avcodec"), "avcodec", 56, 60, 0, 80),
+        ("libavutil/avutil.h", None, "avutil", 56, 60, 0, 80),
+        ("libavfilter/avfilter.h", Some("avfilter"), "avfilter", 6, 11, 0, 120),
+        ("libavformat/avformat.h", Some("avformat"), "avformat", 56, 61, 0, 100),
+        ("libpostproc/postprocess.h", Some("postproc"), "postproc", 54, 59, 0, 20),
+        ("libswresample/swresample.h", Some("swresample"), "swresample", 2, 6, 0, 20),
+        ("libswscale/swscale.h", Some("swscale"), "swscale", 4, 9, 0, 20),
+    ];
+    for &(
+        header,
+        feature,
+        lib,
+        begin_version_major,
+        end_version_major,
+        begin_version_minor,
+        end_version_minor,
+    ) in version_check_info.iter()
     {
+        if !feature.is_none_or(feature_enabled) {
+            continue;
+        }
+
+        let include = format!("#include <{}>", header);
+        if includes_code.find(&include).is_none() {
+            includes_code.push_str(&include);
+            includes_code.push('\n');
+        }
         for version_major in begin_version_major..end_version_major {
             for version_minor in begin_version_minor..end_version_minor {
                 main_code.push_str(&format!(
@@ -370,11 +1389,95 @@ fn check_features(
         }
     }
 
+    // the layout of a few key structs as the headers describe it, printed when the linked
+    // libraries don't match the headers
+    let layout_check_info = [
+        ("libavutil/frame.h", None, "AVFrame", &["data", "format", "pts"][..]),
+        (
+            "libavcodec/avcodec.h",
+            Some("avcodec"),
+            "AVCodecContext",
+            &["codec_id", "width", "pix_fmt"][..],
+        ),
+        (
+            "libavformat/avformat.h",
+            Some("avformat"),
+            "AVFormatContext",
+            &["iformat", "nb_streams", "streams"][..],
+        ),
+        // AVSubtitleRect.pict is compiled in with FF_API_AVPICTURE
+        (
+            "libavcodec/avcodec.h",
+            Some("avcodec"),
+            "AVSubtitleRect",
+            &["nb_colors", "data", "type", "text", "ass", "flags"][..],
+        ),
+    ];
+    for &(header, feature, name, fields) in layout_check_info.iter() {
+        if !feature.is_none_or(feature_enabled) {
+            continue;
+        }
+
+        let include = format!("#include <{}>", header);
+        if includes_code.find(&include).is_none() {
+            includes_code.push_str(&include);
+            includes_code.push('\n');
+        }
+        main_code.push_str(&format!(
+            r#"printf("[sizeof_{name}]%lu\n", (unsigned long) sizeof({name}));"#,
+            name = name
+        ));
+        for field in fields {
+            main_code.push_str(&format!(
+                r#"printf("[offsetof_{name}_{field}]%lu\n", (unsigned long) offsetof({name}, {field}));"#,
+                name = name,
+                field = field
+            ));
+        }
+    }
+
+    // the version the headers were written for and, when check.c is linked, the version of
+    // the library it actually loads
+    let linked_version_info = [
+        ("libavutil/avutil.h", None, "avutil"),
+        ("libavcodec/avcodec.h", Some("avcodec"), "avcodec"),
+        ("libavformat/avformat.h", Some("avformat"), "avformat"),
+        ("libpostproc/postprocess.h", Some("postproc"), "postproc"),
+    ];
+    for &(header, feature, lib) in linked_version_info.iter() {
+        if !feature.is_none_or(feature_enabled) {
+            continue;
+        }
+
+        let include = format!("#include <{}>", header);
+        if includes_code.find(&include).is_none() {
+            includes_code.push_str(&include);
+            includes_code.push('\n');
+        }
+        main_code.push_str(&format!(
+            r#"printf("[{lib}_header_version]%u\n", (unsigned) LIB{lib_uppercase}_VERSION_INT);"#,
+            lib = lib,
+            lib_uppercase = lib.to_uppercase()
+        ));
+        if link_paths.is_some() {
+            main_code.push_str(&format!(
+                r#"printf("[{lib}_linked_version]%u\n", {lib}_version());"#,
+                lib = lib
+            ));
+        }
+    }
+
+    // the license configure was run with, every library of an installation has the same
+    if link_paths.is_some() {
+        main_code.push_str(r#"printf("[license]%s\n", avutil_license());"#);
+    }
+
     let out_dir = output();
 
     write!(
         File::create(out_dir.join("check.c")).expect("Failed to create file"),
         r#"
+            #include <stddef.h>
             #include <stdio.h>
             {includes_code}
 
@@ -391,15 +1494,29 @@ fn check_features(
     let executable = out_dir.join(if cfg!(windows) { "check.exe" } else { "check" });
     let mut compiler = cc::Build::new().get_compiler().to_command();
 
-    for dir in include_paths {
+    for dir in &include_paths {
         compiler.arg("-I");
         compiler.arg(dir.to_string_lossy().into_owned());
     }
-    if !compiler
+    // the check has to run under the same sanitizer runtime as the libraries
+    compiler.args(sanitizer_cflags());
+    compiler
         .current_dir(&out_dir)
         .arg("-o")
         .arg(&executable)
-        .arg("check.c")
+        .arg("check.c");
+    if let Some(ref link_paths) = link_paths {
+        for dir in link_paths {
+            compiler.arg(format!("-L{}", dir.to_string_lossy()));
+            compiler.arg(format!("-Wl,-rpath,{}", dir.to_string_lossy()));
+        }
+        for &(_, feature, lib) in linked_version_info.iter().rev() {
+            if feature.is_none_or(feature_enabled) {
+                compiler.arg(format!("-l{}", lib));
+            }
+        }
+    }
+    if !compiler
         .status()
         .expect("Command failed")
         .success()
@@ -414,17 +1531,11 @@ fn check_features(
         .stdout;
     let stdout = str::from_utf8(stdout_raw.as_slice()).unwrap();
 
-    //let mut f = File::open("check.txt").expect("check.txt file not found!");
-
-    /*let mut stdout = String::new();
-    f.read_to_string(&mut stdout)
-        .expect("something went wrong reading the file");*/
+    lines.push(format!("stdout={}", stdout));
 
-    //let stdout = contents.unwrap();
-
-    //println!("use check.txt test...");
-
-    println!("stdout={}", stdout);
+    // the value of each macro and whether it's defined at all, some macros are checked in more
+    // than one library
+    let mut ff_api: Vec<(&'static str, bool, bool)> = Vec::new();
 
     for &(_, feature, var) in infos {
         if let Some(feature) = feature {
@@ -433,274 +1544,1599 @@ fn check_features(
             }
         }
 
-        let var_str = format!("[{var}]", var = var);
-        let pos = stdout.find(&var_str).expect("H-Variable not found in output") + var_str.len();
-        if &stdout[pos..pos + 1] == "1" {
-            println!(r#"cargo:rustc-cfg=feature="{}""#, var.to_lowercase());
-            println!(r#"cargo:{}=true"#, var.to_lowercase());
+        let (enabled, defined) = check_output::ff_api_macro(stdout, var)
+            .unwrap_or_else(|| panic!("{} not found in the output of check.c", var));
+        if enabled {
+            lines.push(format!(r#"cargo:rustc-cfg=feature="{}""#, var.to_lowercase()));
+            lines.push(format!(r#"cargo:{}=true"#, var.to_lowercase()));
+        }
+        if let Some(entry) = ff_api.iter_mut().find(|entry| entry.0 == var) {
+            entry.1 |= enabled;
+            entry.2 |= defined;
+        } else {
+            ff_api.push((var, enabled, defined));
         }
 
         // Also find out if defined or not (useful for cases where only the definition of a macro
         // can be used as distinction)
-        if &stdout[pos + 1..pos + 2] == "1" {
-            println!(
+        if defined {
+            lines.push(format!(
                 r#"cargo:rustc-cfg=feature="{}_is_defined""#,
                 var.to_lowercase()
-            );
-            println!(r#"cargo:{}_is_defined=true"#, var.to_lowercase());
+            ));
+            lines.push(format!(r#"cargo:{}_is_defined=true"#, var.to_lowercase()));
+        }
+    }
+
+    let mut ff_api_code = String::new();
+    for &(name, value, _) in &ff_api {
+        ff_api_code.push_str(&format!("pub const {}: bool = {};\n", name, value));
+    }
+    fs::write(out_dir.join("ff_api.rs"), ff_api_code).expect("Couldn't write ff_api.rs");
+
+    // the sizes the C compiler sees, which the structs bindgen generated have to agree with.
+    // check.c runs on the build machine, its sizes only hold for the same target
+    let mut layout_code = String::new();
+    if env::var("TARGET") == env::var("HOST") {
+        for &(_, feature, name, _) in layout_check_info.iter() {
+            if !feature.is_none_or(feature_enabled) {
+                continue;
+            }
+
+            layout_code.push_str(&format!(
+                "const _: () = assert!(\n    \
+                 ::core::mem::size_of::<{name}>() == {size},\n    \
+                 \"the size of {name} differs from the one of the C headers\"\n);\n",
+                name = name,
+                size = check_value(stdout, &format!("sizeof_{}", name))
+            ));
         }
     }
+    fs::write(out_dir.join("layout_checks.rs"), layout_code)
+        .expect("Couldn't write layout_checks.rs");
 
-    for &(lib, begin_version_major, end_version_major, begin_version_minor, end_version_minor) in
-        version_check_info.iter()
+    for &(
+        _,
+        feature,
+        lib,
+        begin_version_major,
+        end_version_major,
+        begin_version_minor,
+        end_version_minor,
+    ) in version_check_info.iter()
     {
         for version_major in begin_version_major..end_version_major {
             for version_minor in begin_version_minor..end_version_minor {
-                let search_str = format!(
-                    "[{lib}_version_greater_than_{version_major}_{version_minor}]",
+                let key = format!(
+                    "{lib}_version_greater_than_{version_major}_{version_minor}",
                     version_major = version_major,
                     version_minor = version_minor,
                     lib = lib
                 );
-                let pos = stdout
-                    .find(&search_str)
-                    .expect("Variable not found in output")
-                    + search_str.len();
-
-                if &stdout[pos..pos + 1] == "1" {
-                    println!(
-                        r#"cargo:rustc-cfg=feature="{}""#,
-                        &search_str[1..(search_str.len() - 1)]
-                    );
+                lines.push(format!(r#"cargo:rustc-check-cfg=cfg(feature, values("{}"))"#, key));
+
+                // the library isn't enabled, its version isn't in the output
+                if !feature.is_none_or(feature_enabled) {
+                    continue;
                 }
-            }
-        }
-    }
-}
 
-fn search_include(include_paths: &Vec<PathBuf>, header: &str) -> String {
-    for dir in include_paths {
-        let include = dir.join(header);
-        if fs::metadata(&include).is_ok() {
-            return format!("{}", include.as_path().to_str().unwrap());
+                let greater = check_output::flag(stdout, &key)
+                    .unwrap_or_else(|| panic!("{} not found in the output of check.c", key));
+                if greater {
+                    lines.push(format!(r#"cargo:rustc-cfg=feature="{}""#, key));
+                }
+            }
         }
     }
-    format!("/usr/include/{}", header)
-}
-
-fn main() {
-    let statik = env::var("CARGO_FEATURE_STATIC").is_ok();
 
-    let include_paths: Vec<PathBuf> = if env::var("CARGO_FEATURE_BUILD").is_ok() {
-        println!(
-            "cargo:rustc-link-search=native={}",
-            search().join("lib").to_string_lossy()
-        );
-        println!("FFMPEG-SYS get build...");
-        let ffmpeg_ty = if statik { "static" } else { "dylib" };
-
-        // Make sure to link with the ffmpeg libs we built
-        println!("cargo:rustc-link-lib={}=avutil", ffmpeg_ty);
-        if env::var("CARGO_FEATURE_AVCODEC").is_ok() {
-            println!("cargo:rustc-link-lib={}=avcodec", ffmpeg_ty);
-        }
-        if env::var("CARGO_FEATURE_AVFORMAT").is_ok() {
-            println!("cargo:rustc-link-lib={}=avformat", ffmpeg_ty);
-        }
-        if env::var("CARGO_FEATURE_AVFILTER").is_ok() {
-            println!("cargo:rustc-link-lib={}=avfilter", ffmpeg_ty);
-        }
-        if env::var("CARGO_FEATURE_AVDEVICE").is_ok() {
-            println!("cargo:rustc-link-lib={}=avdevice", ffmpeg_ty);
-        }
-        if env::var("CARGO_FEATURE_AVRESAMPLE").is_ok() {
-            println!("cargo:rustc-link-lib={}=avresample", ffmpeg_ty);
-        }
-        if env::var("CARGO_FEATURE_SWSCALE").is_ok() {
-            println!("cargo:rustc-link-lib={}=swscale", ffmpeg_ty);
-        }
-        if env::var("CARGO_FEATURE_SWRESAMPLE").is_ok() {
-            println!("cargo:rustc-link-lib={}=swresample", ffmpeg_ty);
-        }
+    let license = if link_paths.is_some() {
+        check_output::value(stdout, "license").map(|license| license.to_owned())
+    } else {
+        None
+    };
 
-        if env::var("CARGO_FEATURE_BUILD_ZLIB").is_ok() && cfg!(target_os = "linux") {
-            println!("cargo:rustc-link-lib=z");
-        }
+    if let Some(link_paths) = link_paths {
+        for &(_, feature, lib) in linked_version_info.iter() {
+            if !feature.is_none_or(feature_enabled) {
+                continue;
+            }
 
-        if fs::metadata(&search().join("lib").join("libavutil.a")).is_err() {
-            fs::create_dir_all(&output())
-                .ok()
-                .expect("failed to create build directory");
-            fetch().unwrap();
-            build().unwrap();
-        }
+            let header_version = check_value(stdout, &format!("{}_header_version", lib));
+            let linked_version = check_value(stdout, &format!("{}_linked_version", lib));
 
-        // Check additional required libraries.
-        {
-            let config_mak = source().join("ffbuild/config.mak");
-            let file = File::open(config_mak).unwrap();
-            let reader = BufReader::new(file);
+            // newer minor versions of a library keep the ABI of older ones, anything else
+            // doesn't match the bindings
+            if header_version >> 16 == linked_version >> 16
+                && linked_version >> 8 >= header_version >> 8
+            {
+                continue;
+            }
 
-            let mut include_libs = Vec::new();
-            for line in reader.lines() {
-                if !line.as_ref().unwrap().starts_with("EXTRALIBS") {
+            let mut layout = String::new();
+            for &(_, feature, name, fields) in layout_check_info.iter() {
+                if !feature.is_none_or(feature_enabled) {
                     continue;
                 }
-                let line = line.unwrap();
-                let mut split = line.splitn(2, '=');
-                let lib = split.next().unwrap().split('-').last().unwrap();
-                let linker_args = split.next().unwrap();
-
-                // the key EXTRALIBS on its own should be linked unconditionally
-                // avutil is always built
-                if lib != "EXTRALIBS" && lib != "avutil" {
-                    // check feature flag if we need to link these libs
-                    let feature = format!("CARGO_FEATURE_{}", lib.to_uppercase());
-                    if env::var(feature).is_err() {
-                        continue;
-                    }
-                }
 
-                let libs: Vec<_> = if env::var("TARGET").unwrap().contains("windows") {
-                    linker_args
-                        .split(' ')
-                        .filter(|v| v.ends_with(".lib"))
-                        .map(|lib| &lib[..lib.len() - 4])
-                        .map(|lib| lib.to_owned())
-                        .collect()
-                } else {
-                    linker_args
-                        .split(' ')
-                        .filter(|v| v.starts_with("-l"))
-                        .map(|flag| &flag[2..])
-                        .map(|lib| lib.to_owned())
-                        .collect()
-                };
-                for lib in libs {
-                    if !include_libs.contains(&lib) {
-                        include_libs.push(lib);
-                    }
+                layout.push_str(&format!(
+                    "\n    sizeof({}) = {}",
+                    name,
+                    check_value(stdout, &format!("sizeof_{}", name))
+                ));
+                for field in fields {
+                    layout.push_str(&format!(
+                        "\n    offsetof({}, {}) = {}",
+                        name,
+                        field,
+                        check_value(stdout, &format!("offsetof_{}_{}", name, field))
+                    ));
                 }
             }
 
-            for lib in include_libs {
-                println!("cargo:rustc-link-lib={}", lib);
-            }
+            let dirs = |paths: &Vec<PathBuf>| {
+                paths
+                    .iter()
+                    .map(|path| path.to_string_lossy().into_owned())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            panic!(
+                "the lib{lib} headers are version {header_version} (from {include_paths}) but the \
+                 linked library is version {linked_version} (from {link_paths}), the headers and \
+                 the libraries belong to two different FFmpeg installations. The headers describe \
+                 this layout:{layout}",
+                lib = lib,
+                header_version = format_version(header_version),
+                include_paths = dirs(&include_paths),
+                linked_version = format_version(linked_version),
+                link_paths = if link_paths.is_empty() {
+                    "the default library path".to_owned()
+                } else {
+                    dirs(&link_paths)
+                },
+                layout = layout
+            );
         }
+    }
 
-        // copy binaries to output
-        {
-            let binaries = vec![
-                ("ffmpeg", "FFMPEG"),
-                ("ffplay", "FFPLAY"),
-                ("ffprobe", "FFPROBE"),
-            ];
-            for (bin, feature) in binaries {
-                if env::var(format!("CARGO_FEATURE_{}", feature)).is_ok() {
-                    let bin = if env::var("TARGET").unwrap().contains("windows") {
-                        PathBuf::from(bin).with_extension("exe")
-                    } else {
-                        PathBuf::from(bin)
-                    };
-                    let bin_path = search().join("bin").join(&bin);
-                    let out_path = output()
-                        .parent()
-                        .unwrap()
-                        .parent()
-                        .unwrap()
-                        .parent()
-                        .unwrap()
-                        .join(&bin);
-                    if out_path.exists() {
-                        fs::remove_file(&out_path)
-                            .expect(&format!("failed to remove {}", out_path.to_string_lossy()));
-                    }
-                    fs::copy(&bin_path, &out_path).expect(&format!(
-                        "failed to copy {} to {}",
-                        bin_path.to_string_lossy(),
-                        out_path.to_string_lossy()
-                    ));
-                }
-            }
-        }
+    (ff_api, license)
+}
 
-        vec![search().join("include")]
+// the license class of the FFmpeg the bindings are for and where it's from, taken from the most
+// reliable source there is
+fn detect_license(linked_license: Option<String>) -> Option<(&'static str, String)> {
+    if feature_enabled("build") {
+        let config_mak = fs::read_to_string(source().join("ffbuild/config.mak")).unwrap();
+        return Some((license::from_config_mak(&config_mak), "ffbuild/config.mak".to_owned()));
     }
-    // Use prebuilt library
-    else if let Ok(ffmpeg_dir) = env::var("FFMPEG_DIR") {
-        let ffmpeg_dir = PathBuf::from(ffmpeg_dir);
 
-        println!(
-            "cargo:rustc-link-search=native={}",
-            ffmpeg_dir.join("lib").to_string_lossy()
-        );
+    if let Some(license) = linked_license {
+        let source = format!("avutil_license(), \"{}\"", license);
+        return Some((license::from_license(&license), source));
+    }
 
-        vec![ffmpeg_dir.join("include")]
+    // the configuration line of the programs of the installation, they only run on the host
+    let prefix = match (env::var("FFMPEG_DIR"), env::var_os("FFMPEG_PKG_CONFIG_PATH")) {
+        (Ok(ffmpeg_dir), None) => Some(PathBuf::from(ffmpeg_dir)),
+        _ => pkg_config::get_variable("libavutil", "prefix")
+            .ok()
+            .map(PathBuf::from),
+    };
+    if let Some(prefix) = prefix.filter(|_| env::var("HOST") == env::var("TARGET")) {
+        let ffmpeg = prefix.join("bin").join(if cfg!(windows) { "ffmpeg.exe" } else { "ffmpeg" });
+        if let Ok(output) = Command::new(&ffmpeg).arg("-version").output() {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            if let Some(configuration) = stdout
+                .lines()
+                .find(|line| line.starts_with("configuration:"))
+            {
+                let class = license::from_configuration(configuration);
+                return Some((class, format!("{} -version", ffmpeg.to_string_lossy())));
+            }
+        }
     }
-    // Fallback to pkg-config
-    else {
-        println!("fallback to pkg-config");
 
-        pkg_config::Config::new()
-            .statik(statik)
-            .probe("libavutil")
-            .unwrap()
-            .include_paths;
-
-        let libs = vec![
-            ("libavformat", "AVFORMAT"),
-            ("libavfilter", "AVFILTER"),
-            ("libavdevice", "AVDEVICE"),
-            ("libavresample", "AVRESAMPLE"),
-            ("libswscale", "SWSCALE"),
-            ("libswresample", "SWRESAMPLE"),
-        ];
+    let mut libs = Vec::new();
+    for package in &["libavutil", "libavcodec", "libavfilter", "libpostproc"] {
+        if let Ok(probed) = pkg_config::Config::new()
+            .statik(true)
+            .cargo_metadata(false)
+            .probe(package)
+        {
+            libs.extend(probed.libs);
+        }
+    }
+    if libs.is_empty() {
+        return None;
+    }
+    Some((
+        license::from_private_libs(&libs),
+        "the private libraries of the pkg-config files".to_owned(),
+    ))
+}
 
-        for (lib_name, env_variable_name) in libs.iter() {
-            if env::var(format!("CARGO_FEATURE_{}", env_variable_name)).is_ok() {
-                pkg_config::Config::new()
-                    .statik(statik)
-                    .probe(lib_name)
-                    .unwrap()
-                    .include_paths;
-            }
-        };
+// reports the license class of FFmpeg and enforces FFMPEG_REQUIRE_LICENSE
+fn check_license(linked_license: Option<String>) {
+    let detected = detect_license(linked_license);
+    if let Some((class, ref source)) = detected {
+        println!("FFmpeg license class: {} (from {})", class, source);
+        println!("cargo:ffmpeg_license={}", class);
+        report(
+            "license",
+            format!(
+                "{{\"class\": {}, \"source\": {}}}",
+                json::string(class),
+                json::string(source)
+            ),
+        );
+    }
 
-        pkg_config::Config::new()
-            .statik(statik)
-            .probe("libavcodec")
-            .unwrap()
-            .include_paths
+    let required = match env::var("FFMPEG_REQUIRE_LICENSE") {
+        Ok(required) => required,
+        Err(_) => return,
     };
+    if license::allows(&required, "lgpl").is_none() {
+        panic!("FFMPEG_REQUIRE_LICENSE must be `lgpl` or `gpl`, not `{}`", required);
+    }
 
-    if statik && cfg!(target_os = "macos") {
-        let frameworks = vec![
-            "AppKit",
-            "AudioToolbox",
-            "AVFoundation",
-            "CoreFoundation",
-            "CoreGraphics",
-            "CoreMedia",
-            "CoreServices",
-            "CoreVideo",
-            "Foundation",
-            "OpenCL",
-            "OpenGL",
-            "QTKit",
-            "QuartzCore",
-            "Security",
-            "VideoDecodeAcceleration",
-            "VideoToolbox",
-        ];
-        for f in frameworks {
-            println!("cargo:rustc-link-lib=framework={}", f);
+    match detected {
+        Some((class, source)) => {
+            if license::allows(&required, class) == Some(false) {
+                panic!(
+                    "FFMPEG_REQUIRE_LICENSE={} but the FFmpeg found is {} (from {}), link an \
+                     FFmpeg configured without {}",
+                    required,
+                    class,
+                    source,
+                    if class == "nonfree" { "--enable-nonfree" } else { "--enable-gpl" }
+                );
+            }
         }
+        None => panic!(
+            "FFMPEG_REQUIRE_LICENSE={} but the license of the FFmpeg found can't be detected, \
+             there are neither linked libraries, an ffmpeg program nor pkg-config files to take \
+             it from",
+            required
+        ),
     }
+}
 
-    check_features(
-        include_paths.clone(),
-        &vec![
-            ("libavutil/avutil.h", None, "FF_API_OLD_AVOPTIONS"),
+// the directories the C compiler searches for <...> includes. bindgen's clang doesn't find the
+// libc headers by itself where they aren't in /usr/include, like on NixOS, where only the
+// compiler wrapper knows them. Its own builtin headers are left to clang
+fn compiler_include_paths() -> Vec<PathBuf> {
+    let tool = cc::Build::new().get_compiler();
+    if tool.is_like_msvc() {
+        return Vec::new();
+    }
+
+    let output = match tool
+        .to_command()
+        .args(["-E", "-v", "-x", "c", "-"])
+        .stdin(Stdio::null())
+        .output()
+    {
+        Ok(output) => output,
+        Err(_) => return Vec::new(),
+    };
+
+    paths::compiler_search_dirs(&String::from_utf8_lossy(&output.stderr))
+}
+
+fn maybe_search_include(include_paths: &Vec<PathBuf>, header: &str) -> Option<String> {
+    for dir in include_paths {
+        let include = dir.join(header);
+        if fs::metadata(&include).is_ok() {
+            return Some(include.to_str().unwrap().to_string());
+        }
+    }
+    None
+}
+
+// adds a field to the build report, replacing an earlier one of the same name
+fn report(name: &'static str, value: String) {
+    let mut report = REPORT.lock().unwrap();
+    report.retain(|&(field, _)| field != name);
+    report.push((name, value));
+}
+
+// writes ffmpeg-sys-build-report.json, FFMPEG_BUILD_REPORT_STDOUT=1 prints it as well for CI
+// logs (cargo shows it with -vv)
+fn write_build_report() {
+    let path = output().join("ffmpeg-sys-build-report.json");
+    println!("cargo:build_report={}", path.to_string_lossy());
+
+    let mut fields = REPORT.lock().unwrap().clone();
+    let directives = json::array(&DIRECTIVES.lock().unwrap());
+    fields.push(("directives", directives));
+    let json = json::object(&fields);
+
+    // also called from the panic hook, where a failure to write can't panic again
+    if fs::write(&path, &json).is_ok() {
+        println!("ffmpeg-sys build report: {}", path.to_string_lossy());
+    }
+    if env::var("FFMPEG_BUILD_REPORT_STDOUT").is_ok_and(|v| v == "1") {
+        print!("{}", json);
+    }
+}
+
+fn header_version(include_paths: &Vec<PathBuf>, lib: &str) -> Option<(u32, u32, u32)> {
+    let mut code = String::new();
+    for header in &["version_major.h", "version.h"] {
+        if let Some(path) = maybe_search_include(include_paths, &format!("lib{}/{}", lib, header)) {
+            code.push_str(&fs::read_to_string(path).ok()?);
+        }
+    }
+
+    version_h::version(&code, lib)
+}
+
+// headers of libraries from different FFmpeg releases, e.g. a libswscale-dev left over from an
+// older release next to a newer FFmpeg in /usr/local, produce bindings that match neither
+fn check_release_family(include_paths: &Vec<PathBuf>, majors: &Vec<(&str, u32)>) {
+    let avutil = majors.iter().find(|&&(lib, _)| lib == "avutil").unwrap().1;
+    let header = |lib: &str| {
+        maybe_search_include(include_paths, &format!("lib{}/version.h", lib)).unwrap()
+    };
+    let mismatches: Vec<_> = release::mismatches(majors)
+        .into_iter()
+        .map(|(lib, major, expected)| {
+            format!("lib{} {} (expected {}) from {}", lib, major, expected, header(lib))
+        })
+        .collect();
+    if !mismatches.is_empty() {
+        panic!(
+            "the FFmpeg headers are from different releases, libavutil {} from {} doesn't go \
+             with\n    {}",
+            avutil,
+            header("avutil"),
+            mismatches.join("\n    ")
+        );
+    }
+}
+
+fn header_declares(include_paths: &Vec<PathBuf>, header: &str, name: &str) -> bool {
+    maybe_search_include(include_paths, header)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .is_some_and(|code| code.contains(name))
+}
+
+// static inline functions bindgen can't bind, each one is wrapped in a C function named
+// ffmpeg_sys_<name> which is bound under the original name
+// (header, name, (C return type, Rust return type), [(C type, Rust type, argument)])
+type InlineShim = (
+    &'static str,
+    &'static str,
+    (&'static str, &'static str),
+    &'static [(&'static str, &'static str, &'static str)],
+);
+
+static INLINE_SHIMS: &[InlineShim] = &[
+    (
+        "libavutil/common.h",
+        "av_clip",
+        ("int", "libc::c_int"),
+        &[
+            ("int", "libc::c_int", "a"),
+            ("int", "libc::c_int", "amin"),
+            ("int", "libc::c_int", "amax"),
+        ],
+    ),
+    (
+        "libavutil/common.h",
+        "av_clip_uint8",
+        ("uint8_t", "u8"),
+        &[("int", "libc::c_int", "a")],
+    ),
+    (
+        "libavutil/common.h",
+        "av_clip_int16",
+        ("int16_t", "i16"),
+        &[("int", "libc::c_int", "a")],
+    ),
+    (
+        "libavutil/common.h",
+        "av_clipf",
+        ("float", "f32"),
+        &[("float", "f32", "a"), ("float", "f32", "amin"), ("float", "f32", "amax")],
+    ),
+    (
+        "libavutil/common.h",
+        "av_sat_add32",
+        ("int", "libc::c_int"),
+        &[("int", "libc::c_int", "a"), ("int", "libc::c_int", "b")],
+    ),
+    (
+        "libavutil/common.h",
+        "av_ceil_log2",
+        ("int", "libc::c_int"),
+        &[("int", "libc::c_int", "x")],
+    ),
+    (
+        "libavutil/common.h",
+        "av_popcount",
+        ("int", "libc::c_int"),
+        &[("uint32_t", "u32", "x")],
+    ),
+    (
+        "libavutil/bswap.h",
+        "av_bswap16",
+        ("uint16_t", "u16"),
+        &[("uint16_t", "u16", "x")],
+    ),
+    (
+        "libavutil/bswap.h",
+        "av_bswap32",
+        ("uint32_t", "u32"),
+        &[("uint32_t", "u32", "x")],
+    ),
+    (
+        "libavutil/bswap.h",
+        "av_bswap64",
+        ("uint64_t", "u64"),
+        &[("uint64_t", "u64", "x")],
+    ),
+    (
+        "libavutil/mem.h",
+        "av_size_mult",
+        ("int", "libc::c_int"),
+        &[("size_t", "usize", "a"), ("size_t", "usize", "b"), ("size_t *", "*mut usize", "r")],
+    ),
+    (
+        "libavutil/avutil.h",
+        "av_x_if_null",
+        ("void *", "*mut libc::c_void"),
+        &[
+            ("const void *", "*const libc::c_void", "p"),
+            ("const void *", "*const libc::c_void", "x"),
+        ],
+    ),
+    (
+        "libavutil/avstring.h",
+        "av_strnlen",
+        ("size_t", "usize"),
+        &[("const char *", "*const libc::c_char", "s"), ("size_t", "usize", "len")],
+    ),
+    (
+        "libavutil/avstring.h",
+        "av_isdigit",
+        ("int", "libc::c_int"),
+        &[("int", "libc::c_int", "c")],
+    ),
+    (
+        "libavutil/timestamp.h",
+        "av_ts_make_string",
+        ("char *", "*mut libc::c_char"),
+        &[("char *", "*mut libc::c_char", "buf"), ("int64_t", "i64", "ts")],
+    ),
+    (
+        "libavutil/timestamp.h",
+        "av_ts_make_time_string",
+        ("char *", "*mut libc::c_char"),
+        &[
+            ("char *", "*mut libc::c_char", "buf"),
+            ("int64_t", "i64", "ts"),
+            ("AVRational *", "*mut AVRational", "tb"),
+        ],
+    ),
+];
+
+fn build_inline_shims(include_paths: &Vec<PathBuf>) {
+    let mut c_code = String::new();
+    let mut rust_code = String::new();
+
+    if env::var("CARGO_FEATURE_INLINE_SHIMS").is_ok() {
+        let mut includes = Vec::new();
+        for &(header, name, (c_ret, rust_ret), args) in INLINE_SHIMS {
+            // older FFmpeg versions don't have all of them
+            if !header_declares(include_paths, header, name) {
+                continue;
+            }
+
+            if !includes.contains(&header) {
+                c_code.push_str(&format!("#include <{}>\n", header));
+                includes.push(header);
+            }
+
+            let c_args: Vec<_> = args
+                .iter()
+                .map(|&(c_ty, _, arg)| format!("{} {}", c_ty, arg))
+                .collect();
+            let c_names: Vec<_> = args.iter().map(|&(_, _, arg)| arg).collect();
+            c_code.push_str(&format!(
+                "{ret} ffmpeg_sys_{name}({args}) {{ return {name}({names}); }}\n",
+                ret = c_ret,
+                name = name,
+                args = c_args.join(", "),
+                names = c_names.join(", ")
+            ));
+
+            let rust_args: Vec<_> = args
+                .iter()
+                .map(|&(_, rust_ty, arg)| format!("{}: {}", arg, rust_ty))
+                .collect();
+            rust_code.push_str(&format!(
+                "extern \"C\" {{\n    #[link_name = \"ffmpeg_sys_{name}\"]\n    \
+                 pub fn {name}({args}) -> {ret};\n}}\n",
+                name = name,
+                args = rust_args.join(", "),
+                ret = rust_ret
+            ));
+        }
+
+        let c_file = output().join("inline_shims.c");
+        fs::write(&c_file, c_code).expect("Couldn't write inline_shims.c");
+
+        let mut build = cc::Build::new();
+        for dir in include_paths {
+            build.include(dir);
+        }
+        for flag in sanitizer_cflags() {
+            build.flag(&flag);
+        }
+        build.file(c_file).compile("ffmpeg_sys_inline_shims");
+    }
+
+    fs::write(output().join("inline_shims.rs"), rust_code).expect("Couldn't write inline_shims.rs");
+}
+
+// the loader of the `dlopen` feature: for every enabled library bindgen generates a struct with
+// a pointer and a method for each function its headers declare, the types are those of the
+// bindings. Library in src/dlopen.rs loads the files and hands them to these structs
+fn write_dlopen(include_paths: &Vec<PathBuf>, builder: &bindgen::Builder) {
+    let mut code = String::new();
+
+    // dependencies first, the dynamic loader finds them by soname once they are loaded
+    let libs: Vec<_> = ffmpeg_libs().into_iter().rev().collect();
+    code.push_str("const LIBRARIES: &[(&str, u32)] = &[\n");
+    for lib in &libs {
+        let (major, _, _) = header_version(include_paths, lib).unwrap_or_else(|| {
+            panic!(
+                "couldn't read the LIB{}_VERSION macros from the headers",
+                lib.to_uppercase()
+            )
+        });
+        code.push_str(&format!("    (\"{}\", {}),\n", lib, major));
+    }
+    code.push_str("];\n\n");
+
+    let structure = |lib: &str| {
+        let mut name = lib.to_owned();
+        name[..1].make_ascii_uppercase();
+        name
+    };
+
+    for lib in &libs {
+        let functions = builder
+            .clone()
+            .allowlist_file(format!(".*/lib{}/.*", lib))
+            .with_codegen_config(bindgen::CodegenConfig::FUNCTIONS)
+            .dynamic_library_name(structure(lib))
+            .generate()
+            .unwrap_or_else(|_| panic!("Unable to generate the dlopen bindings of lib{}", lib));
+
+        fs::write(output().join(format!("dlopen_{}.rs", lib)), functions.to_string())
+            .expect("Couldn't write the dlopen bindings");
+        code.push_str(&format!(
+            "mod {lib} {{\n    use super::super::*;\n    \
+             include!(concat!(env!(\"OUT_DIR\"), \"/dlopen_{lib}.rs\"));\n}}\n\
+             pub use self::{lib}::{structure};\n\n",
+            lib = lib,
+            structure = structure(lib)
+        ));
+    }
+
+    code.push_str(
+        "/// The functions of the enabled FFmpeg libraries, loaded at runtime.\n\
+         pub struct Library {\n",
+    );
+    for lib in &libs {
+        code.push_str(&format!("    pub {}: {},\n", lib, structure(lib)));
+    }
+    code.push_str("}\n\n");
+
+    // in the order of LIBRARIES, the structs keep the files loaded
+    code.push_str(
+        "impl Library {\n    \
+         fn resolve(libraries: Vec<libloading::Library>) -> Library {\n        \
+         let mut libraries = libraries.into_iter();\n        \
+         unsafe {\n            Library {\n",
+    );
+    for lib in &libs {
+        code.push_str(&format!(
+            "                {}: {}::from_library(libraries.next().unwrap()).unwrap(),\n",
+            lib,
+            structure(lib)
+        ));
+    }
+    code.push_str("            }\n        }\n    }\n}\n");
+
+    fs::write(output().join("dlopen.rs"), code).expect("Couldn't write dlopen.rs");
+}
+
+// the functions and types of the bindings that come from FFmpeg itself, the system types differ
+// between platforms
+fn binding_items(bindings: &str) -> BTreeSet<String> {
+    let item = Regex::new(r"pub (fn|struct|union|enum|type) (\w+)").unwrap();
+    let ffmpeg = Regex::new(r"^(?i:av|ff|sw|pp|postproc|rcoverride|rdft|dct)").unwrap();
+
+    item.captures_iter(bindings)
+        .filter(|captures| ffmpeg.is_match(&captures[2]) && !captures[2].contains("_bindgen_ty_"))
+        .map(|captures| format!("{} {}", &captures[1], &captures[2]))
+        .collect()
+}
+
+// a header probe that silently failed usually shows up as hundreds of missing functions, which
+// only fail to link in the crates using them
+fn verify_bindings(include_paths: &Vec<PathBuf>, bindings: &str, update: bool) {
+    // libavutil 55 is FFmpeg 3.x
+    let (avutil, _, _) = header_version(include_paths, "avutil").unwrap();
+    let snapshot = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("snapshots")
+        .join(format!("ffmpeg-{}.txt", avutil - 52));
+    let libs = format!("# libs: {}", ffmpeg_libs().join(" "));
+    let items = binding_items(bindings);
+
+    // written to OUT_DIR, `cargo xtask snapshot` copies it into the crate
+    if update {
+        let mut code = libs;
+        code.push('\n');
+        for item in &items {
+            code.push_str(item);
+            code.push('\n');
+        }
+        let update = output().join("snapshots").join(snapshot.file_name().unwrap());
+        fs::create_dir_all(update.parent().unwrap()).expect("Couldn't create snapshots");
+        fs::write(&update, code).expect("Couldn't write the snapshot");
+        println!("wrote {}", update.display());
+        return;
+    }
+
+    let expected = match fs::read_to_string(&snapshot) {
+        Ok(expected) => expected,
+        Err(_) => {
+            println!(
+                "cargo:warning=there is no {}, `cargo xtask snapshot` creates it",
+                snapshot.display()
+            );
+            return;
+        }
+    };
+    let mut lines = expected.lines();
+    if lines.next() != Some(&*libs) {
+        println!(
+            "cargo:warning={} was taken with other libraries enabled, not comparing",
+            snapshot.display()
+        );
+        return;
+    }
+    let expected: BTreeSet<_> = lines.map(|line| line.to_owned()).collect();
+
+    let missing: Vec<_> = expected.difference(&items).collect();
+    let added: Vec<_> = items.difference(&expected).collect();
+    for item in &missing {
+        println!("missing from the bindings: {}", item);
+    }
+    for item in &added {
+        println!("not in the snapshot: {}", item);
+    }
+    if !missing.is_empty() {
+        println!(
+            "cargo:warning={} items of {} are missing from the bindings, e.g. {}",
+            missing.len(),
+            snapshot.display(),
+            missing
+                .iter()
+                .take(10)
+                .map(|item| item.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    if !added.is_empty() {
+        println!(
+            "cargo:warning={} items of the bindings are not in {}",
+            added.len(),
+            snapshot.display()
+        );
+    }
+}
+
+// the bindings checked in for review, so a change of the builder shows up as a diff. the header
+// names what they were generated from, a check against bindings of another feature set or
+// target fails on it
+fn dump_bindings(include_paths: &Vec<PathBuf>, bindings: &str, update: bool) {
+    let (avutil, minor, micro) = header_version(include_paths, "avutil").unwrap();
+    let path = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("generated")
+        .join(format!("ffmpeg-{}.rs", avutil - 52));
+
+    let mut features: Vec<_> = env::vars()
+        .filter_map(|(key, _)| {
+            key.strip_prefix("CARGO_FEATURE_")
+                .map(|feature| feature.to_lowercase().replace('_', "-"))
+        })
+        .collect();
+    features.sort();
+
+    let code = format!(
+        "// FFmpeg {major}.x (libavutil {avutil}.{minor}.{micro}), generated by `cargo xtask \
+         generate-bindings`\n\
+         // target: {target}\n\
+         // libs: {libs}\n\
+         // features: {features}\n\n\
+         {bindings}",
+        major = avutil - 52,
+        avutil = avutil,
+        minor = minor,
+        micro = micro,
+        target = env::var("TARGET").unwrap(),
+        libs = ffmpeg_libs().join(" "),
+        features = features.join(" "),
+        bindings = bindings
+    );
+
+    // written to OUT_DIR, `cargo xtask generate-bindings` copies it into the crate
+    if update {
+        let update = output().join("generated").join(path.file_name().unwrap());
+        fs::create_dir_all(update.parent().unwrap()).expect("Couldn't create generated");
+        fs::write(&update, code).expect("Couldn't write the bindings");
+        println!("wrote {}", update.display());
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!("there is no {}, `cargo xtask generate-bindings` creates it", path.display())
+    });
+    if let Some((line, (expected, generated))) = expected
+        .lines()
+        .zip(code.lines())
+        .enumerate()
+        .find(|&(_, (expected, generated))| expected != generated)
+    {
+        panic!(
+            "the bindings differ from {} from line {}:\n    {}\ninstead of\n    {}\n\
+             `cargo xtask generate-bindings` rewrites it",
+            path.display(),
+            line + 1,
+            generated,
+            expected
+        );
+    }
+    if expected.lines().count() != code.lines().count() {
+        panic!(
+            "the bindings have {} lines, {} has {}. `cargo xtask generate-bindings` rewrites it",
+            code.lines().count(),
+            path.display(),
+            expected.lines().count()
+        );
+    }
+}
+
+// AVSubtitleRect.pict only exists while FF_API_AVPICTURE is 1, bindgen's clang has to have seen
+// the same value as check.c or the fields after it are read at the wrong offsets
+fn check_avpicture_fields(ff_api: &[(&'static str, bool, bool)], bindings: &str) {
+    let avpicture = ff_api
+        .iter()
+        .any(|&(name, enabled, _)| name == "FF_API_AVPICTURE" && enabled);
+
+    let start = match bindings.find("pub struct AVSubtitleRect {") {
+        Some(start) => start,
+        None => return,
+    };
+    let end = bindings[start..].find("\n}").map_or(bindings.len(), |end| start + end);
+    let pict = bindings[start..end].contains("pub pict:");
+
+    if pict != avpicture {
+        panic!(
+            "FF_API_AVPICTURE is {} but the generated AVSubtitleRect {} the pict field, bindgen \
+             didn't parse the headers check.c was compiled against",
+            avpicture as u8,
+            if pict { "has" } else { "lacks" }
+        );
+    }
+}
+
+fn required_headers(
+    mut builder: bindgen::Builder,
+    include_paths: &Vec<PathBuf>,
+    missing_headers: &mut Vec<String>,
+    feature: &str,
+    headers: &[&str],
+) -> bindgen::Builder {
+    for header in headers {
+        if let Some(path) = maybe_search_include(include_paths, header) {
+            builder = builder.header(path);
+        } else {
+            missing_headers.push(format!("{} (required by `{}`)", header, feature));
+        }
+    }
+    builder
+}
+
+fn main() {
+    // a failed build is when the report is needed most
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        report("panic", json::string(&info.to_string()));
+        write_build_report();
+        default_hook(info);
+    }));
+
+    // pkg-config reports the variables it reads, after which cargo only reruns the build script
+    // for the variables and files reported, so all of them are
+    println!("cargo:rerun-if-changed=build.rs");
+    for var in &[
+        "FFMPEG_DIR",
+        "FFMPEG_PKG_CONFIG_PATH",
+        "FFMPEG_LINK_MODE",
+        "FFMPEG_ALLOW_LIBAV",
+        "FFMPEG_ALLOW_MISSING_HEADERS",
+        "FFMPEG_ALLOW_MIXED_INSTALLATIONS",
+        "FFMPEG_REQUIRE_LICENSE",
+        "FFMPEG_VERIFY_BINDINGS",
+        "FFMPEG_DUMP_BINDINGS",
+        "FFMPEG_BUILD_REPORT_STDOUT",
+        "FFMPEG_PARALLEL",
+        "FFMPEG_FORCE_REBUILD",
+        "FFMPEG_FORCE_CLEAN",
+        "FFMPEG_SANITIZER",
+        "FFMPEG_FRAME_POINTERS",
+        "FFMPEG_CPU",
+        "FFMPEG_DISABLE_RUNTIME_CPUDETECT",
+        "FFMPEG_LTO",
+        "SOURCE_DATE_EPOCH",
+        "AMF_SDK_DIR",
+        "RPI_VC_DIR",
+        "VULKAN_SDK",
+        // read by bindgen itself
+        "BINDGEN_EXTRA_CLANG_ARGS",
+    ] {
+        println!("cargo:rerun-if-env-changed={}", var);
+    }
+
+    // whether any FFmpeg library is linked statically
+    let mut statik = link_mode() == "static";
+
+    // the libraries are loaded at runtime by the dlopen module, nothing is linked
+    let dlopen = feature_enabled("dlopen");
+    if dlopen && (statik || feature_enabled("build")) {
+        panic!(
+            "the `dlopen` feature loads the shared libraries at runtime, it can't be combined \
+             with `build`, `static` or FFMPEG_LINK_MODE=static"
+        );
+    }
+
+    if env::var("CARGO_FEATURE_HWCONTEXT_D3D11").is_ok()
+        && !env::var("TARGET").unwrap().contains("windows")
+    {
+        panic!("the `hwcontext-d3d11` feature is only available on Windows targets");
+    }
+
+    for feature in &["build-d3d11va", "build-dxva2"] {
+        if feature_enabled(feature) && !env::var("TARGET").unwrap().contains("windows") {
+            panic!("the `{}` feature is only available on Windows targets", feature);
+        }
+    }
+
+    if env::var("CARGO_FEATURE_HWCONTEXT_VIDEOTOOLBOX").is_ok()
+        && !env::var("TARGET").unwrap().contains("apple")
+    {
+        panic!("the `hwcontext-videotoolbox` feature is only available on Apple targets");
+    }
+
+    for feature in &["build-videotoolbox", "build-audiotoolbox"] {
+        if feature_enabled(feature) && !env::var("TARGET").unwrap().contains("apple") {
+            panic!("the `{}` feature is only available on Apple targets", feature);
+        }
+    }
+
+    if env::var("CARGO_FEATURE_VDPAU").is_ok() && !env::var("TARGET").unwrap().contains("linux") {
+        panic!("the `vdpau` feature is only available on Linux targets");
+    }
+
+    for feature in &["build-indev-alsa", "build-libv4l2", "build-vdpau"] {
+        if feature_enabled(feature) && !env::var("TARGET").unwrap().contains("linux") {
+            panic!("the `{}` feature is only available on Linux targets", feature);
+        }
+    }
+
+    // external libraries that only plug into a single component
+    for &(feature, component) in &[
+        ("build-lib-vmaf", "avfilter"),
+        ("build-lib-zimg", "avfilter"),
+        ("build-lib-srt", "avformat"),
+        ("build-lib-rist", "avformat"),
+        ("build-lib-rtmp", "avformat"),
+        ("build-lib-zmq", "avfilter"),
+        ("build-lib-xml2", "avformat"),
+        ("build-lib-bluray", "avformat"),
+        ("build-lib-vidstab", "avfilter"),
+        ("build-lib-rubberband", "avfilter"),
+        ("build-lib-codec2", "avcodec"),
+        ("build-lib-gme", "avformat"),
+        ("build-lib-openmpt", "avformat"),
+        ("build-lib-placebo", "avfilter"),
+        ("build-lib-jxl", "avcodec"),
+        ("build-indev-alsa", "avdevice"),
+        ("build-indev-pulse", "avdevice"),
+        ("build-libv4l2", "avdevice"),
+        ("build-xcb", "avdevice"),
+    ] {
+        if feature_enabled(feature) && !feature_enabled(component) {
+            panic!("the `{}` feature requires the `{}` feature", feature, component);
+        }
+    }
+
+    // configure would fail or silently skip the programs, which is only noticed when linking
+    // or copying them
+    check_components();
+
+    // without `build` nothing configures ffprobe, the pinned static binary is downloaded instead
+    if feature_enabled("ffprobe") && !feature_enabled("build") {
+        download_ffprobe();
+    }
+
+    // external libraries under the GPL
+    for feature in &["build-lib-vidstab", "build-lib-rubberband"] {
+        if feature_enabled(feature) && !feature_enabled("build-license-gpl") {
+            panic!("the `{}` feature requires the `build-license-gpl` feature", feature);
+        }
+    }
+
+    // libpostproc itself is GPL, configure would silently leave it out
+    if feature_enabled("postproc") {
+        if feature_enabled("build") && !feature_enabled("build-license-gpl") {
+            panic!("building the `postproc` feature requires the `build-license-gpl` feature");
+        } else if !feature_enabled("build") {
+            println!(
+                "cargo:warning=libpostproc is licensed under the GPL, which extends to binaries \
+                 linking it"
+            );
+        }
+    }
+
+    // only the programs are wanted, without bindings or anything to link
+    if feature_enabled("tools-only") {
+        if !["ffmpeg", "ffplay", "ffprobe"].iter().any(|program| feature_enabled(program)) {
+            panic!(
+                "the `tools-only` feature requires at least one of the `ffmpeg`, `ffplay` and \
+                 `ffprobe` features"
+            );
+        }
+
+        report("discovery", json::string("build"));
+        let source_id = build_dist();
+        report_configure_args();
+        report_programs();
+
+        fs::write(
+            output().join("versions.rs"),
+            format!(
+                "/// The FFmpeg sources the programs were built from, as \
+                 `<repository> <branch> <commit>`.\n\
+                 pub const FFMPEG_SOURCE_ID: &str = {:?};\n",
+                source_id
+            ),
+        )
+        .expect("Couldn't write versions!");
+        write_build_report();
+        return;
+    }
+
+    // the identity of the FFmpeg sources when they're built here
+    let mut source_id = None;
+
+    // the version of each library outside of builds from source, and where it was found
+    let mut library_versions: Vec<(&str, String, String)> = Vec::new();
+
+    // packaging scripts need to know which libraries have to be shipped as shared objects, none
+    // when everything is static
+    let mut shared_libs: Vec<PathBuf> = Vec::new();
+
+    let mut include_paths: Vec<PathBuf> = if env::var("CARGO_FEATURE_BUILD").is_ok() {
+        println!("FFMPEG-SYS get build...");
+        if link_mode() == "dylib" {
+            panic!(
+                "FFmpeg built from source is only linked statically, FFMPEG_LINK_MODE=dylib \
+                 needs FFMPEG_DIR or pkg-config"
+            );
+        }
+        statik = true;
+
+        // Make sure to link with the ffmpeg libs we built
+        for lib in ffmpeg_libs() {
+            println!("cargo:rustc-link-lib=static={}", lib);
+            report_link_kind(lib, "static");
+        }
+
+        // configure only records `pkg-config --libs`, which leaves out the private
+        // dependencies of the static SVT-AV1 and rav1e archives
+        if let Some(cxx) = target::cxx_runtime(&target_os()).filter(|_| feature_enabled("build-lib-svtav1")) {
+            println!("cargo:rustc-link-lib=pthread");
+            println!("cargo:rustc-link-lib={}", cxx);
+        }
+
+        // configure only records `pkg-config --libs`, the private dependencies of these
+        // (crypto libraries, the C++ runtime, compression libraries) have to be added here
+        for &(feature, package) in &[
+            ("build-lib-srt", "srt"),
+            ("build-lib-rist", "librist"),
+            ("build-lib-rtmp", "librtmp"),
+            ("build-lib-zmq", "libzmq"),
+            ("build-lib-xml2", "libxml-2.0"),
+            ("build-lib-bluray", "libbluray"),
+            ("build-lib-vidstab", "vidstab"),
+            ("build-lib-rubberband", "rubberband"),
+            ("build-lib-gme", "libgme"),
+            ("build-lib-openmpt", "libopenmpt"),
+            ("build-lib-kvazaar", "kvazaar"),
+            ("build-lib-placebo", "libplacebo"),
+            ("build-lib-jxl", "libjxl"),
+            ("build-lib-jxl", "libjxl_threads"),
+            ("build-indev-alsa", "alsa"),
+            ("build-indev-pulse", "libpulse"),
+            ("build-libv4l2", "libv4l2"),
+            ("build-xcb", "xcb"),
+            ("build-xcb", "xcb-shm"),
+            ("build-xcb", "xcb-xfixes"),
+            ("build-xcb", "xcb-shape"),
+            ("build-vaapi", "libva"),
+            ("build-vaapi", "libva-drm"),
+            ("build-vdpau", "vdpau"),
+            ("build-vpl", vpl_package()),
+            ("build-lib-shaderc", "shaderc"),
+        ] {
+            if !feature_enabled(feature) {
+                continue;
+            }
+
+            if let Ok(lib) = pkg_config::Config::new()
+                .statik(true)
+                .cargo_metadata(false)
+                .probe(package)
+            {
+                for path in lib.link_paths {
+                    println!("cargo:rustc-link-search=native={}", path.to_string_lossy());
+                }
+                for name in lib.libs {
+                    println!("cargo:rustc-link-lib={}", name);
+                }
+            }
+        }
+
+        if env::var("CARGO_FEATURE_BUILD_MMAL").is_ok() && target_is_arm() {
+            println!(
+                "cargo:rustc-link-search=native={}",
+                rpi_vc_dir().join("lib").to_string_lossy()
+            );
+            for lib in &[
+                "mmal_core",
+                "mmal_util",
+                "mmal_vc_client",
+                "bcm_host",
+                "vcos",
+                "vchiq_arm",
+            ] {
+                println!("cargo:rustc-link-lib={}", lib);
+            }
+        }
+
+        // libvmaf, zimg, game-music-emu, libopenmpt, libjxl, oneVPL and the shader compilers
+        // are C++ libraries
+        if (feature_enabled("build-lib-vmaf")
+            || feature_enabled("build-lib-zimg")
+            || feature_enabled("build-lib-gme")
+            || feature_enabled("build-lib-openmpt")
+            || feature_enabled("build-lib-jxl")
+            || feature_enabled("build-vpl")
+            || feature_enabled("build-lib-shaderc")
+            || feature_enabled("build-lib-glslang"))
+            && target::cxx_runtime(&target_os()).is_some()
+        {
+            println!("cargo:rustc-link-lib=pthread");
+            println!("cargo:rustc-link-lib={}", target::cxx_runtime(&target_os()).unwrap());
+        }
+
+        // rav1e is a Rust staticlib, its own copy of std only needs the system libraries
+        if feature_enabled("build-lib-rav1e") {
+            for lib in target::rust_staticlib_libs(&target_os()) {
+                println!("cargo:rustc-link-lib={}", lib);
+            }
+        }
+
+        report("discovery", json::string("build"));
+        source_id = Some(build_dist());
+        report_configure_args();
+
+        // configure's --libdir keeps them in lib, unless the toolchain moves them anyway
+        let lib_dir = libdir::find_lib_dir(&search(), &ffmpeg_libs())
+            .unwrap_or_else(|| search().join("lib"));
+        println!("cargo:rustc-link-search=native={}", lib_dir.to_string_lossy());
+
+        // Check additional required libraries.
+        {
+            let config_mak = fs::read_to_string(source().join("ffbuild/config.mak")).unwrap();
+            let libs = ffmpeg_libs();
+            let extra = extralibs::parse_extralibs(
+                &config_mak,
+                &env::var("TARGET").unwrap(),
+                |lib| libs.contains(&lib),
+            );
+
+            for path in extra.link_paths {
+                println!("cargo:rustc-link-search=native={}", path);
+            }
+            for framework in extra.frameworks {
+                println!("cargo:rustc-link-lib=framework={}", framework);
+            }
+            for lib in extra.libs {
+                println!("cargo:rustc-link-lib={}", lib);
+            }
+        }
+
+        report_programs();
+
+        // link arguments only reach the targets of this package, like the tests. rustc already
+        // garbage collects sections when it links the binaries of other packages
+        if feature_enabled("gc-sections") {
+            println!("cargo:rustc-link-arg={}", gc_sections_link_arg());
+        }
+
+        println!(r#"cargo:rustc-check-cfg=cfg(feature, values("has_vulkan"))"#);
+        if env::var("CARGO_FEATURE_BUILD_VULKAN").is_ok() {
+            println!(r#"cargo:rustc-cfg=feature="has_vulkan""#);
+            println!("cargo:has_vulkan=true");
+        }
+
+        vec![search().join("include")]
+    }
+    // Use prebuilt library, FFMPEG_PKG_CONFIG_PATH takes precedence
+    else if let (Ok(ffmpeg_dir), None) = (
+        env::var("FFMPEG_DIR"),
+        env::var_os("FFMPEG_PKG_CONFIG_PATH"),
+    ) {
+        report("discovery", json::string("ffmpeg_dir"));
+        let ffmpeg_dir = PathBuf::from(ffmpeg_dir);
+        let lib_dir = ffmpeg_dir.join("lib");
+
+        for lib in ffmpeg_libs() {
+            if let Some(major) = shared_lib_major(&ffmpeg_dir, lib) {
+                library_versions.push((lib, major, ffmpeg_dir.to_string_lossy().into_owned()));
+            }
+        }
+
+        if !dlopen {
+            println!("cargo:rustc-link-search=native={}", lib_dir.to_string_lossy());
+
+            let mode = link_mode();
+            let mut private = extralibs::ExtraLibs::default();
+            for lib in ffmpeg_libs() {
+                let kind = match &*mode {
+                    "auto" if has_static_lib(slice::from_ref(&lib_dir), lib) => "static",
+                    "auto" => "dylib",
+                    mode => mode,
+                };
+                statik |= kind == "static";
+
+                println!("cargo:rustc-link-lib={}={}", kind, lib);
+                report_link_kind(lib, kind);
+                if kind == "dylib" {
+                    shared_libs.extend(shared_lib_files(slice::from_ref(&lib_dir), lib));
+                    continue;
+                }
+
+                // the libraries a static one depends on, from the .pc files installed with it
+                let pc = lib_dir.join("pkgconfig").join(format!("lib{}.pc", lib));
+                match fs::read_to_string(&pc) {
+                    Ok(pc) => extralibs::merge(
+                        &mut private,
+                        extralibs::parse_libs_private(&pc, &env::var("TARGET").unwrap()),
+                    ),
+                    Err(_) => println!(
+                        "cargo:warning={} not found, the libraries lib{}.a depends on aren't \
+                         linked",
+                        pc.to_string_lossy(),
+                        lib
+                    ),
+                }
+            }
+
+            for path in private.link_paths {
+                println!("cargo:rustc-link-search=native={}", path);
+            }
+            for framework in private.frameworks {
+                println!("cargo:rustc-link-lib=framework={}", framework);
+            }
+            for lib in private.libs {
+                println!("cargo:rustc-link-lib={}", lib);
+            }
+        } else {
+            for lib in ffmpeg_libs() {
+                shared_libs.extend(shared_lib_files(slice::from_ref(&lib_dir), lib));
+            }
+        }
+
+        report_datadir(&ffmpeg_dir);
+
+        vec![ffmpeg_dir.join("include")]
+    }
+    // Fallback to pkg-config
+    else {
+        report("discovery", json::string("pkg-config"));
+        println!("fallback to pkg-config");
+
+        // Nix and Conan install every library into its own prefix and generate .pc files
+        // pointing there
+        if let Some(paths) = env::var_os("FFMPEG_PKG_CONFIG_PATH") {
+            let var = pkg_config_path_var();
+            let mut pkg_config_paths: Vec<_> = env::split_paths(&paths).collect();
+            if let Some(paths) = env::var_os(&var) {
+                pkg_config_paths.extend(env::split_paths(&paths));
+            }
+            env::set_var(&var, env::join_paths(pkg_config_paths).unwrap());
+        }
+
+        let mut include_paths = Vec::new();
+        let mut add_include_paths = |paths: Vec<PathBuf>| {
+            for path in paths {
+                if !include_paths.contains(&path) {
+                    include_paths.push(path);
+                }
+            }
+        };
+
+        // libavutil is always needed, everything else only for its feature
+        let mode = link_mode();
+        let mut missing = Vec::new();
+        for lib in ffmpeg_libs() {
+            let lib_name = format!("lib{}", lib);
+
+            // static where pkg-config points at an archive
+            let lib_statik = match &*mode {
+                _ if dlopen => false,
+                "auto" => pkg_config::Config::new()
+                    .cargo_metadata(false)
+                    .probe(&lib_name)
+                    .is_ok_and(|probed| has_static_lib(&probed.link_paths, lib)),
+                mode => mode == "static",
+            };
+
+            match pkg_config::Config::new()
+                .statik(lib_statik)
+                .cargo_metadata(!dlopen)
+                .env_metadata(true)
+                .probe(&lib_name)
+            {
+                Ok(probed) => {
+                    let origin = probed.link_paths.first().map_or("pkg-config".to_owned(), |path| {
+                        path.to_string_lossy().into_owned()
+                    });
+                    library_versions.push((lib, probed.version.clone(), origin));
+                    statik |= lib_statik;
+                    if !dlopen {
+                        report_link_kind(lib, if lib_statik { "static" } else { "dylib" });
+                    }
+                    if !lib_statik {
+                        // pkg-config leaves out the -L of the system library directories
+                        let mut dirs = probed.link_paths.clone();
+                        if let Ok(libdir) = pkg_config::get_variable(&lib_name, "libdir") {
+                            dirs.push(PathBuf::from(libdir));
+                        }
+                        shared_libs.extend(shared_lib_files(&dirs, lib));
+                    }
+                    add_include_paths(probed.include_paths);
+                }
+                // the .pc files pkg-config finds by default are those of the host
+                Err(pkg_config::Error::CrossCompilation) => panic!(
+                    "pkg-config can't be used to find FFmpeg for {target} without being set up \
+                     for cross compiling. Set PKG_CONFIG_ALLOW_CROSS=1 and point \
+                     PKG_CONFIG_PATH_{target_var} or PKG_CONFIG_SYSROOT_DIR at the FFmpeg of \
+                     the target, set FFMPEG_DIR to it, or enable the `build` feature",
+                    target = env::var("TARGET").unwrap(),
+                    target_var = env::var("TARGET").unwrap().replace('-', "_")
+                ),
+                Err(_) if lib == "avutil" => missing.push(format!(
+                    "ffmpeg-sys requires {}; install {}-dev",
+                    lib_name, lib_name
+                )),
+                Err(_) => missing.push(format!(
+                    "the `{}` feature requires {}; install {}-dev or disable the feature",
+                    lib, lib_name, lib_name
+                )),
+            }
+        }
+        if !missing.is_empty() {
+            panic!(
+                "FFmpeg libraries were not found by pkg-config:\n    {}",
+                missing.join("\n    ")
+            );
+        }
+
+        // every library of an installation shares the prefix, libavutil is always there
+        if let Ok(prefix) = pkg_config::get_variable("libavutil", "prefix") {
+            report_datadir(&PathBuf::from(prefix));
+        }
+
+        // pkg-config leaves out include directories the C compiler searches by itself, like
+        // /usr/include, which bindgen's clang doesn't necessarily search. the includedir of
+        // libavutil is the one of the installation the libraries were found in
+        if let Ok(includedir) = pkg_config::get_variable("libavutil", "includedir") {
+            let includedir = PathBuf::from(includedir);
+            if !include_paths.contains(&includedir) {
+                include_paths.push(includedir);
+            }
+        }
+        include_paths
+    };
+
+    // a static libavformat doesn't always bring the libraries of its TLS backend along
+    if statik && env::var("CARGO_FEATURE_AVFORMAT").is_ok() {
+        if let Some(backend) = tls_backend() {
+            println!("linking the {} TLS backend", backend);
+            link_tls_backend(backend);
+        }
+    }
+
+    // the QSV headers include the Intel Media SDK / oneVPL headers, only link them ourselves
+    // when FFmpeg is linked statically
+    if env::var("CARGO_FEATURE_QSV").is_ok() {
+        let mfx = pkg_config::Config::new()
+            .statik(statik)
+            .cargo_metadata(statik)
+            .probe("vpl")
+            .or_else(|_| {
+                pkg_config::Config::new()
+                    .statik(statik)
+                    .cargo_metadata(statik)
+                    .probe("libmfx")
+            })
+            .unwrap_or_else(|_| {
+                panic!(
+                    "the `qsv` feature requires the oneVPL (libvpl-dev) or Intel Media SDK \
+                     (libmfx-dev) development files"
+                )
+            });
+
+        include_paths.extend(mfx.include_paths);
+    }
+
+    if env::var("CARGO_FEATURE_VDPAU").is_ok() {
+        let vdpau = pkg_config::Config::new()
+            .statik(statik)
+            .cargo_metadata(statik)
+            .probe("vdpau")
+            .unwrap_or_else(|_| panic!("the `vdpau` feature requires libvdpau-dev"));
+
+        include_paths.extend(vdpau.include_paths);
+    }
+
+    if env::var("CARGO_FEATURE_HWCONTEXT_VULKAN").is_ok() {
+        if let Ok(sdk) = env::var("VULKAN_SDK") {
+            include_paths.push(PathBuf::from(sdk).join("include"));
+        } else {
+            let vulkan = pkg_config::Config::new()
+                .statik(statik)
+                .cargo_metadata(statik)
+                .probe("vulkan")
+                .unwrap_or_else(|_| {
+                    panic!(
+                        "the `hwcontext-vulkan` feature requires the Vulkan headers, install \
+                         libvulkan-dev or set VULKAN_SDK"
+                    )
+                });
+
+            include_paths.extend(vulkan.include_paths);
+        }
+    }
+
+    // builds from source link the frameworks configure picked from config.mak, prebuilt
+    // libraries don't say which ones they need
+    let link_frameworks =
+        statik && cfg!(target_os = "macos") && env::var("CARGO_FEATURE_BUILD").is_err();
+    if link_frameworks {
+        let frameworks = vec![
+            "AppKit",
+            "AudioToolbox",
+            "AVFoundation",
+            "CoreFoundation",
+            "CoreGraphics",
+            "CoreMedia",
+            "CoreServices",
+            "CoreVideo",
+            "Foundation",
+            "OpenCL",
+            "OpenGL",
+            "QTKit",
+            "QuartzCore",
+            "Security",
+            "VideoDecodeAcceleration",
+            "VideoToolbox",
+        ];
+        for f in frameworks {
+            println!("cargo:rustc-link-lib=framework={}", f);
+        }
+    }
+
+    if env::var("CARGO_FEATURE_HWCONTEXT_D3D11").is_ok() {
+        println!("cargo:rustc-link-lib=d3d11");
+        println!("cargo:rustc-link-lib=dxguid");
+    }
+
+    // system libraries the D3D11VA and DXVA2 hwaccels link against
+    if env::var("CARGO_FEATURE_BUILD_D3D11VA").is_ok() {
+        println!("cargo:rustc-link-lib=d3d11");
+        println!("cargo:rustc-link-lib=dxgi");
+    }
+    if env::var("CARGO_FEATURE_BUILD_D3D11VA").is_ok()
+        || env::var("CARGO_FEATURE_BUILD_DXVA2").is_ok()
+    {
+        println!("cargo:rustc-link-lib=ole32");
+    }
+
+    // already linked with the framework list above
+    if env::var("CARGO_FEATURE_HWCONTEXT_VIDEOTOOLBOX").is_ok() && !link_frameworks {
+        for f in &["CoreFoundation", "CoreMedia", "CoreVideo", "VideoToolbox"] {
+            println!("cargo:rustc-link-lib=framework={}", f);
+        }
+    }
+
+    // Libav shares the library names and most of the API with FFmpeg, but not the ABI or the
+    // FF_API macros, which makes the bindings fail in confusing ways. Its micro versions stay
+    // below 100, FFmpeg's start at 100
+    if !env::var("FFMPEG_ALLOW_LIBAV").is_ok_and(|v| v == "1") {
+        if let Some((major, minor, micro)) = header_version(&include_paths, "avutil") {
+            if micro < 100 {
+                panic!(
+                    "{} is from Libav {}.{}.{}, which ffmpeg-sys doesn't support. Point \
+                     FFMPEG_DIR or FFMPEG_PKG_CONFIG_PATH at an FFmpeg installation, or set \
+                     FFMPEG_ALLOW_LIBAV=1 to try anyway",
+                    maybe_search_include(&include_paths, "libavutil/version.h").unwrap(),
+                    major,
+                    minor,
+                    micro
+                );
+            }
+        }
+    }
+
+    // for the build script of a downstream crate or a packaging script to ship next to the
+    // executable, also available as ffmpeg_sys::build_info::shared_libraries()
+    let shared_libs: Vec<_> = shared_libs
+        .iter()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+    println!("cargo:shared_libs={}", shared_libs.join(";"));
+    report("shared_libs", json::array(&shared_libs));
+    fs::write(
+        output().join("build_info.rs"),
+        format!(
+            "/// The shared FFmpeg libraries the crate links or loads, with every file of their \
+             symlink chains, empty when everything is linked statically.\n\
+             pub fn shared_libraries() -> &'static [&'static str] {{\n    &{:?}\n}}\n",
+            shared_libs
+        ),
+    )
+    .expect("Couldn't write build_info.rs");
+
+    report(
+        "include_paths",
+        json::array(
+            &include_paths
+                .iter()
+                .map(|path| path.to_string_lossy().into_owned())
+                .collect::<Vec<_>>(),
+        ),
+    );
+    report(
+        "libraries",
+        format!(
+            "[{}]",
+            library_versions
+                .iter()
+                .map(|&(lib, ref version, ref origin)| format!(
+                    "{{\"name\": {}, \"version\": {}, \"origin\": {}}}",
+                    json::string(lib),
+                    json::string(version),
+                    json::string(origin)
+                ))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    );
+
+    // headers and libraries of two different installations, like FFmpeg 6 headers in /usr/local
+    // next to the FFmpeg 4 of the distribution, make for bindings that crash. A library reports
+    // its major version through the file name or its full version through pkg-config, newer
+    // minor versions than the headers keep the ABI
+    if !env::var("FFMPEG_ALLOW_MIXED_INSTALLATIONS").is_ok_and(|v| v == "1") {
+        let mut mismatches = Vec::new();
+        for &(lib, ref version, ref origin) in &library_versions {
+            let version_h = format!("lib{}/version.h", lib);
+            if maybe_search_include(&include_paths, &version_h).is_none() {
+                continue;
+            }
+            let (major, minor, micro) = match header_version(&include_paths, lib) {
+                Some(header_version) => header_version,
+                None => continue,
+            };
+
+            let parts: Vec<u32> = version.split('.').filter_map(|part| part.parse().ok()).collect();
+            let compatible = match parts.as_slice() {
+                [library_major] => *library_major == major,
+                [library_major, library_minor, ..] => {
+                    *library_major == major && *library_minor >= minor
+                }
+                _ => true,
+            };
+            if !compatible {
+                mismatches.push(format!(
+                    "lib{}: the headers in {} are version {}.{}.{}, the library in {} is \
+                     version {}",
+                    lib,
+                    maybe_search_include(&include_paths, &version_h).unwrap(),
+                    major,
+                    minor,
+                    micro,
+                    origin,
+                    version
+                ));
+            }
+        }
+        if !mismatches.is_empty() {
+            panic!(
+                "the FFmpeg headers and libraries come from different installations, the \
+                 bindings wouldn't match the libraries:\n    {}\nPoint FFMPEG_DIR or \
+                 FFMPEG_PKG_CONFIG_PATH at a single installation, or set \
+                 FFMPEG_ALLOW_MIXED_INSTALLATIONS=1 if the mix is deliberate",
+                mismatches.join("\n    ")
+            );
+        }
+    }
+
+    // check.c is linked against the libraries to catch headers and libraries of two different
+    // installations. Builds from source take both from the same tree, static archives would
+    // need all of their private dependencies and Windows wouldn't find the DLLs. dlopen only
+    // needs the headers at build time
+    let check_link_paths = if env::var("CARGO_FEATURE_BUILD").is_ok()
+        || statik
+        || dlopen
+        || env::var("TARGET").unwrap().contains("windows")
+    {
+        None
+    } else if let (Ok(ffmpeg_dir), None) = (
+        env::var("FFMPEG_DIR"),
+        env::var_os("FFMPEG_PKG_CONFIG_PATH"),
+    ) {
+        Some(vec![PathBuf::from(ffmpeg_dir).join("lib")])
+    } else {
+        let mut link_paths = Vec::new();
+        for lib in ffmpeg_libs() {
+            let package = format!("lib{}", lib);
+            if let Ok(lib) = pkg_config::Config::new().cargo_metadata(false).probe(&package) {
+                for path in lib.link_paths {
+                    if !link_paths.contains(&path) {
+                        link_paths.push(path);
+                    }
+                }
+            }
+        }
+        Some(link_paths)
+    };
+
+    // the shims only need the headers, they compile next to check.c and bindgen
+    let inline_shims = {
+        let include_paths = include_paths.clone();
+        stage(move |_| build_inline_shims(&include_paths))
+    };
+
+    // check.c runs next to bindgen as well, the blocklists that depend on the FF_API_* macros
+    // it reports are applied to the bindings afterwards
+    let checks_started = Instant::now();
+    let checks = {
+        let include_paths = include_paths.clone();
+        let infos = vec![
+            ("libavutil/avutil.h", None, "FF_API_OLD_AVOPTIONS"),
             ("libavutil/avutil.h", None, "FF_API_PIX_FMT"),
             ("libavutil/avutil.h", None, "FF_API_CONTEXT_SIZE"),
             ("libavutil/avutil.h", None, "FF_API_PIX_FMT_DESC"),
@@ -711,240 +3147,628 @@ fn main() {
             ("libavutil/avutil.h", None, "FF_API_AVFRAME_LAVC"),
             ("libavutil/avutil.h", None, "FF_API_VDPAU"),
             (
-                "libavutil/avutil.h",
-                None,
-                "FF_API_GET_CHANNEL_LAYOUT_COMPAT",
+                "libavutil/avutil.h",
+                None,
+                "FF_API_GET_CHANNEL_LAYOUT_COMPAT",
+            ),
+            ("libavutil/avutil.h", None, "FF_API_XVMC"),
+            ("libavutil/avutil.h", None, "FF_API_OPT_TYPE_METADATA"),
+            ("libavutil/avutil.h", None, "FF_API_DLOG"),
+            ("libavutil/avutil.h", None, "FF_API_HMAC"),
+            ("libavutil/avutil.h", None, "FF_API_VAAPI"),
+            ("libavutil/avutil.h", None, "FF_API_PKT_PTS"),
+            ("libavutil/avutil.h", None, "FF_API_ERROR_FRAME"),
+            ("libavutil/avutil.h", None, "FF_API_FRAME_QP"),
+            ("libavutil/avutil.h", None, "FF_API_PLUS1_MINUS1"),
+            ("libavutil/avutil.h", None, "FF_API_CRYPTO_SIZE_T"),
+            ("libavutil/avutil.h", None, "FF_API_FRAME_GET_SET"),
+            ("libavutil/avutil.h", None, "FF_API_PSEUDOPAL"),
+            ("libavutil/avutil.h", None, "FF_API_CHILD_CLASS_NEXT"),
+            ("libavutil/avutil.h", None, "FF_API_BUFFER_SIZE_T"),
+            ("libavutil/avutil.h", None, "FF_API_D2STR"),
+            ("libavutil/avutil.h", None, "FF_API_DECLARE_ALIGNED"),
+            ("libavutil/avutil.h", None, "FF_API_COLORSPACE_NAME"),
+            ("libavutil/avutil.h", None, "FF_API_AV_MALLOCZ_ARRAY"),
+            ("libavutil/avutil.h", None, "FF_API_FIFO_PEEK2"),
+            ("libavutil/avutil.h", None, "FF_API_FIFO_OLD_API"),
+            ("libavutil/avutil.h", None, "FF_API_OLD_CHANNEL_LAYOUT"),
+            ("libavutil/avutil.h", None, "FF_API_AV_FOPEN_UTF8"),
+            ("libavutil/avutil.h", None, "FF_API_PKT_DURATION"),
+            ("libavutil/avutil.h", None, "FF_API_REORDERED_OPAQUE"),
+            ("libavutil/avutil.h", None, "FF_API_FRAME_PICTURE_NUMBER"),
+            ("libavutil/avutil.h", None, "FF_API_HDR_VIVID_THREE_SPLINE"),
+            ("libavutil/avutil.h", None, "FF_API_FRAME_PKT"),
+            ("libavutil/avutil.h", None, "FF_API_INTERLACED_FRAME"),
+            ("libavutil/avutil.h", None, "FF_API_FRAME_KEY"),
+            ("libavutil/avutil.h", None, "FF_API_PALETTE_HAS_CHANGED"),
+            (
+                "libavutil/avutil.h",
+                None,
+                "FF_API_VULKAN_CONTIGUOUS_MEMORY",
+            ),
+            ("libavutil/avutil.h", None, "FF_API_H274_FILM_GRAIN_VCS"),
+            ("libavutil/avutil.h", None, "FF_API_MOD_UINTP2"),
+            ("libavutil/avutil.h", None, "FF_API_RISCV_FD_ZBA"),
+            ("libavutil/avutil.h", None, "FF_API_VULKAN_FIXED_QUEUES"),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_VIMA_DECODER",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_REQUEST_CHANNELS",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_OLD_DECODE_AUDIO",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_OLD_ENCODE_AUDIO",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_OLD_ENCODE_VIDEO",
+            ),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_CODEC_ID"),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_AUDIO_CONVERT",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_AVCODEC_RESAMPLE",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_DEINTERLACE",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_DESTRUCT_PACKET",
+            ),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_GET_BUFFER"),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_MISSING_SAMPLE",
+            ),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_LOWRES"),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_CAP_VDPAU"),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_BUFS_VDPAU"),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_VOXWARE"),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_SET_DIMENSIONS",
+            ),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_DEBUG_MV"),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_AC_VLC"),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_OLD_MSMPEG4",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_ASPECT_EXTENDED",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_THREAD_OPAQUE",
+            ),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_CODEC_PKT"),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_ARCH_ALPHA"),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_XVMC"),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_ERROR_RATE"),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_QSCALE_TYPE",
+            ),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_MB_TYPE"),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_MAX_BFRAMES",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_NEG_LINESIZES",
+            ),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_EMU_EDGE"),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_ARCH_SH4"),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_ARCH_SPARC"),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_UNUSED_MEMBERS",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_IDCT_XVIDMMX",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_INPUT_PRESERVED",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_NORMALIZE_AQP",
+            ),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_GMC"),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_MV0"),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_CODEC_NAME"),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_AFD"),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_VISMV"),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_DV_FRAME_PROFILE",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_AUDIOENC_DELAY",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_VAAPI_CONTEXT",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_AVCTX_TIMEBASE",
             ),
-            ("libavutil/avutil.h", None, "FF_API_XVMC"),
-            ("libavutil/avutil.h", None, "FF_API_OPT_TYPE_METADATA"),
-            ("libavutil/avutil.h", None, "FF_API_DLOG"),
-            ("libavutil/avutil.h", None, "FF_API_HMAC"),
-            ("libavutil/avutil.h", None, "FF_API_VAAPI"),
-            ("libavutil/avutil.h", None, "FF_API_PKT_PTS"),
-            ("libavutil/avutil.h", None, "FF_API_ERROR_FRAME"),
-            ("libavutil/avutil.h", None, "FF_API_FRAME_QP"),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_MPV_OPT"),
             (
                 "libavcodec/avcodec.h",
                 Some("avcodec"),
-                "FF_API_VIMA_DECODER",
+                "FF_API_STREAM_CODEC_TAG",
             ),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_QUANT_BIAS"),
             (
                 "libavcodec/avcodec.h",
                 Some("avcodec"),
-                "FF_API_REQUEST_CHANNELS",
+                "FF_API_RC_STRATEGY",
             ),
             (
                 "libavcodec/avcodec.h",
                 Some("avcodec"),
-                "FF_API_OLD_DECODE_AUDIO",
+                "FF_API_CODED_FRAME",
             ),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_MOTION_EST"),
             (
                 "libavcodec/avcodec.h",
                 Some("avcodec"),
-                "FF_API_OLD_ENCODE_AUDIO",
+                "FF_API_WITHOUT_PREFIX",
             ),
             (
                 "libavcodec/avcodec.h",
                 Some("avcodec"),
-                "FF_API_OLD_ENCODE_VIDEO",
+                "FF_API_CONVERGENCE_DURATION",
             ),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_CODEC_ID"),
             (
                 "libavcodec/avcodec.h",
                 Some("avcodec"),
-                "FF_API_AUDIO_CONVERT",
+                "FF_API_PRIVATE_OPT",
             ),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_CODER_TYPE"),
             (
                 "libavcodec/avcodec.h",
                 Some("avcodec"),
-                "FF_API_AVCODEC_RESAMPLE",
+                "FF_API_RTP_CALLBACK",
             ),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_STAT_BITS"),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_VBV_DELAY"),
             (
                 "libavcodec/avcodec.h",
                 Some("avcodec"),
-                "FF_API_DEINTERLACE",
+                "FF_API_SIDEDATA_ONLY_PKT",
             ),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_AVPICTURE"),
             (
                 "libavcodec/avcodec.h",
                 Some("avcodec"),
-                "FF_API_DESTRUCT_PACKET",
+                "FF_API_VDPAU_PROFILE",
             ),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_GET_BUFFER"),
             (
                 "libavcodec/avcodec.h",
                 Some("avcodec"),
-                "FF_API_MISSING_SAMPLE",
+                "FF_API_AVPACKET_OLD_API",
             ),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_LOWRES"),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_CAP_VDPAU"),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_BUFS_VDPAU"),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_VOXWARE"),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_ASS_TIMING"),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_OLD_BSF"),
             (
                 "libavcodec/avcodec.h",
                 Some("avcodec"),
-                "FF_API_SET_DIMENSIONS",
+                "FF_API_COPY_CONTEXT",
             ),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_DEBUG_MV"),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_AC_VLC"),
             (
                 "libavcodec/avcodec.h",
                 Some("avcodec"),
-                "FF_API_OLD_MSMPEG4",
+                "FF_API_GET_CONTEXT_DEFAULTS",
             ),
             (
                 "libavcodec/avcodec.h",
                 Some("avcodec"),
-                "FF_API_ASPECT_EXTENDED",
+                "FF_API_NVENC_OLD_NAME",
             ),
             (
                 "libavcodec/avcodec.h",
                 Some("avcodec"),
-                "FF_API_THREAD_OPAQUE",
+                "FF_API_STRUCT_VAAPI_CONTEXT",
             ),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_CODEC_PKT"),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_ARCH_ALPHA"),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_XVMC"),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_ERROR_RATE"),
             (
                 "libavcodec/avcodec.h",
                 Some("avcodec"),
-                "FF_API_QSCALE_TYPE",
+                "FF_API_MERGE_SD_API",
             ),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_MB_TYPE"),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_TAG_STRING"),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_GETCHROMA"),
             (
                 "libavcodec/avcodec.h",
                 Some("avcodec"),
-                "FF_API_MAX_BFRAMES",
+                "FF_API_CODEC_GET_SET",
             ),
             (
                 "libavcodec/avcodec.h",
                 Some("avcodec"),
-                "FF_API_NEG_LINESIZES",
+                "FF_API_USER_VISIBLE_AVHWACCEL",
             ),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_EMU_EDGE"),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_ARCH_SH4"),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_ARCH_SPARC"),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_LOCKMGR"),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_NEXT"),
             (
                 "libavcodec/avcodec.h",
                 Some("avcodec"),
-                "FF_API_UNUSED_MEMBERS",
+                "FF_API_UNSANITIZED_BITRATES",
             ),
             (
                 "libavcodec/avcodec.h",
                 Some("avcodec"),
-                "FF_API_IDCT_XVIDMMX",
+                "FF_API_OPENH264_SLICE_MODE",
             ),
             (
                 "libavcodec/avcodec.h",
                 Some("avcodec"),
-                "FF_API_INPUT_PRESERVED",
+                "FF_API_OPENH264_CABAC",
             ),
             (
                 "libavcodec/avcodec.h",
                 Some("avcodec"),
-                "FF_API_NORMALIZE_AQP",
+                "FF_API_UNUSED_CODEC_CAPS",
             ),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_GMC"),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_MV0"),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_CODEC_NAME"),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_AFD"),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_VISMV"),
             (
                 "libavcodec/avcodec.h",
                 Some("avcodec"),
-                "FF_API_DV_FRAME_PROFILE",
+                "FF_API_AVPRIV_PUT_BITS",
+            ),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_OLD_ENCDEC"),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_AVCODEC_PIX_FMT",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_MPV_RC_STRATEGY",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_PARSER_CHANGE",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_THREAD_SAFE_CALLBACKS",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_GET_FRAME_CLASS",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_AUTO_THREADS",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_INIT_PACKET",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_FLAG_TRUNCATED",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_SUB_TEXT_FORMAT",
+            ),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_IDCT_NONE"),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_SVTAV1_OPTS",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_AYUV_CODECID",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_VT_OUTPUT_CALLBACK",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_AVCODEC_CHROMA_POS",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_VT_HWACCEL_CONTEXT",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_AVCTX_FRAME_NUMBER",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_SLICE_OFFSET",
+            ),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_SUBFRAMES"),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_TICKS_PER_FRAME",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_DROPCHANGED",
+            ),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_AVFFT"),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_FF_PROFILE_LEVEL",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_AVCODEC_CLOSE",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_BUFFER_MIN_SIZE",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_VDPAU_ALLOC_GET_SET",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_QUALITY_FACTOR",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_V408_CODECID",
+            ),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_CODEC_PROPS",
+            ),
+            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_EXR_GAMMA"),
+            (
+                "libavcodec/avcodec.h",
+                Some("avcodec"),
+                "FF_API_NVDEC_OLD_PIX_FMTS",
+            ),
+            (
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_LAVF_BITEXACT",
+            ),
+            (
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_LAVF_FRAC",
+            ),
+            (
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_URL_FEOF",
+            ),
+            (
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_PROBESIZE_32",
+            ),
+            (
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_LAVF_AVCTX",
+            ),
+            (
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_OLD_OPEN_CALLBACKS",
+            ),
+            (
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_COMPUTE_PKT_FIELDS2",
+            ),
+            (
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_HTTP_USER_AGENT",
+            ),
+            (
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_HLS_WRAP",
+            ),
+            (
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_HLS_USE_LOCALTIME",
+            ),
+            (
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_LAVF_KEEPSIDE_FLAG",
+            ),
+            (
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_OLD_ROTATE_API",
+            ),
+            (
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_FORMAT_GET_SET",
+            ),
+            (
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_OLD_AVIO_EOF_0",
+            ),
+            (
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_LAVF_FFSERVER",
+            ),
+            (
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_FORMAT_FILENAME",
+            ),
+            (
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_OLD_RTSP_OPTIONS",
+            ),
+            (
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_DASH_MIN_SEG_DURATION",
             ),
             (
-                "libavcodec/avcodec.h",
-                Some("avcodec"),
-                "FF_API_AUDIOENC_DELAY",
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_LAVF_MP4A_LATM",
             ),
+            ("libavformat/avformat.h", Some("avformat"), "FF_API_NEXT"),
             (
-                "libavcodec/avcodec.h",
-                Some("avcodec"),
-                "FF_API_VAAPI_CONTEXT",
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_AVIOFORMAT",
             ),
             (
-                "libavcodec/avcodec.h",
-                Some("avcodec"),
-                "FF_API_AVCTX_TIMEBASE",
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_DEMUXER_OPEN",
             ),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_MPV_OPT"),
             (
-                "libavcodec/avcodec.h",
-                Some("avcodec"),
-                "FF_API_STREAM_CODEC_TAG",
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_CHAPTER_ID_INT",
             ),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_QUANT_BIAS"),
             (
-                "libavcodec/avcodec.h",
-                Some("avcodec"),
-                "FF_API_RC_STRATEGY",
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_LAVF_PRIV_OPT",
             ),
             (
-                "libavcodec/avcodec.h",
-                Some("avcodec"),
-                "FF_API_CODED_FRAME",
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_AVIOCONTEXT_WRITTEN",
             ),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_MOTION_EST"),
             (
-                "libavcodec/avcodec.h",
-                Some("avcodec"),
-                "FF_API_WITHOUT_PREFIX",
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_AVSTREAM_CLASS",
             ),
             (
-                "libavcodec/avcodec.h",
-                Some("avcodec"),
-                "FF_API_CONVERGENCE_DURATION",
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_GET_END_PTS",
             ),
             (
-                "libavcodec/avcodec.h",
-                Some("avcodec"),
-                "FF_API_PRIVATE_OPT",
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_AVIODIRCONTEXT",
             ),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_CODER_TYPE"),
             (
-                "libavcodec/avcodec.h",
-                Some("avcodec"),
-                "FF_API_RTP_CALLBACK",
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_AVFORMAT_IO_CLOSE",
             ),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_STAT_BITS"),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_VBV_DELAY"),
             (
-                "libavcodec/avcodec.h",
-                Some("avcodec"),
-                "FF_API_SIDEDATA_ONLY_PKT",
+                "libavformat/avformat.h",
+                Some("avformat"),
+                "FF_API_AVIO_WRITE_NONCONST",
             ),
-            ("libavcodec/avcodec.h", Some("avcodec"), "FF_API_AVPICTURE"),
             (
                 "libavformat/avformat.h",
                 Some("avformat"),
-                "FF_API_LAVF_BITEXACT",
+                "FF_API_AVSTREAM_SIDE_DATA",
             ),
             (
                 "libavformat/avformat.h",
                 Some("avformat"),
-                "FF_API_LAVF_FRAC",
+                "FF_API_LAVF_SHORTEST",
             ),
             (
                 "libavformat/avformat.h",
                 Some("avformat"),
-                "FF_API_URL_FEOF",
+                "FF_API_ALLOW_FLUSH",
             ),
             (
                 "libavformat/avformat.h",
                 Some("avformat"),
-                "FF_API_PROBESIZE_32",
+                "FF_API_R_FRAME_RATE",
             ),
             (
                 "libavformat/avformat.h",
                 Some("avformat"),
-                "FF_API_LAVF_AVCTX",
+                "FF_API_INTERNAL_TIMING",
             ),
             (
                 "libavformat/avformat.h",
                 Some("avformat"),
-                "FF_API_OLD_OPEN_CALLBACKS",
+                "FF_API_NO_DEFAULT_TLS_VERIFY",
             ),
             (
                 "libavfilter/avfilter.h",
@@ -986,160 +3810,777 @@ fn main() {
                 Some("avfilter"),
                 "FF_API_NOCONST_GET_NAME",
             ),
+            (
+                "libavfilter/avfilter.h",
+                Some("avfilter"),
+                "FF_API_LAVR_OPTS",
+            ),
+            (
+                "libavfilter/avfilter.h",
+                Some("avfilter"),
+                "FF_API_FILTER_GET_SET",
+            ),
+            ("libavfilter/avfilter.h", Some("avfilter"), "FF_API_NEXT"),
+            (
+                "libavfilter/avfilter.h",
+                Some("avfilter"),
+                "FF_API_SWS_PARAM_OPTION",
+            ),
+            (
+                "libavfilter/avfilter.h",
+                Some("avfilter"),
+                "FF_API_BUFFERSINK_ALLOC",
+            ),
+            (
+                "libavfilter/avfilter.h",
+                Some("avfilter"),
+                "FF_API_PAD_COUNT",
+            ),
+            (
+                "libavfilter/avfilter.h",
+                Some("avfilter"),
+                "FF_API_LIBPLACEBO_OPTS",
+            ),
+            (
+                "libavfilter/avfilter.h",
+                Some("avfilter"),
+                "FF_API_LINK_PUBLIC",
+            ),
+            (
+                "libavfilter/avfilter.h",
+                Some("avfilter"),
+                "FF_API_BUFFERSINK_OPTS",
+            ),
+            (
+                "libavfilter/avfilter.h",
+                Some("avfilter"),
+                "FF_API_CONTEXT_PUBLIC",
+            ),
             (
                 "libavresample/avresample.h",
                 Some("avresample"),
                 "FF_API_RESAMPLE_CLOSE_OPEN",
             ),
+            (
+                "libavdevice/avdevice.h",
+                Some("avdevice"),
+                "FF_API_DEVICE_CAPABILITIES",
+            ),
+            (
+                "libavdevice/avdevice.h",
+                Some("avdevice"),
+                "FF_API_BKTR_DEVICE",
+            ),
+            (
+                "libavdevice/avdevice.h",
+                Some("avdevice"),
+                "FF_API_OPENGL_DEVICE",
+            ),
+            (
+                "libavdevice/avdevice.h",
+                Some("avdevice"),
+                "FF_API_SDL2_DEVICE",
+            ),
             (
                 "libswscale/swscale.h",
                 Some("swscale"),
                 "FF_API_SWS_CPU_CAPS",
             ),
             ("libswscale/swscale.h", Some("swscale"), "FF_API_ARCH_BFIN"),
-        ],
-    );
+            ("libswscale/swscale.h", Some("swscale"), "FF_API_SWS_VECTOR"),
+        ];
+        stage(move |lines| {
+            let started = Instant::now();
+            let checked = check_features(include_paths, check_link_paths, &infos, lines);
+            (checked, started.elapsed())
+        })
+    };
+
+    // the versions of the headers the bindings are generated from, the same macros the
+    // version cfgs above are derived from
+    let mut versions_code = String::new();
+    let mut majors = Vec::new();
+    let mut header_versions = Vec::new();
+    for &(lib, feature) in &[
+        ("avutil", None),
+        ("avcodec", Some("avcodec")),
+        ("avformat", Some("avformat")),
+        ("avfilter", Some("avfilter")),
+        ("avdevice", Some("avdevice")),
+        ("avresample", Some("avresample")),
+        ("swscale", Some("swscale")),
+        ("swresample", Some("swresample")),
+        ("postproc", Some("postproc")),
+    ] {
+        if !feature.is_none_or(feature_enabled) {
+            continue;
+        }
 
-    let tmp = std::env::current_dir().unwrap().join("tmp");
-    if symlink_metadata(&tmp).is_err() {
-        create_dir(&tmp).expect("Failed to create temporary output dir");
+        let (major, minor, micro) = header_version(&include_paths, lib).unwrap_or_else(|| {
+            panic!("couldn't read the LIB{}_VERSION macros from the headers", lib.to_uppercase())
+        });
+        println!("cargo:{}_version={}.{}.{}", lib, major, minor, micro);
+        header_versions.push(format!(
+            "{}: {}",
+            json::string(lib),
+            json::string(&format!("{}.{}.{}", major, minor, micro))
+        ));
+        majors.push((lib, major));
+        versions_code.push_str(&format!(
+            "/// The lib{lib} version the bindings were generated against.\n\
+             pub const FFMPEG_SYS_{lib_uppercase}_VERSION: (u32, u32, u32) = \
+             ({major}, {minor}, {micro});\n",
+            lib = lib,
+            lib_uppercase = lib.to_uppercase(),
+            major = major,
+            minor = minor,
+            micro = micro
+        ));
+    }
+    report("header_versions", format!("{{{}}}", header_versions.join(", ")));
+    if let Some(source_id) = source_id {
+        versions_code.push_str(&format!(
+            "/// The FFmpeg sources the static libraries were built from, as \
+             `<repository> <branch> <commit>`.\n\
+             pub const FFMPEG_SOURCE_ID: &str = {:?};\n",
+            source_id
+        ));
     }
-    let mut f = File::create(tmp.join(".build")).expect("Filed to create .build");
+    fs::write(output().join("versions.rs"), versions_code).expect("Couldn't write versions!");
+    check_release_family(&include_paths, &majors);
+
+    // the command check.c is compiled with, for IDEs and other tooling that want to look at the
+    // headers the same way
     let tool = cc::Build::new().get_compiler();
-    write!(f, "{}", tool.path().to_string_lossy().into_owned()).expect("failed to write cmd");
+    let mut arguments = vec![tool.path().to_string_lossy().into_owned()];
     for arg in tool.args() {
-        write!(f, " {}", arg.to_str().unwrap()).expect("failed to write arg");
+        arguments.push(arg.to_string_lossy().into_owned());
     }
     for dir in &include_paths {
-        write!(f, " -I {}", dir.to_string_lossy().into_owned()).expect("failed to write incdir");
+        arguments.push(format!("-I{}", dir.to_string_lossy()));
     }
-    let clang_includes = include_paths
+    arguments.extend(sanitizer_cflags());
+    arguments.push("-c".into());
+    arguments.push("check.c".into());
+
+    let compile_commands = output().join("compile_commands.json");
+    fs::write(
+        &compile_commands,
+        format!(
+            "[{{\"directory\": {}, \"file\": {}, \"arguments\": [{}]}}]\n",
+            json::string(&output().to_string_lossy()),
+            json::string(&output().join("check.c").to_string_lossy()),
+            arguments
+                .iter()
+                .map(|arg| json::string(arg))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    )
+    .expect("Couldn't write compile_commands.json");
+    println!("cargo:compile_commands={}", compile_commands.to_string_lossy());
+
+    let mut clang_includes: Vec<_> = include_paths
         .iter()
-        .map(|include| format!("-I{}", include.to_string_lossy()));
+        .map(|include| format!("-I{}", include.to_string_lossy()))
+        .collect();
+    for include in compiler_include_paths() {
+        clang_includes.push("-isystem".into());
+        clang_includes.push(include.to_string_lossy().into_owned());
+    }
 
     // The bindgen::Builder is the main entry point
     // to bindgen, and lets you build up options for
     // the resulting bindings.
     let mut builder = bindgen::Builder::default()
-        .clang_args(clang_includes)
+        .clang_args(clang_includes.clone())
         .ctypes_prefix("libc")
+        // core paths work with and without the std feature
+        .use_core()
         // https://github.com/servo/rust-bindgen/issues/687
-        .blacklist_type("FP_NAN")
-        .blacklist_type("FP_INFINITE")
-        .blacklist_type("FP_ZERO")
-        .blacklist_type("FP_SUBNORMAL")
-        .blacklist_type("FP_NORMAL")
+        .blocklist_type("FP_NAN")
+        .blocklist_type("FP_INFINITE")
+        .blocklist_type("FP_ZERO")
+        .blocklist_type("FP_SUBNORMAL")
+        .blocklist_type("FP_NORMAL")
         // https://github.com/servo/rust-bindgen/issues/550
-        .blacklist_type("max_align_t")
-        .rustified_enum("*")
+        .blocklist_type("max_align_t")
+        // bindgen anchors the patterns as ^(...)$, a bare * isn't a valid regex anymore
+        .rustified_enum(".*")
         .prepend_enum_name(false)
         .derive_eq(true)
+        // the macros IntCallbacks leaves alone stay u32 or i32, as they were with bindgen 0.51
+        .default_macro_constant_type(MacroTypeVariation::Unsigned)
         .parse_callbacks(Box::new(IntCallbacks));
 
+    // newer headers define these through AVChannel, the masks the crate doesn't have itself
+    // are still bound where the headers can be evaluated
+    for name in channel_masks() {
+        builder = builder.blocklist_item(name);
+    }
+
+    // headers that can't be found are collected so they can be reported at once
+    let mut missing_headers = Vec::new();
+
     // The input headers we would like to generate
     // bindings for.
     if env::var("CARGO_FEATURE_AVCODEC").is_ok() {
-        builder = builder
-            .header(search_include(&include_paths, "libavcodec/avcodec.h"))
-            .header(search_include(&include_paths, "libavcodec/dv_profile.h"))
-            .header(search_include(&include_paths, "libavcodec/avfft.h"))
-            .header(search_include(&include_paths, "libavcodec/vaapi.h"))
-            .header(search_include(&include_paths, "libavcodec/vorbis_parser.h"));
+        builder = required_headers(
+            builder,
+            &include_paths,
+            &mut missing_headers,
+            "avcodec",
+            &["libavcodec/avcodec.h", "libavcodec/dv_profile.h"],
+        );
+        if feature_enabled("avcodec-avfft") {
+            builder = required_headers(
+                builder,
+                &include_paths,
+                &mut missing_headers,
+                "avcodec-avfft",
+                &["libavcodec/avfft.h"],
+            );
+        }
+
+        // vaapi.h was removed in later versions, vorbis_parser.h is left out by some packages
+        println!(r#"cargo:rustc-check-cfg=cfg(feature, values("has_vaapi_h", "has_vorbis_parser_h"))"#);
+        for &(header, cfg, feature) in &[
+            ("libavcodec/vaapi.h", "has_vaapi_h", None),
+            ("libavcodec/vorbis_parser.h", "has_vorbis_parser_h", Some("avcodec-vorbis-parser")),
+        ] {
+            if !feature.is_none_or(feature_enabled) {
+                continue;
+            }
+            if let Some(path) = maybe_search_include(&include_paths, header) {
+                builder = builder.header(path);
+                println!(r#"cargo:rustc-cfg=feature="{}""#, cfg);
+                println!("cargo:{}=true", cfg);
+            }
+        }
+
+        // split out of avcodec.h in later versions, which doesn't include all of them anymore
+        for header in &[
+            "libavcodec/bsf.h",
+            "libavcodec/codec.h",
+            "libavcodec/codec_desc.h",
+            "libavcodec/codec_id.h",
+            "libavcodec/codec_par.h",
+            "libavcodec/defs.h",
+            "libavcodec/packet.h",
+        ] {
+            if let Some(path) = maybe_search_include(&include_paths, header) {
+                builder = builder.header(path);
+            }
+        }
+
+        // the bitstream filter API moved from avcodec.h to bsf.h
+        println!(r#"cargo:rustc-check-cfg=cfg(feature, values("has_bsf"))"#);
+        if header_declares(&include_paths, "libavcodec/bsf.h", "av_bsf_send_packet")
+            || header_declares(&include_paths, "libavcodec/avcodec.h", "av_bsf_send_packet")
+        {
+            println!(r#"cargo:rustc-cfg=feature="has_bsf""#);
+            println!("cargo:has_bsf=true");
+        }
+
+        // the newest of the AVCodecContext.hwaccel_flags, from FFmpeg 6.1
+        println!(r#"cargo:rustc-check-cfg=cfg(feature, values("has_hwaccel_flag_unsafe_output"))"#);
+        if header_declares(
+            &include_paths,
+            "libavcodec/avcodec.h",
+            "AV_HWACCEL_FLAG_UNSAFE_OUTPUT",
+        ) {
+            println!(r#"cargo:rustc-cfg=feature="has_hwaccel_flag_unsafe_output""#);
+            println!("cargo:has_hwaccel_flag_unsafe_output=true");
+        }
     }
 
     if env::var("CARGO_FEATURE_AVDEVICE").is_ok() {
-        builder = builder.header(search_include(&include_paths, "libavdevice/avdevice.h"));
+        builder = required_headers(
+            builder,
+            &include_paths,
+            &mut missing_headers,
+            "avdevice",
+            &["libavdevice/avdevice.h"],
+        );
     }
 
     if env::var("CARGO_FEATURE_AVFILTER").is_ok() {
-        builder = builder
-            .header(search_include(&include_paths, "libavfilter/buffersink.h"))
-            .header(search_include(&include_paths, "libavfilter/buffersrc.h"))
-            .header(search_include(&include_paths, "libavfilter/avfilter.h"));
+        builder = required_headers(
+            builder,
+            &include_paths,
+            &mut missing_headers,
+            "avfilter",
+            &[
+                "libavfilter/buffersink.h",
+                "libavfilter/buffersrc.h",
+                "libavfilter/avfilter.h",
+            ],
+        );
+
+        // the graph segment API was added in FFmpeg 6.0
+        println!(r#"cargo:rustc-check-cfg=cfg(feature, values("has_graph_segment"))"#);
+        if header_declares(&include_paths, "libavfilter/avfilter.h", "avfilter_graph_segment_parse")
+        {
+            println!(r#"cargo:rustc-cfg=feature="has_graph_segment""#);
+            println!("cargo:has_graph_segment=true");
+        }
     }
 
     if env::var("CARGO_FEATURE_AVFORMAT").is_ok() {
-        builder = builder
-            .header(search_include(&include_paths, "libavformat/avformat.h"))
-            .header(search_include(&include_paths, "libavformat/avio.h"));
+        builder = required_headers(
+            builder,
+            &include_paths,
+            &mut missing_headers,
+            "avformat",
+            &["libavformat/avformat.h", "libavformat/avio.h"],
+        );
+
+        // directory listing over the protocols that support it, from FFmpeg 2.8
+        println!(r#"cargo:rustc-check-cfg=cfg(feature, values("has_avio_dir"))"#);
+        if header_declares(&include_paths, "libavformat/avio.h", "avio_open_dir") {
+            println!(r#"cargo:rustc-cfg=feature="has_avio_dir""#);
+            println!("cargo:has_avio_dir=true");
+        }
     }
 
     if env::var("CARGO_FEATURE_AVRESAMPLE").is_ok() {
-        builder = builder.header(search_include(&include_paths, "libavresample/avresample.h"));
-    }
-
-    builder = builder
-        .header(search_include(&include_paths, "libavutil/adler32.h"))
-        .header(search_include(&include_paths, "libavutil/aes.h"))
-        .header(search_include(&include_paths, "libavutil/audio_fifo.h"))
-        .header(search_include(&include_paths, "libavutil/base64.h"))
-        .header(search_include(&include_paths, "libavutil/blowfish.h"))
-        .header(search_include(&include_paths, "libavutil/bprint.h"))
-        .header(search_include(&include_paths, "libavutil/buffer.h"))
-        .header(search_include(&include_paths, "libavutil/camellia.h"))
-        .header(search_include(&include_paths, "libavutil/cast5.h"))
-        .header(search_include(&include_paths, "libavutil/channel_layout.h"))
-        .header(search_include(&include_paths, "libavutil/cpu.h"))
-        .header(search_include(&include_paths, "libavutil/crc.h"))
-        .header(search_include(&include_paths, "libavutil/dict.h"))
-        .header(search_include(&include_paths, "libavutil/display.h"))
-        .header(search_include(&include_paths, "libavutil/downmix_info.h"))
-        .header(search_include(&include_paths, "libavutil/error.h"))
-        .header(search_include(&include_paths, "libavutil/eval.h"))
-        .header(search_include(&include_paths, "libavutil/fifo.h"))
-        .header(search_include(&include_paths, "libavutil/file.h"))
-        .header(search_include(&include_paths, "libavutil/frame.h"))
-        .header(search_include(&include_paths, "libavutil/hash.h"))
-        .header(search_include(&include_paths, "libavutil/hmac.h"))
-        .header(search_include(&include_paths, "libavutil/imgutils.h"))
-        .header(search_include(&include_paths, "libavutil/lfg.h"))
-        .header(search_include(&include_paths, "libavutil/log.h"))
-        .header(search_include(&include_paths, "libavutil/lzo.h"))
-        .header(search_include(&include_paths, "libavutil/macros.h"))
-        .header(search_include(&include_paths, "libavutil/mathematics.h"))
-        .header(search_include(&include_paths, "libavutil/md5.h"))
-        .header(search_include(&include_paths, "libavutil/mem.h"))
-        .header(search_include(&include_paths, "libavutil/motion_vector.h"))
-        .header(search_include(&include_paths, "libavutil/murmur3.h"))
-        .header(search_include(&include_paths, "libavutil/opt.h"))
-        .header(search_include(&include_paths, "libavutil/parseutils.h"))
-        .header(search_include(&include_paths, "libavutil/pixdesc.h"))
-        .header(search_include(&include_paths, "libavutil/pixfmt.h"))
-        .header(search_include(&include_paths, "libavutil/random_seed.h"))
-        .header(search_include(&include_paths, "libavutil/rational.h"))
-        .header(search_include(&include_paths, "libavutil/replaygain.h"))
-        .header(search_include(&include_paths, "libavutil/ripemd.h"))
-        .header(search_include(&include_paths, "libavutil/samplefmt.h"))
-        .header(search_include(&include_paths, "libavutil/sha.h"))
-        .header(search_include(&include_paths, "libavutil/sha512.h"))
-        .header(search_include(&include_paths, "libavutil/stereo3d.h"))
-        .header(search_include(&include_paths, "libavutil/avstring.h"))
-        .header(search_include(&include_paths, "libavutil/threadmessage.h"))
-        .header(search_include(&include_paths, "libavutil/time.h"))
-        .header(search_include(&include_paths, "libavutil/timecode.h"))
-        .header(search_include(&include_paths, "libavutil/twofish.h"))
-        .header(search_include(&include_paths, "libavutil/avutil.h"))
-        .header(search_include(&include_paths, "libavutil/xtea.h"));
+        builder = required_headers(
+            builder,
+            &include_paths,
+            &mut missing_headers,
+            "avresample",
+            &["libavresample/avresample.h"],
+        );
+    }
+
+    builder = required_headers(
+        builder,
+        &include_paths,
+        &mut missing_headers,
+        "avutil",
+        &[
+            "libavutil/adler32.h",
+            "libavutil/aes.h",
+            "libavutil/audio_fifo.h",
+            "libavutil/base64.h",
+            "libavutil/blowfish.h",
+            "libavutil/bprint.h",
+            "libavutil/buffer.h",
+            "libavutil/camellia.h",
+            "libavutil/cast5.h",
+            "libavutil/channel_layout.h",
+            "libavutil/cpu.h",
+            "libavutil/crc.h",
+            "libavutil/dict.h",
+            "libavutil/display.h",
+            "libavutil/downmix_info.h",
+            "libavutil/error.h",
+            "libavutil/eval.h",
+            "libavutil/fifo.h",
+            "libavutil/file.h",
+            "libavutil/frame.h",
+            "libavutil/hash.h",
+            "libavutil/hmac.h",
+            "libavutil/hwcontext.h",
+            "libavutil/imgutils.h",
+            "libavutil/lfg.h",
+            "libavutil/log.h",
+            "libavutil/lzo.h",
+            "libavutil/macros.h",
+            "libavutil/mastering_display_metadata.h",
+            "libavutil/mathematics.h",
+            "libavutil/md5.h",
+            "libavutil/mem.h",
+            "libavutil/motion_vector.h",
+            "libavutil/murmur3.h",
+            "libavutil/opt.h",
+            "libavutil/parseutils.h",
+            "libavutil/pixelutils.h",
+            "libavutil/pixdesc.h",
+            "libavutil/pixfmt.h",
+            "libavutil/random_seed.h",
+            "libavutil/rational.h",
+            "libavutil/replaygain.h",
+            "libavutil/ripemd.h",
+            "libavutil/samplefmt.h",
+            "libavutil/sha.h",
+            "libavutil/sha512.h",
+            "libavutil/stereo3d.h",
+            "libavutil/avstring.h",
+            "libavutil/threadmessage.h",
+            "libavutil/time.h",
+            "libavutil/timecode.h",
+            "libavutil/twofish.h",
+            "libavutil/avutil.h",
+            "libavutil/xtea.h",
+        ],
+    );
+
+    // only available in later versions
+    for header in &[
+        "libavutil/ambient_viewing_environment.h",
+        "libavutil/csp.h",
+        "libavutil/detection_bbox.h",
+        "libavutil/dovi_meta.h",
+        "libavutil/encryption_info.h",
+        "libavutil/hdr_dynamic_metadata.h",
+        "libavutil/spherical.h",
+        "libavutil/tx.h",
+        "libavutil/uuid.h",
+        "libavutil/video_enc_params.h",
+    ] {
+        if let Some(path) = maybe_search_include(&include_paths, header) {
+            builder = builder.header(path);
+        }
+    }
+
+    // the RPU metadata structs were added to dovi_meta.h after the configuration record
+    println!(r#"cargo:rustc-check-cfg=cfg(feature, values("has_dovi_metadata"))"#);
+    if header_declares(&include_paths, "libavutil/dovi_meta.h", "AVDOVIMetadata") {
+        println!(r#"cargo:rustc-cfg=feature="has_dovi_metadata""#);
+        println!("cargo:has_dovi_metadata=true");
+    }
+
+    // replaces avfft.h, which stays bound for the avcodec-avfft feature
+    println!(r#"cargo:rustc-check-cfg=cfg(feature, values("has_tx"))"#);
+    if header_declares(&include_paths, "libavutil/tx.h", "av_tx_init") {
+        println!(r#"cargo:rustc-cfg=feature="has_tx""#);
+        println!("cargo:has_tx=true");
+    }
+
+    println!(r#"cargo:rustc-check-cfg=cfg(feature, values("has_csp"))"#);
+    if header_declares(&include_paths, "libavutil/csp.h", "av_csp_primaries_desc_from_id") {
+        println!(r#"cargo:rustc-cfg=feature="has_csp""#);
+        println!("cargo:has_csp=true");
+    }
+
+    println!(r#"cargo:rustc-check-cfg=cfg(feature, values("has_video_enc_params"))"#);
+    if header_declares(&include_paths, "libavutil/video_enc_params.h", "AVVideoEncParams") {
+        println!(r#"cargo:rustc-cfg=feature="has_video_enc_params""#);
+        println!("cargo:has_video_enc_params=true");
+    }
+
+    println!(r#"cargo:rustc-check-cfg=cfg(feature, values("has_detection_bbox"))"#);
+    if header_declares(&include_paths, "libavutil/detection_bbox.h", "AVDetectionBBoxHeader") {
+        println!(r#"cargo:rustc-cfg=feature="has_detection_bbox""#);
+        println!("cargo:has_detection_bbox=true");
+    }
+
+    // FFmpeg 3.3
+    println!(r#"cargo:rustc-check-cfg=cfg(feature, values("has_spherical"))"#);
+    if header_declares(&include_paths, "libavutil/spherical.h", "av_spherical_alloc") {
+        println!(r#"cargo:rustc-cfg=feature="has_spherical""#);
+        println!("cargo:has_spherical=true");
+    }
+
+    println!(r#"cargo:rustc-check-cfg=cfg(feature, values("has_hdr_dynamic_metadata"))"#);
+    if header_declares(
+        &include_paths,
+        "libavutil/hdr_dynamic_metadata.h",
+        "av_dynamic_hdr_plus_create_side_data",
+    ) {
+        println!(r#"cargo:rustc-cfg=feature="has_hdr_dynamic_metadata""#);
+        println!("cargo:has_hdr_dynamic_metadata=true");
+    }
+
+    println!(r#"cargo:rustc-check-cfg=cfg(feature, values("has_ambient_viewing_environment"))"#);
+    if header_declares(
+        &include_paths,
+        "libavutil/ambient_viewing_environment.h",
+        "AVAmbientViewingEnvironment",
+    ) {
+        println!(r#"cargo:rustc-cfg=feature="has_ambient_viewing_environment""#);
+        println!("cargo:has_ambient_viewing_environment=true");
+    }
+
+    // AVChannelLayout replaced the channel_layout masks in AVFrame and AVCodecContext, both exist
+    // until FF_API_OLD_CHANNEL_LAYOUT compiles the masks out. Backports come with the field, so
+    // the cfg doesn't follow the version
+    println!(r#"cargo:rustc-check-cfg=cfg(feature, values("has_av_channel_layout"))"#);
+    if header_declares(&include_paths, "libavutil/frame.h", "AVChannelLayout ch_layout") {
+        println!(r#"cargo:rustc-cfg=feature="has_av_channel_layout""#);
+        println!("cargo:has_av_channel_layout=true");
+    }
+
+    // AVFifo replaced the AVFifoBuffer API, which FF_API_FIFO_OLD_API compiles out of the library
+    println!(r#"cargo:rustc-check-cfg=cfg(feature, values("has_avfifo2"))"#);
+    if header_declares(&include_paths, "libavutil/fifo.h", "av_fifo_alloc2") {
+        println!(r#"cargo:rustc-cfg=feature="has_avfifo2""#);
+        println!("cargo:has_avfifo2=true");
+    }
+
+    println!(r#"cargo:rustc-check-cfg=cfg(feature, values("has_uuid"))"#);
+    if header_declares(&include_paths, "libavutil/uuid.h", "av_uuid_parse") {
+        println!(r#"cargo:rustc-cfg=feature="has_uuid""#);
+        println!("cargo:has_uuid=true");
+    }
+
+    if env::var("CARGO_FEATURE_HWCONTEXT_D3D11").is_ok() {
+        builder = required_headers(
+            builder,
+            &include_paths,
+            &mut missing_headers,
+            "hwcontext-d3d11",
+            &[
+                "libavutil/hwcontext_d3d11va.h",
+                "libavutil/hwcontext_dxva2.h",
+            ],
+        );
+        builder = builder
+            // the COM interfaces are declared as opaque types in src/avutil instead of
+            // generating the whole Direct3D/DXVA surface
+            .blocklist_type("I(D3D11|Direct3D|DirectXVideo|DXGI).*")
+            .blocklist_type("LPDIRECT3D.*")
+            .blocklist_function("(D3D11|Direct3DCreate9|DXVA2Create).*");
+
+        if env::var("CARGO_FEATURE_AVCODEC").is_ok() {
+            builder = required_headers(
+                builder,
+                &include_paths,
+                &mut missing_headers,
+                "hwcontext-d3d11",
+                &["libavcodec/d3d11va.h", "libavcodec/dxva2.h"],
+            );
+        }
+    }
+
+    if env::var("CARGO_FEATURE_HWCONTEXT_VIDEOTOOLBOX").is_ok() {
+        builder = required_headers(
+            builder,
+            &include_paths,
+            &mut missing_headers,
+            "hwcontext-videotoolbox",
+            &["libavutil/hwcontext_videotoolbox.h"],
+        );
+        builder = builder
+            // declared as opaque pointers in src/avutil to interoperate with core-foundation
+            .blocklist_type("__CVBuffer")
+            .blocklist_type("CV(Buffer|ImageBuffer|PixelBuffer)Ref")
+            .blocklist_type("opaqueCMSampleBuffer")
+            .blocklist_type("CMSampleBufferRef");
+
+        if env::var("CARGO_FEATURE_AVCODEC").is_ok() {
+            builder = required_headers(
+                builder,
+                &include_paths,
+                &mut missing_headers,
+                "hwcontext-videotoolbox",
+                &["libavcodec/videotoolbox.h"],
+            );
+        }
+    }
+
+    // vulkan_core.h declares the whole Vulkan API, only the items of hwcontext_vulkan.h and the
+    // Vulkan types they use are bound. an allowlist applies to all headers of a builder, so they
+    // get one of their own, included at the crate root next to the other bindings
+    if env::var("CARGO_FEATURE_HWCONTEXT_VULKAN").is_ok() {
+        let header = maybe_search_include(&include_paths, "libavutil/hwcontext_vulkan.h")
+            .expect("the `hwcontext-vulkan` feature requires FFmpeg 4.3 or newer");
+
+        bindgen::Builder::default()
+            .clang_args(clang_includes)
+            .clang_arg("-DVK_NO_PROTOTYPES")
+            .header(header)
+            .ctypes_prefix("libc")
+            .use_core()
+            .rustified_enum(".*")
+            .prepend_enum_name(false)
+            .derive_eq(true)
+            .default_macro_constant_type(MacroTypeVariation::Unsigned)
+            .parse_callbacks(Box::new(IntCallbacks))
+            .allowlist_type("AVVulkan.*|AVVk.*")
+            .allowlist_function("av_vk.*")
+            // the FFmpeg types they use, AVPixelFormat and AVHWFramesContext, are in bindings.rs
+            .blocklist_type("AV[^V].*")
+            // declared in src/avutil with the same representation as ash's handles, these are
+            // all the handles the AVVulkan* structs use
+            .blocklist_type("Vk(Instance|PhysicalDevice|Device|Queue|CommandBuffer)(_T)?")
+            .blocklist_type("Vk(Image|DeviceMemory|Semaphore)(_T)?")
+            .generate()
+            .expect("Unable to generate the Vulkan hwcontext bindings")
+            .write_to_file(output().join("hwcontext_vulkan.rs"))
+            .expect("Couldn't write the Vulkan hwcontext bindings!");
+    }
+
+    if env::var("CARGO_FEATURE_QSV").is_ok() {
+        builder = required_headers(
+            builder,
+            &include_paths,
+            &mut missing_headers,
+            "qsv",
+            &["libavutil/hwcontext_qsv.h"],
+        );
+        builder = builder
+            .opaque_type("_mfxSession")
+            .opaque_type("mfxFrameSurface1");
+
+        if env::var("CARGO_FEATURE_AVCODEC").is_ok() {
+            builder = required_headers(
+                builder,
+                &include_paths,
+                &mut missing_headers,
+                "qsv",
+                &["libavcodec/qsv.h"],
+            );
+        }
+    }
+
+    // deprecated pieces of vdpau.h are already compiled out by the FF_API_*VDPAU macros
+    if env::var("CARGO_FEATURE_VDPAU").is_ok() {
+        builder = required_headers(
+            builder,
+            &include_paths,
+            &mut missing_headers,
+            "vdpau",
+            &["libavutil/hwcontext_vdpau.h"],
+        );
+        builder = builder.opaque_type("VdpPictureInfo.*");
+
+        if env::var("CARGO_FEATURE_AVCODEC").is_ok() {
+            builder = required_headers(
+                builder,
+                &include_paths,
+                &mut missing_headers,
+                "vdpau",
+                &["libavcodec/vdpau.h"],
+            );
+        }
+    }
 
     if env::var("CARGO_FEATURE_POSTPROC").is_ok() {
-        builder = builder.header(search_include(&include_paths, "libpostproc/postprocess.h"));
+        builder = required_headers(
+            builder,
+            &include_paths,
+            &mut missing_headers,
+            "postproc",
+            &["libpostproc/postprocess.h"],
+        );
     }
 
     if env::var("CARGO_FEATURE_SWRESAMPLE").is_ok() {
-        builder = builder.header(search_include(&include_paths, "libswresample/swresample.h"));
+        builder = required_headers(
+            builder,
+            &include_paths,
+            &mut missing_headers,
+            "swresample",
+            &["libswresample/swresample.h"],
+        );
     }
 
     if env::var("CARGO_FEATURE_SWSCALE").is_ok() {
-        builder = builder.header(search_include(&include_paths, "libswscale/swscale.h"));
+        builder = required_headers(
+            builder,
+            &include_paths,
+            &mut missing_headers,
+            "swscale",
+            &["libswscale/swscale.h"],
+        );
+    }
+
+    if !missing_headers.is_empty() {
+        if env::var("FFMPEG_ALLOW_MISSING_HEADERS").is_ok_and(|v| v == "1") {
+            for header in &missing_headers {
+                println!(
+                    "cargo:warning={} not found, its items are left out of the bindings",
+                    header
+                );
+            }
+        } else {
+            panic!(
+                "the following FFmpeg headers were not found in {:?}:\n    {}\n\
+                 set FFMPEG_ALLOW_MISSING_HEADERS=1 to generate the bindings without them",
+                include_paths,
+                missing_headers.join("\n    ")
+            );
+        }
     }
 
+    let dlopen_builder = if dlopen { Some(builder.clone()) } else { None };
+
     // Finish the builder and generate the bindings.
+    let started = Instant::now();
     let bindings = builder.generate()
     // Unwrap the Result and panic on failure.
     .expect("Unable to generate bindings");
+    let bindgen_time = started.elapsed();
+
+    let ((ff_api, linked_license), check_time) = checks.join();
+
+    report(
+        "ff_api",
+        format!(
+            "{{{}}}",
+            ff_api
+                .iter()
+                .map(|&(name, enabled, defined)| format!(
+                    "{}: {{\"enabled\": {}, \"defined\": {}}}",
+                    json::string(name),
+                    enabled,
+                    defined
+                ))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    );
+
+    check_license(linked_license);
+
+    // how long check.c and bindgen took and how long the build script waited for both, to
+    // compare FFMPEG_PARALLEL=1 with a sequential build
+    report(
+        "timings",
+        format!(
+            "{{\"check_features\": {:.3}, \"bindgen\": {:.3}, \"checks_and_bindgen\": {:.3}}}",
+            check_time.as_secs_f64(),
+            bindgen_time.as_secs_f64(),
+            checks_started.elapsed().as_secs_f64()
+        ),
+    );
+
+    let compiled_out = blocklist::compiled_out(&ff_api);
+    let bindings = if compiled_out.is_empty() {
+        bindings.to_string()
+    } else {
+        let blocked = Regex::new(&format!("^({})$", compiled_out.join("|"))).unwrap();
+        blocklist::blocklist(&bindings.to_string(), |name| blocked.is_match(name))
+    };
+
+    if let Some(mut builder) = dlopen_builder {
+        for item in &compiled_out {
+            builder = builder.blocklist_item(*item);
+        }
+        write_dlopen(&include_paths, &builder);
+    }
+
+    if env::var("CARGO_FEATURE_AVCODEC").is_ok() {
+        check_avpicture_fields(&ff_api, &bindings);
+    }
+
+    // the symbols of the APIs that go away with the next major are marked #[deprecated], so
+    // downstream crates hear of it before their build breaks
+    let code = deprecations::deprecate(&bindings, |name| {
+        ff_api
+            .iter()
+            .any(|&(ff_api, enabled, defined)| ff_api == name && enabled && defined)
+    });
 
     // Write the bindings to the $OUT_DIR/bindings.rs file.
-    bindings
-        .write_to_file(output().join("bindings.rs"))
-        .expect("Couldn't write bindings!");
+    fs::write(output().join("bindings.rs"), &code).expect("Couldn't write bindings!");
+
+    // FFMPEG_VERIFY_BINDINGS=1 compares the generated items with snapshots/ffmpeg-<major>.txt,
+    // FFMPEG_VERIFY_BINDINGS=update writes a new one for cargo xtask snapshot
+    if let Ok(mode) = env::var("FFMPEG_VERIFY_BINDINGS") {
+        verify_bindings(&include_paths, &bindings, mode == "update");
+    }
+
+    // FFMPEG_DUMP_BINDINGS=update writes the whole bindings for generated/ffmpeg-<major>.rs,
+    // which cargo xtask generate-bindings copies, FFMPEG_DUMP_BINDINGS=check fails when they
+    // differ from it
+    if let Ok(mode) = env::var("FFMPEG_DUMP_BINDINGS") {
+        dump_bindings(&include_paths, &code, mode == "update");
+    }
+
+    inline_shims.join();
+
+    write_build_report();
 }
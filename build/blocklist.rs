@@ -0,0 +1,107 @@
+//! Takes items out of generated bindings, for the blocklists that have to wait for check.c.
+
+/// The names of the items the headers keep declaring after an `FF_API_*` macro compiled them out
+/// of the libraries, as regexes like those of bindgen's blocklists. `ff_api` is the value of each
+/// macro and whether it's defined at all, as check.c reports them.
+pub fn compiled_out(ff_api: &[(&str, bool, bool)]) -> Vec<&'static str> {
+    let mut items = Vec::new();
+    for &(name, enabled, defined) in ff_api {
+        if enabled || !defined {
+            continue;
+        }
+
+        match name {
+            "FF_API_DEVICE_CAPABILITIES" => items.extend(&[
+                "AVDeviceCapabilitiesQuery",
+                "av_device_capabilities",
+                "avdevice_capabilities_(create|free)",
+            ]),
+            // AVFifo replaced the AVFifoBuffer API
+            "FF_API_FIFO_OLD_API" => items.extend(&[
+                "AVFifoBuffer",
+                "av_fifo_(alloc|alloc_array|free|freep|reset|size|space|realloc2|grow|drain)",
+                "av_fifo_generic_(peek|peek_at|read|write)",
+            ]),
+            _ => {}
+        }
+    }
+    items
+}
+
+/// `bindings` without the items whose name `blocked` matches: the structs, unions, enums, types
+/// and constants along with their impls and layout tests, and the functions and statics of the
+/// `extern "C"` blocks.
+pub fn blocklist<F: Fn(&str) -> bool>(bindings: &str, blocked: F) -> String {
+    let mut code = String::with_capacity(bindings.len());
+
+    for item in items(bindings) {
+        if !item_name(item).is_some_and(&blocked) {
+            code.push_str(item);
+        }
+    }
+    code
+}
+
+// the top-level items of rustfmt-formatted bindings with their attributes, each ending in a
+// newline unless it's the last line of `bindings`
+fn items(bindings: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut start = 0;
+    let mut position = 0;
+
+    for line in bindings.split_inclusive('\n') {
+        position += line.len();
+        let trimmed = line.trim_end();
+        // a closing brace or a one-line item at the start of the line ends the item, attributes
+        // belong to the one that follows them
+        let ends_item = trimmed == "}"
+            || (!line.starts_with(char::is_whitespace)
+                && !trimmed.starts_with("#[")
+                && trimmed.ends_with(';'));
+        if ends_item {
+            items.push(&bindings[start..position]);
+            start = position;
+        }
+    }
+    if start < bindings.len() {
+        items.push(&bindings[start..]);
+    }
+    items
+}
+
+// the name an item is blocklisted by, the function or static for an `extern "C"` block
+fn item_name(item: &str) -> Option<&str> {
+    let declaration = item.lines().find(|line| !line.trim_start().starts_with("#["))?;
+
+    if declaration.starts_with("extern ") {
+        return item.lines().skip(1).find_map(|line| {
+            let line = line.trim_start();
+            ["pub fn ", "pub static mut ", "pub static "]
+                .iter()
+                .find_map(|prefix| line.strip_prefix(prefix))
+                .map(identifier)
+        });
+    }
+
+    let prefixes = [
+        "pub struct ",
+        "pub union ",
+        "pub enum ",
+        "pub type ",
+        "pub const ",
+        "impl Default for ",
+        "impl ",
+        "fn bindgen_test_layout_",
+    ];
+    prefixes
+        .iter()
+        .find_map(|prefix| declaration.strip_prefix(prefix))
+        .map(identifier)
+}
+
+fn identifier(code: &str) -> &str {
+    let end = code
+        .find(|c: char| !c.is_alphanumeric() && c != '_')
+        .unwrap_or(code.len());
+    &code[..end]
+}
@@ -0,0 +1,98 @@
+//! The pkg-config packages of the `build-*` features, checked before configure runs so a missing
+//! library is reported by name instead of as a configure failure.
+
+// the pkg-config packages the `build-*` features need and the FFmpeg release that first supports
+// them, (0, 0) when every supported release does
+const PACKAGES: &[(&str, &str, (u8, u8))] = &[
+    ("build-lib-dav1d", "dav1d", (0, 0)),
+    ("build-lib-aom", "aom", (0, 0)),
+    ("build-lib-rav1e", "rav1e", (4, 3)),
+    ("build-lib-svtav1", "SvtAv1Enc", (4, 4)),
+    ("build-lib-vmaf", "libvmaf", (0, 0)),
+    ("build-lib-zimg", "zimg", (0, 0)),
+    ("build-lib-srt", "srt", (0, 0)),
+    ("build-lib-rist", "librist", (4, 4)),
+    ("build-lib-rtmp", "librtmp", (0, 0)),
+    ("build-lib-zmq", "libzmq", (0, 0)),
+    ("build-lib-xml2", "libxml-2.0", (0, 0)),
+    ("build-lib-bluray", "libbluray", (0, 0)),
+    ("build-lib-vidstab", "vidstab", (0, 0)),
+    ("build-lib-rubberband", "rubberband", (0, 0)),
+    ("build-lib-codec2", "codec2", (0, 0)),
+    ("build-lib-gme", "libgme", (0, 0)),
+    ("build-lib-openmpt", "libopenmpt", (0, 0)),
+    ("build-lib-kvazaar", "kvazaar", (0, 0)),
+    ("build-lib-placebo", "libplacebo", (5, 1)),
+    ("build-lib-placebo", "vulkan", (5, 1)),
+    ("build-lib-jxl", "libjxl", (5, 1)),
+    ("build-lib-jxl", "libjxl_threads", (5, 1)),
+    ("build-indev-alsa", "alsa", (0, 0)),
+    ("build-indev-pulse", "libpulse", (0, 0)),
+    ("build-libv4l2", "libv4l2", (0, 0)),
+    ("build-xcb", "xcb", (0, 0)),
+    ("build-xcb", "xcb-shm", (0, 0)),
+    ("build-xcb", "xcb-xfixes", (0, 0)),
+    ("build-xcb", "xcb-shape", (0, 0)),
+    ("build-vaapi", "libva", (0, 0)),
+    ("build-vaapi", "libva-drm", (0, 0)),
+    ("build-vdpau", "vdpau", (0, 0)),
+    ("build-vulkan", "vulkan", (4, 3)),
+    ("build-lib-shaderc", "shaderc", (5, 0)),
+    // configure leaves out ffplay without complaint when SDL2 is missing
+    ("ffplay", "sdl2", (0, 0)),
+];
+
+/// The packages of every `build-*` feature for `target`, with `vpl` the package of oneVPL or
+/// of its predecessor libmfx, whichever the FFmpeg release uses.
+pub fn packages(target: &str, vpl: &'static str) -> Vec<(&'static str, &'static str, (u8, u8))> {
+    let mut packages = PACKAGES.to_vec();
+    packages.push(("build-vpl", vpl, (0, 0)));
+
+    // zlib, bzip2 and OpenCL are part of the macOS SDK, bzip2 rarely ships a .pc file elsewhere
+    if !target.contains("apple") && !target.contains("windows") {
+        packages.push(("build-zlib", "zlib", (0, 0)));
+        packages.push(("build-lzma", "liblzma", (0, 0)));
+        packages.push(("build-opencl", "OpenCL", (0, 0)));
+    }
+    packages
+}
+
+/// Why the features `enabled` accepts can't be built with FFmpeg `version`, without asking
+/// pkg-config: the features that are too new for the release and the combinations configure
+/// can't do anything with.
+pub fn unsupported<F>(
+    packages: &[(&str, &str, (u8, u8))],
+    version: (u8, u8),
+    enabled: F,
+) -> Vec<String>
+where
+    F: Fn(&str) -> bool,
+{
+    let mut problems = Vec::new();
+    for &(feature, _, (major, minor)) in packages {
+        if !enabled(feature) || version >= (major, minor) {
+            continue;
+        }
+        let problem = format!(
+            "the `{}` feature requires FFmpeg {}.{} or newer, not {}.{}",
+            feature, major, minor, version.0, version.1
+        );
+        if !problems.contains(&problem) {
+            problems.push(problem);
+        }
+    }
+
+    // the Vulkan filters compile their shaders at runtime
+    if enabled("build-vulkan")
+        && enabled("avfilter")
+        && !enabled("build-lib-shaderc")
+        && !enabled("build-lib-glslang")
+    {
+        problems.push(
+            "the `build-vulkan` feature needs `build-lib-shaderc` or `build-lib-glslang` for the \
+             Vulkan filters"
+                .into(),
+        );
+    }
+    problems
+}
@@ -0,0 +1,31 @@
+//! The `[key]value` lines check.c prints for everything it looks up.
+
+/// The text after `[key]` up to the end of its line, `None` when check.c didn't print it.
+pub fn value<'a>(stdout: &'a str, key: &str) -> Option<&'a str> {
+    let key = format!("[{}]", key);
+    stdout
+        .find(&key)
+        .and_then(|pos| stdout[pos + key.len()..].lines().next())
+        .map(|value| value.trim())
+}
+
+/// A number check.c printed, like a version or the size of a struct.
+pub fn number(stdout: &str, key: &str) -> Option<u64> {
+    value(stdout, key).and_then(|value| value.parse().ok())
+}
+
+/// A condition check.c printed as 0 or 1, like `[avcodec_version_greater_than_58_10]1`.
+pub fn flag(stdout: &str, key: &str) -> Option<bool> {
+    value(stdout, key).and_then(|value| value.chars().next()).map(|value| value == '1')
+}
+
+/// The value of an `FF_API_*` macro and whether the headers define it at all, printed as two
+/// digits: `[FF_API_AVPICTURE]11` is a defined macro that's 1, `00` one the headers don't
+/// know (any more).
+pub fn ff_api_macro(stdout: &str, name: &str) -> Option<(bool, bool)> {
+    let mut digits = value(stdout, name)?.chars();
+    match (digits.next(), digits.next()) {
+        (Some(enabled), Some(defined)) => Some((enabled == '1', defined == '1')),
+        _ => None,
+    }
+}
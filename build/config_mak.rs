@@ -0,0 +1,19 @@
+//! The configuration FFmpeg's ffbuild/config.mak records.
+
+use std::collections::BTreeSet;
+
+/// Whether `config_mak` was generated by configure with every argument of `args`, one per line.
+/// FFmpeg records them as FFMPEG_CONFIGURATION, quoting those with spaces, which are skipped.
+pub fn configured_with(config_mak: &str, args: &str) -> bool {
+    let configured: BTreeSet<&str> = match config_mak
+        .lines()
+        .find_map(|line| line.strip_prefix("FFMPEG_CONFIGURATION="))
+    {
+        Some(configuration) => configuration.split_whitespace().collect(),
+        None => return false,
+    };
+
+    args.lines()
+        .filter(|arg| !arg.contains(' '))
+        .all(|arg| configured.contains(arg))
+}
@@ -0,0 +1,153 @@
+//! The configure flags that follow from the cargo features alone.
+
+/// Features that turn a part of FFmpeg on or off, `--enable-<name>` with the feature and
+/// `--disable-<name>` without it.
+pub const SWITCHES: &[(&str, &str)] = &[
+    // the binary using ffmpeg-sys must comply with GPL
+    ("build-license-gpl", "gpl"),
+    // the binary using ffmpeg-sys must comply with (L)GPLv3
+    ("build-license-version3", "version3"),
+    // the binary using ffmpeg-sys cannot be redistributed
+    ("build-license-nonfree", "nonfree"),
+    // configure building libraries based on features
+    ("avcodec", "avcodec"),
+    ("avdevice", "avdevice"),
+    ("avfilter", "avfilter"),
+    ("avformat", "avformat"),
+    ("avresample", "avresample"),
+    ("postproc", "postproc"),
+    ("swresample", "swresample"),
+    ("swscale", "swscale"),
+    // configure building programs based on features
+    ("ffmpeg", "ffmpeg"),
+    ("ffplay", "ffplay"),
+    ("ffprobe", "ffprobe"),
+    // configure compression libraries, these are autodetected otherwise
+    ("build-zlib", "zlib"),
+    ("build-bzlib", "bzlib"),
+    ("build-lzma", "lzma"),
+];
+
+/// Features that enable parts of FFmpeg that are off or autodetected otherwise, as the names of
+/// their `--enable-<name>` flags.
+pub const ENABLES: &[(&str, &[&str])] = &[
+    // configure external SSL libraries
+    ("build-lib-gnutls", &["gnutls"]),
+    ("build-lib-openssl", &["openssl"]),
+    ("build-lib-schannel", &["schannel"]),
+    ("build-lib-securetransport", &["securetransport"]),
+    // configure external filters
+    ("build-lib-fontconfig", &["fontconfig"]),
+    ("build-lib-frei0r", &["frei0r"]),
+    ("build-lib-ladspa", &["ladspa"]),
+    ("build-lib-ass", &["libass"]),
+    ("build-lib-freetype", &["libfreetype"]),
+    ("build-lib-opencv", &["libopencv"]),
+    ("build-lib-vmaf", &["libvmaf"]),
+    ("build-lib-zimg", &["libzimg"]),
+    ("build-lib-zmq", &["libzmq"]),
+    ("build-lib-vidstab", &["libvidstab"]),
+    ("build-lib-rubberband", &["librubberband"]),
+    // the libplacebo filter runs on top of FFmpeg's Vulkan hwcontext
+    ("build-lib-placebo", &["libplacebo", "vulkan"]),
+    // configure external encoders/decoders
+    ("build-lib-aacplus", &["libaacplus"]),
+    ("build-lib-celt", &["libcelt"]),
+    ("build-lib-codec2", &["libcodec2"]),
+    ("build-lib-dcadec", &["libdcadec"]),
+    ("build-lib-dav1d", &["libdav1d"]),
+    ("build-lib-aom", &["libaom"]),
+    ("build-lib-rav1e", &["librav1e"]),
+    ("build-lib-svtav1", &["libsvtav1"]),
+    ("build-lib-faac", &["libfaac"]),
+    ("build-lib-fdk-aac", &["libfdk-aac"]),
+    ("build-lib-gme", &["libgme"]),
+    ("build-lib-gsm", &["libgsm"]),
+    ("build-lib-jxl", &["libjxl"]),
+    ("build-lib-ilbc", &["libilbc"]),
+    ("build-lib-kvazaar", &["libkvazaar"]),
+    ("build-lib-mp3lame", &["libmp3lame"]),
+    ("build-lib-opencore-amrnb", &["libopencore-amrnb"]),
+    ("build-lib-opencore-amrwb", &["libopencore-amrwb"]),
+    ("build-lib-openh264", &["libopenh264"]),
+    ("build-lib-openjpeg", &["libopenjpeg"]),
+    ("build-lib-openmpt", &["libopenmpt"]),
+    ("build-lib-opus", &["libopus"]),
+    ("build-lib-schroedinger", &["libschroedinger"]),
+    ("build-lib-shine", &["libshine"]),
+    ("build-lib-snappy", &["libsnappy"]),
+    ("build-lib-speex", &["libspeex"]),
+    ("build-lib-stagefright-h264", &["libstagefright-h264"]),
+    ("build-lib-theora", &["libtheora"]),
+    ("build-lib-twolame", &["libtwolame"]),
+    ("build-lib-utvideo", &["libutvideo"]),
+    ("build-lib-vo-aacenc", &["libvo-aacenc"]),
+    ("build-lib-vo-amrwbenc", &["libvo-amrwbenc"]),
+    ("build-lib-vorbis", &["libvorbis"]),
+    ("build-lib-vpx", &["libvpx"]),
+    ("build-lib-wavpack", &["libwavpack"]),
+    ("build-lib-webp", &["libwebp"]),
+    ("build-lib-x264", &["libx264"]),
+    ("build-lib-x265", &["libx265"]),
+    ("build-lib-avs", &["libavs"]),
+    ("build-lib-xvid", &["libxvid"]),
+    // other external libraries
+    ("build-nvenc", &["nvenc"]),
+    ("build-nvdec", &["nvdec"]),
+    ("build-cuvid", &["cuvid"]),
+    ("build-vaapi", &["vaapi"]),
+    ("build-vdpau", &["vdpau"]),
+    ("build-d3d11va", &["d3d11va"]),
+    ("build-dxva2", &["dxva2"]),
+    ("build-videotoolbox", &["videotoolbox"]),
+    ("build-audiotoolbox", &["audiotoolbox"]),
+    ("build-opencl", &["opencl"]),
+    ("build-vulkan", &["vulkan"]),
+    ("build-lib-shaderc", &["libshaderc"]),
+    ("build-lib-glslang", &["libglslang"]),
+    // configure external protocols
+    ("build-lib-smbclient", &["libsmbclient"]),
+    ("build-lib-ssh", &["libssh"]),
+    ("build-lib-srt", &["libsrt"]),
+    ("build-lib-rist", &["librist"]),
+    ("build-lib-rtmp", &["librtmp"]),
+    ("build-lib-bluray", &["libbluray"]),
+    // configure external parsers
+    ("build-lib-xml2", &["libxml2"]),
+    // configure input devices
+    ("build-indev-alsa", &["alsa"]),
+    ("build-indev-pulse", &["libpulse"]),
+    ("build-libv4l2", &["libv4l2"]),
+    ("build-xcb", &["libxcb", "libxcb-shm", "libxcb-xfixes", "libxcb-shape"]),
+    // configure misc build options
+    ("build-pic", &["pic"]),
+];
+
+/// Raspberry Pi acceleration, only enabled on ARM targets. Feature sets are often shared with
+/// other targets.
+pub const RASPBERRY_PI: &[(&str, &[&str])] = &[
+    ("build-omx", &["omx"]),
+    ("build-omx-rpi", &["omx-rpi"]),
+    ("build-mmal", &["mmal"]),
+    ("build-v4l2-m2m", &["v4l2-m2m"]),
+];
+
+/// The flags of [`SWITCHES`] and [`ENABLES`] for the features `enabled` says are on.
+pub fn feature_flags<F: Fn(&str) -> bool>(enabled: F) -> Vec<String> {
+    let mut flags = Vec::new();
+    for &(feature, name) in SWITCHES {
+        let switch = if enabled(feature) { "enable" } else { "disable" };
+        flags.push(format!("--{}-{}", switch, name));
+    }
+    flags.extend(enable_flags(ENABLES, enabled));
+    flags
+}
+
+/// The `--enable-<name>` flags of the features of `table` that `enabled` says are on.
+pub fn enable_flags<F: Fn(&str) -> bool>(table: &[(&str, &[&str])], enabled: F) -> Vec<String> {
+    table
+        .iter()
+        .filter(|&&(feature, _)| enabled(feature))
+        .flat_map(|&(_, names)| names.iter().map(|name| format!("--enable-{}", name)))
+        .collect()
+}
@@ -0,0 +1,157 @@
+//! `#[deprecated]` for the bindings that only exist while an `FF_API_*` macro is 1.
+
+use std::cmp::Reverse;
+
+/// Symbols of the bindings and the `FF_API_*` macro whose deprecation window they live in.
+///
+/// `Struct::field` names a field, anything else a function or struct. Symbols missing from the
+/// bindings are skipped, not every FFmpeg version or configuration has all of them.
+pub const DEPRECATIONS: &[(&str, &str)] = &[
+    // avutil
+    ("av_fifo_alloc", "FF_API_FIFO_OLD_API"),
+    ("av_fifo_alloc_array", "FF_API_FIFO_OLD_API"),
+    ("av_fifo_free", "FF_API_FIFO_OLD_API"),
+    ("av_fifo_freep", "FF_API_FIFO_OLD_API"),
+    ("av_fifo_reset", "FF_API_FIFO_OLD_API"),
+    ("av_fifo_size", "FF_API_FIFO_OLD_API"),
+    ("av_fifo_space", "FF_API_FIFO_OLD_API"),
+    ("av_fifo_generic_peek_at", "FF_API_FIFO_OLD_API"),
+    ("av_fifo_generic_peek", "FF_API_FIFO_OLD_API"),
+    ("av_fifo_generic_read", "FF_API_FIFO_OLD_API"),
+    ("av_fifo_generic_write", "FF_API_FIFO_OLD_API"),
+    ("av_fifo_realloc2", "FF_API_FIFO_OLD_API"),
+    ("av_fifo_grow", "FF_API_FIFO_OLD_API"),
+    ("av_fifo_drain", "FF_API_FIFO_OLD_API"),
+    ("av_get_channel_layout", "FF_API_OLD_CHANNEL_LAYOUT"),
+    ("av_get_channel_name", "FF_API_OLD_CHANNEL_LAYOUT"),
+    ("av_get_channel_layout_string", "FF_API_OLD_CHANNEL_LAYOUT"),
+    ("av_get_channel_layout_nb_channels", "FF_API_OLD_CHANNEL_LAYOUT"),
+    ("av_get_default_channel_layout", "FF_API_OLD_CHANNEL_LAYOUT"),
+    ("AVFrame::channel_layout", "FF_API_OLD_CHANNEL_LAYOUT"),
+    ("AVFrame::channels", "FF_API_OLD_CHANNEL_LAYOUT"),
+    ("AVFrame::pkt_pts", "FF_API_PKT_PTS"),
+    ("AVFrame::pkt_duration", "FF_API_PKT_DURATION"),
+    ("AVFrame::pkt_pos", "FF_API_FRAME_PKT"),
+    ("AVFrame::pkt_size", "FF_API_FRAME_PKT"),
+    ("AVFrame::interlaced_frame", "FF_API_INTERLACED_FRAME"),
+    ("AVFrame::top_field_first", "FF_API_INTERLACED_FRAME"),
+    ("AVFrame::key_frame", "FF_API_FRAME_KEY"),
+    ("AVFrame::coded_picture_number", "FF_API_FRAME_PICTURE_NUMBER"),
+    ("AVFrame::display_picture_number", "FF_API_FRAME_PICTURE_NUMBER"),
+    ("AVFrame::reordered_opaque", "FF_API_REORDERED_OPAQUE"),
+    // avcodec
+    ("AVPicture", "FF_API_AVPICTURE"),
+    ("avpicture_alloc", "FF_API_AVPICTURE"),
+    ("avpicture_free", "FF_API_AVPICTURE"),
+    ("avpicture_fill", "FF_API_AVPICTURE"),
+    ("avpicture_layout", "FF_API_AVPICTURE"),
+    ("avpicture_get_size", "FF_API_AVPICTURE"),
+    ("av_picture_copy", "FF_API_AVPICTURE"),
+    ("av_picture_crop", "FF_API_AVPICTURE"),
+    ("av_picture_pad", "FF_API_AVPICTURE"),
+    ("AVSubtitleRect::pict", "FF_API_AVPICTURE"),
+    ("av_init_packet", "FF_API_INIT_PACKET"),
+    ("avcodec_register_all", "FF_API_NEXT"),
+    ("av_codec_next", "FF_API_NEXT"),
+    ("av_parser_next", "FF_API_NEXT"),
+    ("av_register_codec_parser", "FF_API_NEXT"),
+    ("av_register_all", "FF_API_NEXT"),
+    ("av_iformat_next", "FF_API_NEXT"),
+    ("av_oformat_next", "FF_API_NEXT"),
+    ("avfilter_register_all", "FF_API_NEXT"),
+    ("avfilter_next", "FF_API_NEXT"),
+    ("AVBitStreamFilterContext", "FF_API_OLD_BSF"),
+    ("av_register_bitstream_filter", "FF_API_OLD_BSF"),
+    ("av_bitstream_filter_init", "FF_API_OLD_BSF"),
+    ("av_bitstream_filter_filter", "FF_API_OLD_BSF"),
+    ("av_bitstream_filter_close", "FF_API_OLD_BSF"),
+    ("av_bitstream_filter_next", "FF_API_OLD_BSF"),
+    ("avcodec_decode_audio4", "FF_API_OLD_ENCDEC"),
+    ("avcodec_decode_video2", "FF_API_OLD_ENCDEC"),
+    ("avcodec_encode_audio2", "FF_API_OLD_ENCDEC"),
+    ("avcodec_encode_video2", "FF_API_OLD_ENCDEC"),
+    ("avcodec_copy_context", "FF_API_COPY_CONTEXT"),
+    ("avcodec_get_context_defaults3", "FF_API_GET_CONTEXT_DEFAULTS"),
+    ("avcodec_close", "FF_API_AVCODEC_CLOSE"),
+    ("AVCodecContext::channel_layout", "FF_API_OLD_CHANNEL_LAYOUT"),
+    ("AVCodecContext::channels", "FF_API_OLD_CHANNEL_LAYOUT"),
+    ("AVCodecContext::request_channel_layout", "FF_API_OLD_CHANNEL_LAYOUT"),
+    ("AVCodecContext::ticks_per_frame", "FF_API_TICKS_PER_FRAME"),
+    ("AVCodecContext::reordered_opaque", "FF_API_REORDERED_OPAQUE"),
+    // avformat
+    ("AVStream::codec", "FF_API_LAVF_AVCTX"),
+    // avdevice
+    ("AVDeviceCapabilitiesQuery", "FF_API_DEVICE_CAPABILITIES"),
+    ("avdevice_capabilities_create", "FF_API_DEVICE_CAPABILITIES"),
+    ("avdevice_capabilities_free", "FF_API_DEVICE_CAPABILITIES"),
+];
+
+/// `bindings` with `#[deprecated]` attached to the symbols of [`DEPRECATIONS`] whose macro
+/// `active` says is in its deprecation window, that is 1 and going away with the next major.
+pub fn deprecate<F: Fn(&str) -> bool>(bindings: &str, active: F) -> String {
+    let mut lines: Vec<&str> = bindings.lines().collect();
+    // (line index, indentation, macro), inserted from the back so the indices stay valid
+    let mut attributes: Vec<(usize, &str, &str)> = Vec::new();
+
+    for &(symbol, ff_api) in DEPRECATIONS {
+        if !active(ff_api) {
+            continue;
+        }
+
+        let found = match symbol.find("::") {
+            Some(separator) => field_line(&lines, &symbol[..separator], &symbol[separator + 2..]),
+            None => item_line(&lines, symbol),
+        };
+
+        if let Some(index) = found {
+            let line = lines[index];
+            let indentation = &line[..line.len() - line.trim_start().len()];
+            attributes.push((index, indentation, ff_api));
+        }
+    }
+
+    attributes.sort_by_key(|&(index, _, _)| Reverse(index));
+
+    let notes: Vec<String> = attributes
+        .iter()
+        .map(|&(_, indentation, ff_api)| {
+            format!(
+                "{}#[deprecated(note = \"removed when {} elapses; see ffmpeg doc/APIchanges\")]",
+                indentation, ff_api
+            )
+        })
+        .collect();
+
+    for (&(index, _, _), note) in attributes.iter().zip(&notes) {
+        lines.insert(index, note);
+    }
+
+    let mut code = lines.join("\n");
+    if bindings.ends_with('\n') {
+        code.push('\n');
+    }
+    code
+}
+
+// the declaration of a function or struct, the first line of the item after its other
+// attributes, which keeps the derives and reprs bindgen writes above it together
+fn item_line(lines: &[&str], name: &str) -> Option<usize> {
+    let function = format!("pub fn {}(", name);
+    let structure = format!("pub struct {} {{", name);
+
+    lines.iter().position(|line| {
+        let line = line.trim_start();
+        line.starts_with(&function) || line.starts_with(&structure)
+    })
+}
+
+fn field_line(lines: &[&str], structure: &str, field: &str) -> Option<usize> {
+    let start = item_line(lines, structure)?;
+    let field = format!("pub {}:", field);
+
+    lines[start + 1..]
+        .iter()
+        .take_while(|line| !line.starts_with('}'))
+        .position(|line| line.trim_start().starts_with(&field))
+        .map(|index| start + 1 + index)
+}
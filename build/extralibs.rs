@@ -0,0 +1,95 @@
+//! The EXTRALIBS of ffbuild/config.mak and the Libs.private of FFmpeg's .pc files.
+
+/// What the EXTRALIBS lines ask the linker for, in the order they're first mentioned.
+#[derive(Debug, Default, PartialEq)]
+pub struct ExtraLibs {
+    pub libs: Vec<String>,
+    pub link_paths: Vec<String>,
+    pub frameworks: Vec<String>,
+}
+
+fn push_unique(list: &mut Vec<String>, value: &str) {
+    if !list.iter().any(|existing| existing == value) {
+        list.push(value.to_owned());
+    }
+}
+
+/// Collects the flags of the bare `EXTRALIBS` line and of the `EXTRALIBS-<lib>` lines of the
+/// libraries `linked` accepts. avutil's line is always taken, every other library depends on it.
+/// The lines of the programs (`EXTRALIBS-ffplay` and so on) are never taken.
+pub fn parse_extralibs<F>(config_mak: &str, target: &str, linked: F) -> ExtraLibs
+where
+    F: Fn(&str) -> bool,
+{
+    let mut extra = ExtraLibs::default();
+
+    for line in config_mak.lines() {
+        if !line.starts_with("EXTRALIBS") {
+            continue;
+        }
+        let (key, linker_args) = line.split_once('=').unwrap_or((line, ""));
+
+        if let Some((_, lib)) = key.split_once('-') {
+            if lib != "avutil" && !linked(lib) {
+                continue;
+            }
+        }
+
+        add_linker_args(&mut extra, linker_args, target);
+    }
+
+    extra
+}
+
+/// Collects the flags of the `Libs.private` line of an FFmpeg library's .pc file, the libraries
+/// it needs when linked statically.
+pub fn parse_libs_private(pc: &str, target: &str) -> ExtraLibs {
+    let mut extra = ExtraLibs::default();
+
+    for line in pc.lines() {
+        if let Some(linker_args) = line.strip_prefix("Libs.private:") {
+            add_linker_args(&mut extra, linker_args, target);
+        }
+    }
+
+    extra
+}
+
+/// Adds the libraries, link paths and frameworks of `other` that `extra` doesn't have yet.
+pub fn merge(extra: &mut ExtraLibs, other: ExtraLibs) {
+    for lib in &other.libs {
+        push_unique(&mut extra.libs, lib);
+    }
+    for path in &other.link_paths {
+        push_unique(&mut extra.link_paths, path);
+    }
+    for framework in &other.frameworks {
+        push_unique(&mut extra.frameworks, framework);
+    }
+}
+
+fn add_linker_args(extra: &mut ExtraLibs, linker_args: &str, target: &str) {
+    let flags: Vec<_> = linker_args.split_whitespace().collect();
+    for (i, &flag) in flags.iter().enumerate() {
+        if let Some(path) = flag.strip_prefix("-L") {
+            // dependency chains from pkg-config may point into their own prefixes
+            push_unique(&mut extra.link_paths, path);
+        } else if let Some(lib) = flag.strip_prefix("-l") {
+            push_unique(&mut extra.libs, lib);
+        } else if flag == "-pthread" && !target.contains("windows") {
+            // the compiler driver turns it into -lpthread, rustc passes it on as is
+            push_unique(&mut extra.libs, "pthread");
+        } else if flag == "-framework" {
+            // only the frameworks of the features configure enabled are listed
+            if let Some(framework) = flags.get(i + 1) {
+                push_unique(&mut extra.frameworks, framework);
+            }
+        } else if let Some(framework) = flag.strip_prefix("-Wl,-framework,") {
+            push_unique(&mut extra.frameworks, framework);
+        } else if let Some(lib) = flag.strip_suffix(".lib") {
+            if target.contains("msvc") {
+                push_unique(&mut extra.libs, lib);
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! The static ffprobe binaries the `ffprobe` feature downloads without `build`.
+
+/// A pinned archive with a static ffprobe in it.
+pub struct Binary {
+    /// What the host triple has to start with, and end with.
+    pub host: (&'static str, &'static str),
+    pub url: &'static str,
+    /// The path of ffprobe in the archive.
+    pub member: &'static str,
+}
+
+// johnvansickle.com moves a release from releases/ to old-releases/ once the next one is out,
+// old-releases/ keeps it from then on
+pub const BINARIES: &[Binary] = &[
+    Binary {
+        host: ("x86_64-", "-linux-gnu"),
+        url: "https://johnvansickle.com/ffmpeg/old-releases/ffmpeg-7.0.2-amd64-static.tar.xz",
+        member: "ffmpeg-7.0.2-amd64-static/ffprobe",
+    },
+    Binary {
+        host: ("x86_64-", "-linux-musl"),
+        url: "https://johnvansickle.com/ffmpeg/old-releases/ffmpeg-7.0.2-amd64-static.tar.xz",
+        member: "ffmpeg-7.0.2-amd64-static/ffprobe",
+    },
+    Binary {
+        host: ("aarch64-", "-linux-gnu"),
+        url: "https://johnvansickle.com/ffmpeg/old-releases/ffmpeg-7.0.2-arm64-static.tar.xz",
+        member: "ffmpeg-7.0.2-arm64-static/ffprobe",
+    },
+    Binary {
+        host: ("aarch64-", "-linux-musl"),
+        url: "https://johnvansickle.com/ffmpeg/old-releases/ffmpeg-7.0.2-arm64-static.tar.xz",
+        member: "ffmpeg-7.0.2-arm64-static/ffprobe",
+    },
+    // evermeet.cx only builds for x86_64 and there is no official universal one, Apple silicon
+    // runs the same binary through Rosetta 2
+    Binary {
+        host: ("x86_64-", "-apple-darwin"),
+        url: "https://evermeet.cx/ffmpeg/ffprobe-7.0.2.zip",
+        member: "ffprobe",
+    },
+    Binary {
+        host: ("aarch64-", "-apple-darwin"),
+        url: "https://evermeet.cx/ffmpeg/ffprobe-7.0.2.zip",
+        member: "ffprobe",
+    },
+    Binary {
+        host: ("x86_64-", "-windows-msvc"),
+        url: "https://github.com/GyanD/codexffmpeg/releases/download/7.0.2/\
+              ffmpeg-7.0.2-essentials_build.zip",
+        member: "ffmpeg-7.0.2-essentials_build/bin/ffprobe.exe",
+    },
+    Binary {
+        host: ("x86_64-", "-windows-gnu"),
+        url: "https://github.com/GyanD/codexffmpeg/releases/download/7.0.2/\
+              ffmpeg-7.0.2-essentials_build.zip",
+        member: "ffmpeg-7.0.2-essentials_build/bin/ffprobe.exe",
+    },
+];
+
+/// The pinned binary that runs on `host`, none for those without an official static build.
+pub fn binary_for(host: &str) -> Option<&'static Binary> {
+    BINARIES
+        .iter()
+        .find(|binary| host.starts_with(binary.host.0) && host.ends_with(binary.host.1))
+}
+
+impl Binary {
+    /// The name of the downloaded archive, the last component of the URL.
+    pub fn archive(&self) -> &'static str {
+        self.url.rsplit('/').next().unwrap()
+    }
+}
+
+/// The checksums of the archives of [`BINARIES`], rewritten by `cargo xtask pin-ffprobe`. They
+/// are part of this crate so they get published with it.
+pub const PINNED: &str = include_str!("ffprobe.sha256");
+
+/// The checksum `sums` pins for `archive`, in the format of sha256sum with `#` comments.
+pub fn pinned_sha256<'a>(sums: &'a str, archive: &str) -> Option<&'a str> {
+    sums.lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            Some((fields.next()?, fields.next()?))
+        })
+        .find(|&(_, file)| file.trim_start_matches('*') == archive)
+        .map(|(sum, _)| sum)
+}
@@ -0,0 +1,54 @@
+//! The Rust types of FFmpeg's integer macros, build.rs maps them to bindgen's IntKind.
+
+/// The type a macro is generated with, instead of the i32 or i64 bindgen picks by its value.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MacroKind {
+    Int,
+    UInt,
+    ULongLong,
+    Usize,
+}
+
+// buffer sizes and array lengths
+const ARRAY_SIZES: &[&str] = &[
+    "AV_ERROR_MAX_STRING_SIZE",
+    "AV_FOURCC_MAX_STRING_SIZE",
+    "AV_TS_MAX_STRING_SIZE",
+    "AV_NUM_DATA_POINTERS",
+    "AV_PARSER_PTS_NB",
+    "AV_HASH_MAX_SIZE",
+    "AV_INPUT_BUFFER_PADDING_SIZE",
+    "AV_INPUT_BUFFER_MIN_SIZE",
+];
+
+fn fits_int(value: i64) -> bool {
+    value >= i32::MIN as i64 && value <= i32::MAX as i64
+}
+
+/// The type of the integer macro `name` with `value`, `None` leaves it to bindgen.
+pub fn int_macro_kind(name: &str, value: i64) -> Option<MacroKind> {
+    if name.starts_with("AV_CH") {
+        Some(MacroKind::ULongLong)
+    } else if value >= 0 && name.starts_with("AV_PIX_FMT_FLAG") {
+        // compared against AVPixFmtDescriptor.flags, an uint64_t
+        Some(MacroKind::ULongLong)
+    } else if fits_int(value)
+        && (name.starts_with("AV_CODEC_CAP") || name.starts_with("AV_CODEC_FLAG"))
+    {
+        Some(MacroKind::UInt)
+    } else if fits_int(value) && name.starts_with("AV_HWACCEL_FLAG") {
+        // or'ed into AVCodecContext.hwaccel_flags, an int
+        Some(MacroKind::Int)
+    } else if value >= 0 && value <= u32::MAX as i64 && name.starts_with("AV_CPU_FLAG") {
+        // AV_CPU_FLAG_FORCE is 0x80000000, which doesn't fit an int. av_force_cpu_flags and
+        // av_get_cpu_flags still use int, the flags are cast to c_int to pass them and the
+        // result of av_get_cpu_flags to u32 to test it
+        Some(MacroKind::UInt)
+    } else if ARRAY_SIZES.contains(&name) {
+        Some(MacroKind::Usize)
+    } else if fits_int(value) {
+        Some(MacroKind::Int)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,31 @@
+//! The JSON of ffmpeg-sys-build-report.json, the build script has no crate for it.
+
+/// `value` as a JSON string, with the quotes, backslashes and control characters escaped.
+pub fn string(value: &str) -> String {
+    let mut json = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// `values` as a JSON array of strings.
+pub fn array<T: AsRef<str>>(values: &[T]) -> String {
+    let values: Vec<_> = values.iter().map(|value| string(value.as_ref())).collect();
+    format!("[{}]", values.join(", "))
+}
+
+/// `fields` as a JSON object with a field per line, the values are JSON already.
+pub fn object(fields: &[(&str, String)]) -> String {
+    let fields: Vec<_> = fields
+        .iter()
+        .map(|&(name, ref value)| format!("  {}: {}", string(name), value))
+        .collect();
+    format!("{{\n{}\n}}\n", fields.join(",\n"))
+}
@@ -0,0 +1,23 @@
+//! The parts of the ffmpeg-sys build script that only turn inputs into outputs: the parsing of
+//! what configure, check.c, pkg-config and the compiler print, and the tables the configure
+//! arguments and bindings are made from. They take everything as parameters instead of reading
+//! the environment, so `cargo test -p ffmpeg-sys-build` checks them without FFmpeg.
+
+pub mod blocklist;
+pub mod build_libs;
+pub mod check_output;
+pub mod config_mak;
+pub mod configure_args;
+pub mod deprecations;
+pub mod extralibs;
+pub mod ffprobe_binary;
+pub mod int_kind;
+pub mod json;
+pub mod libdir;
+pub mod license;
+pub mod paths;
+pub mod release;
+pub mod sha256;
+pub mod shared_lib;
+pub mod target;
+pub mod version_h;
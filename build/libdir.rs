@@ -0,0 +1,16 @@
+//! Where `make install` put the static libraries of a prefix.
+
+use std::path::{Path, PathBuf};
+
+/// The library directories of a prefix, in the order they're probed. Some toolchains (those of
+/// RHEL and its derivatives among them) default to `lib64` for 64-bit targets.
+pub const LIB_DIRS: &[&str] = &["lib", "lib64"];
+
+/// The directory of `prefix` that holds the static archive of every library in `libs`, `None`
+/// when neither has all of them.
+pub fn find_lib_dir(prefix: &Path, libs: &[&str]) -> Option<PathBuf> {
+    LIB_DIRS
+        .iter()
+        .map(|dir| prefix.join(dir))
+        .find(|dir| libs.iter().all(|lib| dir.join(format!("lib{}.a", lib)).is_file()))
+}
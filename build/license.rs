@@ -0,0 +1,73 @@
+//! The license class of FFmpeg, from each of the places build.rs can take it from.
+
+// the license classes in the order of the obligations they bring
+const CLASSES: &[&str] = &["lgpl", "gpl", "nonfree"];
+
+// libraries configure only accepts with --enable-gpl, and those that turn a GPL build nonfree
+const GPL_LIBS: &[&str] = &[
+    "postproc",
+    "x264",
+    "x265",
+    "xvidcore",
+    "vidstab",
+    "rubberband",
+    "frei0r",
+    "cdio_paranoia",
+    "davs2",
+    "xavs",
+    "xavs2",
+];
+const NONFREE_WITH_GPL_LIBS: &[&str] = &["fdk-aac"];
+
+/// The class of an FFMPEG_LICENSE string, as returned by `avutil_license()`.
+pub fn from_license(license: &str) -> &'static str {
+    if license.contains("nonfree") {
+        "nonfree"
+    } else if license.starts_with("GPL") {
+        "gpl"
+    } else {
+        "lgpl"
+    }
+}
+
+/// The class an ffbuild/config.mak was configured with.
+pub fn from_config_mak(config_mak: &str) -> &'static str {
+    if config_mak.lines().any(|line| line == "CONFIG_NONFREE=yes") {
+        "nonfree"
+    } else if config_mak.lines().any(|line| line == "CONFIG_GPL=yes") {
+        "gpl"
+    } else {
+        "lgpl"
+    }
+}
+
+/// The class of the `configuration:` line `ffmpeg -version` prints.
+pub fn from_configuration(configuration: &str) -> &'static str {
+    if configuration.contains("--enable-nonfree") {
+        "nonfree"
+    } else if configuration.contains("--enable-gpl") {
+        "gpl"
+    } else {
+        "lgpl"
+    }
+}
+
+/// The class the private libraries of the .pc files imply. Only a lower bound, a GPL build
+/// without any GPL library looks the same as an LGPL one.
+pub fn from_private_libs<T: AsRef<str>>(libs: &[T]) -> &'static str {
+    let uses = |names: &[&str]| libs.iter().any(|lib| names.contains(&lib.as_ref()));
+    if uses(GPL_LIBS) && uses(NONFREE_WITH_GPL_LIBS) {
+        "nonfree"
+    } else if uses(GPL_LIBS) {
+        "gpl"
+    } else {
+        "lgpl"
+    }
+}
+
+/// Whether FFMPEG_REQUIRE_LICENSE=`required` accepts an FFmpeg of `class`, `None` when
+/// `required` is neither `lgpl` nor `gpl`.
+pub fn allows(required: &str, class: &str) -> Option<bool> {
+    let allowed = CLASSES[..2].iter().position(|&c| c == required)?;
+    Some(CLASSES.iter().position(|&c| c == class).unwrap() <= allowed)
+}
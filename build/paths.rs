@@ -0,0 +1,31 @@
+//! The include directories the compiler searches and the paths of the MSYS shell.
+
+use std::path::PathBuf;
+
+/// A Windows path as MSYS2 mounts it by default, `C:\a\b` is `/c/a/b`. Only for when cygpath,
+/// which knows the actual mount table, isn't there.
+pub fn msys_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    match path.find(':') {
+        Some(1) => format!("/{}{}", path[..1].to_lowercase(), &path[2..]),
+        _ => path,
+    }
+}
+
+/// The system include directories of `cc -E -v -x c -` from its stderr, without the compiler's
+/// own, whose headers would shadow those of the libc.
+pub fn compiler_search_dirs(stderr: &str) -> Vec<PathBuf> {
+    stderr
+        .lines()
+        .skip_while(|line| !line.starts_with("#include <...> search starts here:"))
+        .skip(1)
+        .take_while(|line| !line.starts_with("End of search list."))
+        // macOS lists its framework directories too
+        .filter(|line| !line.ends_with("(framework directory)"))
+        .map(|line| PathBuf::from(line.trim()))
+        .filter(|path| {
+            let path = path.to_string_lossy();
+            !path.contains("/lib/gcc/") && !path.contains("/lib/clang/")
+        })
+        .collect()
+}
@@ -0,0 +1,91 @@
+//! The major versions of the libraries of each FFmpeg release, to catch headers of mixed ones.
+
+// the major versions of the other libraries of each FFmpeg release since 3.0, by the major
+// version of libavutil
+const FAMILIES: &[(u32, &[(&str, u32)])] = &[
+    (
+        55,
+        &[
+            ("avcodec", 57),
+            ("avdevice", 57),
+            ("avfilter", 6),
+            ("avformat", 57),
+            ("avresample", 3),
+            ("postproc", 54),
+            ("swresample", 2),
+            ("swscale", 4),
+        ],
+    ),
+    (
+        56,
+        &[
+            ("avcodec", 58),
+            ("avdevice", 58),
+            ("avfilter", 7),
+            ("avformat", 58),
+            ("avresample", 4),
+            ("postproc", 55),
+            ("swresample", 3),
+            ("swscale", 5),
+        ],
+    ),
+    (
+        57,
+        &[
+            ("avcodec", 59),
+            ("avdevice", 59),
+            ("avfilter", 8),
+            ("avformat", 59),
+            ("postproc", 56),
+            ("swresample", 4),
+            ("swscale", 6),
+        ],
+    ),
+    (
+        58,
+        &[
+            ("avcodec", 60),
+            ("avdevice", 60),
+            ("avfilter", 9),
+            ("avformat", 60),
+            ("postproc", 57),
+            ("swresample", 4),
+            ("swscale", 7),
+        ],
+    ),
+    (
+        59,
+        &[
+            ("avcodec", 61),
+            ("avdevice", 61),
+            ("avfilter", 10),
+            ("avformat", 61),
+            ("postproc", 58),
+            ("swresample", 5),
+            ("swscale", 8),
+        ],
+    ),
+];
+
+/// The libraries of `majors` whose major version doesn't belong to the release of libavutil's,
+/// with the one that would. Empty for a libavutil major this table doesn't know yet.
+pub fn mismatches<'a>(majors: &[(&'a str, u32)]) -> Vec<(&'a str, u32, u32)> {
+    let avutil = match majors.iter().find(|&&(lib, _)| lib == "avutil") {
+        Some(&(_, avutil)) => avutil,
+        None => return Vec::new(),
+    };
+    let family = match FAMILIES.iter().find(|&&(major, _)| major == avutil) {
+        Some(&(_, family)) => family,
+        None => return Vec::new(),
+    };
+
+    let mut mismatches = Vec::new();
+    for &(lib, major) in majors {
+        if let Some(&(_, expected)) = family.iter().find(|&&(name, _)| name == lib) {
+            if major != expected {
+                mismatches.push((lib, major, expected));
+            }
+        }
+    }
+    mismatches
+}
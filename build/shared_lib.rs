@@ -0,0 +1,60 @@
+//! The file names shared FFmpeg libraries are installed under on each platform.
+
+fn is_version(digits: &str) -> bool {
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+// a suffix of dot separated numbers, `.58.3.100` or nothing
+fn is_version_suffix(suffix: &str) -> bool {
+    suffix.is_empty()
+        || suffix
+            .strip_prefix('.')
+            .is_some_and(|suffix| suffix.split('.').all(is_version))
+}
+
+/// The major version in the name of a shared library of `lib`, `libavutil.so.58`,
+/// `libavutil.58.dylib` or `avutil-58.dll`.
+pub fn major<'a>(lib: &str, file_name: &'a str) -> Option<&'a str> {
+    let major = if let Some(rest) = file_name.strip_prefix("lib") {
+        let rest = rest.strip_prefix(lib)?;
+        rest.strip_prefix(".so.")
+            .or_else(|| rest.strip_prefix('.')?.strip_suffix(".dylib"))?
+    } else {
+        file_name.strip_prefix(lib)?.strip_prefix('-')?.strip_suffix(".dll")?
+    };
+    Some(major).filter(|major| is_version(major))
+}
+
+/// The unversioned name the linker looks for the shared library of `lib` under, `None` for
+/// Windows, where there is only the DLL with the major version in its name.
+pub fn link_name(target: &str, lib: &str) -> Option<String> {
+    if target.contains("windows") {
+        None
+    } else if target.contains("apple") {
+        Some(format!("lib{}.dylib", lib))
+    } else {
+        Some(format!("lib{}.so", lib))
+    }
+}
+
+/// Whether `file_name` is a file of the shared library of `lib` on `target`, the unversioned
+/// name as well as the versioned sonames.
+pub fn is_file_of(target: &str, lib: &str, file_name: &str) -> bool {
+    if target.contains("windows") {
+        return file_name
+            .strip_prefix(lib)
+            .and_then(|rest| rest.strip_prefix('-'))
+            .and_then(|rest| rest.strip_suffix(".dll"))
+            .is_some_and(is_version);
+    }
+
+    let rest = match file_name.strip_prefix("lib").and_then(|rest| rest.strip_prefix(lib)) {
+        Some(rest) => rest,
+        None => return false,
+    };
+    if target.contains("apple") {
+        rest.strip_suffix(".dylib").is_some_and(is_version_suffix)
+    } else {
+        rest.strip_prefix(".so").is_some_and(is_version_suffix)
+    }
+}
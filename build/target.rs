@@ -0,0 +1,82 @@
+//! What differs between targets, by their triple or CARGO_CFG_TARGET_OS.
+
+/// Whether `triple` is one of the systems whose make is BSD make, or the one of Solaris.
+pub fn is_bsd_like(triple: &str) -> bool {
+    ["freebsd", "netbsd", "openbsd", "dragonfly", "illumos", "solaris"]
+        .iter()
+        .any(|os| triple.contains(os))
+}
+
+/// FFmpeg's `--target-os` for a cross build to `triple`, configure takes it from uname
+/// otherwise.
+pub fn configure_target_os(triple: &str) -> Option<&'static str> {
+    if triple.contains("illumos") || triple.contains("solaris") {
+        return Some("sunos");
+    }
+
+    ["freebsd", "netbsd", "openbsd", "dragonfly"]
+        .iter()
+        .find(|&&os| triple.contains(os))
+        .cloned()
+}
+
+/// The C++ runtime the C++ libraries of `target_os` link against, `None` where nothing is
+/// added.
+pub fn cxx_runtime(target_os: &str) -> Option<&'static str> {
+    match target_os {
+        "linux" | "netbsd" | "dragonfly" | "illumos" | "solaris" => Some("stdc++"),
+        "freebsd" | "openbsd" => Some("c++"),
+        _ => None,
+    }
+}
+
+/// The system libraries std needs in a Rust staticlib for `target_os`.
+pub fn rust_staticlib_libs(target_os: &str) -> &'static [&'static str] {
+    match target_os {
+        "linux" => &["pthread", "dl", "m"],
+        "freebsd" | "netbsd" | "dragonfly" => &["pthread", "execinfo", "m"],
+        "openbsd" => &["pthread", "m"],
+        "illumos" | "solaris" => &["pthread", "m", "socket", "posix4", "resolv", "nsl", "umem"],
+        _ => &[],
+    }
+}
+
+/// The Rust runtime symbols `nm -g --defined-only` lists as defined by a Rust staticlib. A
+/// program linking the archive defines them too, the copies in the archive collide with its own
+/// when both come from the same toolchain. Older toolchains export them unmangled, newer ones
+/// under `<hash>___rustc<len>___rust_*`, Mach-O adds a leading `_` to either.
+pub fn rust_runtime_symbols(nm_output: &str) -> Vec<&str> {
+    const RUNTIME: &[&str] = &[
+        "rust_eh_personality",
+        "rust_begin_unwind",
+        "rust_alloc",
+        "rust_alloc_zeroed",
+        "rust_dealloc",
+        "rust_realloc",
+        "rust_alloc_error_handler",
+    ];
+
+    let mut symbols = Vec::new();
+    for line in nm_output.lines() {
+        let mut fields = line.split_whitespace().rev();
+        let (name, kind) = match (fields.next(), fields.next()) {
+            (Some(name), Some(kind)) => (name, kind),
+            _ => continue,
+        };
+
+        // weak symbols give way to the program's, lowercase ones are local
+        if !kind.starts_with(|c: char| c.is_ascii_uppercase()) || "UVW".contains(kind) {
+            continue;
+        }
+
+        let base = match name.rfind("___rustc") {
+            Some(index) => name[index + 8..].trim_start_matches(|c: char| c.is_ascii_digit()),
+            None => name,
+        };
+
+        if RUNTIME.contains(&base.trim_start_matches('_')) && !symbols.contains(&name) {
+            symbols.push(name);
+        }
+    }
+    symbols
+}
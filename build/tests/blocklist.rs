@@ -0,0 +1,94 @@
+extern crate ffmpeg_sys_build;
+
+use ffmpeg_sys_build::blocklist::{blocklist, compiled_out};
+
+const BINDINGS: &str = "\
+pub const AV_FIFO_FLAG_AUTO_GROW: u32 = 1;
+#[doc = \" a very simple circular buffer FIFO implementation\"]
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct AVFifoBuffer {
+    pub buffer: *mut u8,
+    pub end: *mut u8,
+}
+#[test]
+fn bindgen_test_layout_AVFifoBuffer() {
+    assert_eq!(::std::mem::size_of::<AVFifoBuffer>(), 16usize);
+}
+impl Default for AVFifoBuffer {
+    fn default() -> Self {
+        unsafe { ::std::mem::zeroed() }
+    }
+}
+extern \"C\" {
+    #[doc = \" Initialize an AVFifoBuffer.\"]
+    pub fn av_fifo_alloc(size: ::std::os::raw::c_uint) -> *mut AVFifoBuffer;
+}
+extern \"C\" {
+    pub fn av_fifo_generic_read(
+        f: *mut AVFifoBuffer,
+        dest: *mut ::std::os::raw::c_void,
+        buf_size: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern \"C\" {
+    pub fn av_fifo_alloc2(nb_elems: usize, elem_size: usize, flags: ::std::os::raw::c_uint);
+}
+extern \"C\" {
+    pub static mut av_device_capabilities: [AVOption; 0usize];
+}
+pub type AVFifoCB = ::std::option::Option<unsafe extern \"C\" fn(opaque: *mut u8)>;
+";
+
+#[test]
+fn nothing_blocked() {
+    assert_eq!(blocklist(BINDINGS, |_| false), BINDINGS);
+}
+
+#[test]
+fn struct_with_its_impls_and_tests() {
+    let code = blocklist(BINDINGS, |name| name == "AVFifoBuffer");
+    assert!(!code.contains("pub struct AVFifoBuffer"), "{}", code);
+    assert!(!code.contains("bindgen_test_layout_AVFifoBuffer"));
+    assert!(!code.contains("impl Default for AVFifoBuffer"));
+    assert!(!code.contains("a very simple circular buffer"));
+    // the functions using it stay, like with the builder's blocklist_type
+    assert!(code.contains("pub fn av_fifo_alloc(size"));
+    assert!(code.starts_with("pub const AV_FIFO_FLAG_AUTO_GROW: u32 = 1;\nextern \"C\" {\n"));
+}
+
+#[test]
+fn functions_and_statics() {
+    let blocked = ["av_fifo_alloc", "av_fifo_generic_read", "av_device_capabilities"];
+    let code = blocklist(BINDINGS, |name| blocked.contains(&name));
+    assert!(!code.contains("pub fn av_fifo_alloc("), "{}", code);
+    assert!(!code.contains("Initialize an AVFifoBuffer"));
+    assert!(!code.contains("av_fifo_generic_read"));
+    assert!(!code.contains("av_device_capabilities"));
+    assert!(code.contains("pub fn av_fifo_alloc2("));
+    assert_eq!(code.matches("extern \"C\" {").count(), 1);
+    assert!(code.ends_with("fn(opaque: *mut u8)>;\n"));
+}
+
+#[test]
+fn type_alias() {
+    let code = blocklist(BINDINGS, |name| name == "AVFifoCB");
+    assert!(code.ends_with("pub static mut av_device_capabilities: [AVOption; 0usize];\n}\n"));
+}
+
+#[test]
+fn compiled_out_apis() {
+    let ff_api = [
+        // still in the library
+        ("FF_API_DEVICE_CAPABILITIES", true, true),
+        // compiled out, the headers keep declaring it
+        ("FF_API_FIFO_OLD_API", false, true),
+        // not defined by these headers at all
+        ("FF_API_OLD_BSF", false, false),
+    ];
+    let items = compiled_out(&ff_api);
+    assert_eq!(items.len(), 3);
+    assert_eq!(items[0], "AVFifoBuffer");
+    assert!(compiled_out(&[("FF_API_DEVICE_CAPABILITIES", false, true)])
+        .contains(&"avdevice_capabilities_(create|free)"));
+}
@@ -0,0 +1,42 @@
+extern crate ffmpeg_sys_build;
+
+use ffmpeg_sys_build::build_libs::{packages, unsupported};
+
+fn enabled<'a>(features: &'a [&'a str]) -> impl Fn(&str) -> bool + 'a {
+    move |feature| features.contains(&feature)
+}
+
+#[test]
+fn platform_packages() {
+    let linux = packages("x86_64-unknown-linux-gnu", "vpl");
+    assert!(linux.contains(&("build-zlib", "zlib", (0, 0))));
+    assert!(linux.contains(&("build-vpl", "vpl", (0, 0))));
+
+    let macos = packages("aarch64-apple-darwin", "libmfx");
+    assert!(!macos.iter().any(|&(feature, _, _)| feature == "build-zlib"));
+    assert!(macos.contains(&("build-vpl", "libmfx", (0, 0))));
+}
+
+#[test]
+fn too_new_for_release() {
+    let libs = packages("x86_64-unknown-linux-gnu", "libmfx");
+    let problems = unsupported(&libs, (4, 1), enabled(&["build-lib-jxl", "build-lib-dav1d"]));
+    // two packages, one message
+    assert_eq!(
+        problems,
+        vec!["the `build-lib-jxl` feature requires FFmpeg 5.1 or newer, not 4.1"]
+    );
+    assert!(unsupported(&libs, (5, 1), enabled(&["build-lib-jxl"])).is_empty());
+}
+
+#[test]
+fn vulkan_filters() {
+    let libs = packages("x86_64-unknown-linux-gnu", "vpl");
+    let problems = unsupported(&libs, (6, 1), enabled(&["build-vulkan", "avfilter"]));
+    assert_eq!(problems.len(), 1);
+    assert!(problems[0].contains("build-lib-shaderc"));
+
+    let features = ["build-vulkan", "avfilter", "build-lib-shaderc"];
+    assert!(unsupported(&libs, (6, 1), enabled(&features)).is_empty());
+    assert!(unsupported(&libs, (6, 1), enabled(&["build-vulkan"])).is_empty());
+}
@@ -0,0 +1,38 @@
+extern crate ffmpeg_sys_build;
+
+use ffmpeg_sys_build::check_output::{ff_api_macro, flag, number, value};
+
+// the output of check.c for FFmpeg 3.x (libavcodec 57), from before it printed layouts and linked versions
+const CHECK: &str = include_str!("check_output/check.txt");
+
+#[test]
+fn ff_api_macros() {
+    assert_eq!(ff_api_macro(CHECK, "FF_API_AVPICTURE"), Some((true, true)));
+    // removed before 3.4, the headers don't define it
+    assert_eq!(ff_api_macro(CHECK, "FF_API_OLD_AVOPTIONS"), Some((false, false)));
+    // only the whole name matches, not FF_API_VAAPI_CONTEXT
+    assert_eq!(ff_api_macro(CHECK, "FF_API_VAAPI"), Some((true, true)));
+    assert_eq!(ff_api_macro(CHECK, "FF_API_NOT_CHECKED"), None);
+}
+
+#[test]
+fn versions() {
+    assert_eq!(flag(CHECK, "avcodec_version_greater_than_57_0"), Some(true));
+    assert_eq!(flag(CHECK, "avcodec_version_greater_than_58_0"), Some(false));
+    assert_eq!(flag(CHECK, "avcodec_version_greater_than_60_0"), None);
+}
+
+#[test]
+fn layout_and_license() {
+    let stdout = "[sizeof_AVSubtitleRect]104\n\
+                  [offsetof_AVSubtitleRect_flags]100\n\
+                  [avutil_header_version]3812708\n\
+                  [license]LGPL version 2.1 or later\n";
+
+    assert_eq!(number(stdout, "sizeof_AVSubtitleRect"), Some(104));
+    assert_eq!(number(stdout, "offsetof_AVSubtitleRect_flags"), Some(100));
+    assert_eq!(number(stdout, "avutil_header_version"), Some(3812708));
+    assert_eq!(value(stdout, "license"), Some("LGPL version 2.1 or later"));
+    // not a number
+    assert_eq!(number(stdout, "license"), None);
+}
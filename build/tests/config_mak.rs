@@ -0,0 +1,23 @@
+extern crate ffmpeg_sys_build;
+
+use ffmpeg_sys_build::config_mak::configured_with;
+
+const LINUX: &str = include_str!("config_mak/x86_64-unknown-linux-gnu.mak");
+
+#[test]
+fn same_arguments() {
+    // in another order, and the ones with spaces aren't compared
+    let args = "--enable-gpl\n--enable-libx264\n--prefix=/build/dist\n\
+                --extra-cflags=-O2 -g\n--enable-pic";
+    assert!(configured_with(LINUX, args));
+}
+
+#[test]
+fn new_argument() {
+    assert!(!configured_with(LINUX, "--prefix=/build/dist\n--enable-libdav1d"));
+}
+
+#[test]
+fn not_configured() {
+    assert!(!configured_with("# Automatically generated by configure\n", "--enable-pic"));
+}
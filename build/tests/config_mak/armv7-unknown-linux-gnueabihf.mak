@@ -0,0 +1,20 @@
+# Automatically generated by configure - do not modify!
+ifndef FFMPEG_CONFIG_MAK
+FFMPEG_CONFIG_MAK=1
+FFMPEG_CONFIGURATION=--prefix=/build/dist --cross-prefix=armv7-unknown-linux-gnueabihf- --enable-static --disable-shared --enable-pic
+prefix=/build/dist
+CC=armv7-unknown-linux-gnueabihf-gcc
+ARCH=arm
+EXTRALIBS-avdevice=-lm -latomic
+EXTRALIBS-avfilter=-pthread -lm -latomic
+EXTRALIBS-swscale=-lm -latomic
+EXTRALIBS-postproc=-lm -latomic
+EXTRALIBS-avformat=-lm -latomic -lz
+EXTRALIBS-avcodec=-pthread -lm -latomic -lz
+EXTRALIBS-swresample=-lm -latomic
+EXTRALIBS-avutil=-pthread -lm -latomic
+EXTRALIBS-ffplay=
+EXTRALIBS-ffprobe=
+EXTRALIBS-ffmpeg=
+EXTRALIBS=
+endif # FFMPEG_CONFIG_MAK
@@ -0,0 +1,19 @@
+# Automatically generated by configure - do not modify!
+ifndef FFMPEG_CONFIG_MAK
+FFMPEG_CONFIG_MAK=1
+FFMPEG_CONFIGURATION=--prefix=/build/dist --enable-static --disable-shared --enable-pic --enable-videotoolbox --enable-audiotoolbox
+prefix=/build/dist
+CC=clang
+EXTRALIBS-avdevice=-framework Foundation -framework AudioToolbox -framework CoreAudio -lm -framework AVFoundation -framework CoreVideo -framework CoreMedia -framework CoreGraphics -L/usr/lib -lxcb
+EXTRALIBS-avfilter=-framework OpenGL -framework OpenGL -pthread -lm -framework CoreImage -framework AppKit
+EXTRALIBS-swscale=-lm
+EXTRALIBS-postproc=-lm
+EXTRALIBS-avformat=-lm -lbz2 -lz -Wl,-framework,CoreFoundation -Wl,-framework,Security
+EXTRALIBS-avcodec=-liconv -lm -framework AudioToolbox -pthread -lz -framework VideoToolbox -framework CoreFoundation -framework CoreMedia -framework CoreVideo -framework CoreServices
+EXTRALIBS-swresample=-lm
+EXTRALIBS-avutil=-pthread -lm -framework VideoToolbox -framework CoreFoundation -framework CoreMedia -framework CoreVideo -framework CoreServices
+EXTRALIBS-ffplay=-lSDL2
+EXTRALIBS-ffprobe=
+EXTRALIBS-ffmpeg=
+EXTRALIBS=
+endif # FFMPEG_CONFIG_MAK
@@ -0,0 +1,19 @@
+# Automatically generated by configure - do not modify!
+ifndef FFMPEG_CONFIG_MAK
+FFMPEG_CONFIG_MAK=1
+FFMPEG_CONFIGURATION=--prefix=/c/build/dist --target-os=win64 --arch=x86_64 --enable-static --disable-shared --enable-pic
+prefix=/c/build/dist
+CC=x86_64-w64-mingw32-gcc
+EXTRALIBS-avdevice=-lpsapi -lole32 -lstrmiids -luuid -loleaut32 -lshlwapi -lgdi32 -lm -latomic -lvfw32
+EXTRALIBS-avfilter=-lm -latomic
+EXTRALIBS-swscale=-lm -latomic
+EXTRALIBS-postproc=-lm -latomic
+EXTRALIBS-avformat=-lm -latomic -lz -lsecur32 -lws2_32
+EXTRALIBS-avcodec=-lmfuuid -lole32 -lstrmiids -lole32 -luser32 -lm -latomic -lz
+EXTRALIBS-swresample=-lm -latomic
+EXTRALIBS-avutil=-lm -latomic -luser32 -lbcrypt
+EXTRALIBS-ffplay=-lSDL2
+EXTRALIBS-ffprobe=
+EXTRALIBS-ffmpeg=
+EXTRALIBS=-lpsapi -lshell32
+endif # FFMPEG_CONFIG_MAK
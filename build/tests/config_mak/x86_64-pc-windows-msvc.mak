@@ -0,0 +1,19 @@
+# Automatically generated by configure - do not modify!
+ifndef FFMPEG_CONFIG_MAK
+FFMPEG_CONFIG_MAK=1
+FFMPEG_CONFIGURATION=--prefix=/c/build/dist --toolchain=msvc --target-os=win64 --arch=x86_64 --enable-static --disable-shared --enable-pic
+prefix=/c/build/dist
+CC=cl
+EXTRALIBS-avdevice=psapi.lib ole32.lib strmiids.lib uuid.lib oleaut32.lib shlwapi.lib gdi32.lib vfw32.lib
+EXTRALIBS-avfilter=
+EXTRALIBS-swscale=
+EXTRALIBS-postproc=
+EXTRALIBS-avformat=secur32.lib ws2_32.lib
+EXTRALIBS-avcodec=mfuuid.lib ole32.lib strmiids.lib ole32.lib user32.lib
+EXTRALIBS-swresample=
+EXTRALIBS-avutil=user32.lib bcrypt.lib
+EXTRALIBS-ffplay=
+EXTRALIBS-ffprobe=
+EXTRALIBS-ffmpeg=
+EXTRALIBS=psapi.lib shell32.lib
+endif # FFMPEG_CONFIG_MAK
@@ -0,0 +1,20 @@
+# Automatically generated by configure - do not modify!
+ifndef FFMPEG_CONFIG_MAK
+FFMPEG_CONFIG_MAK=1
+FFMPEG_CONFIGURATION=--prefix=/build/dist --disable-debug --enable-stripping --enable-static --disable-shared --enable-pic --enable-gpl --enable-libx264
+prefix=/build/dist
+CC=gcc
+LD=gcc
+EXTRALIBS-avdevice=-lm -latomic -lxcb -lxcb-shm -lxcb-shape -lxcb-xfixes -lasound
+EXTRALIBS-avfilter=-pthread -lm -latomic
+EXTRALIBS-swscale=-lm -latomic
+EXTRALIBS-postproc=-lm -latomic
+EXTRALIBS-avformat=-lm -latomic -lbz2 -lz
+EXTRALIBS-avcodec=-pthread -lm -latomic -llzma -L/opt/x264/lib -lx264 -lz
+EXTRALIBS-swresample=-lm -latomic
+EXTRALIBS-avutil=-pthread -lm -latomic
+EXTRALIBS-ffplay=-lSDL2
+EXTRALIBS-ffprobe=
+EXTRALIBS-ffmpeg=
+EXTRALIBS=-lrt
+endif # FFMPEG_CONFIG_MAK
@@ -0,0 +1,59 @@
+extern crate ffmpeg_sys_build;
+
+use ffmpeg_sys_build::configure_args::{
+    enable_flags, feature_flags, ENABLES, RASPBERRY_PI, SWITCHES,
+};
+
+// the [features] of ffmpeg-sys, every feature of the tables has to be one of them
+const MANIFEST: &str = include_str!("../../Cargo.toml");
+
+#[test]
+fn without_features() {
+    let flags = feature_flags(|_| false);
+    assert_eq!(flags.len(), SWITCHES.len());
+    assert!(flags.iter().all(|flag| flag.starts_with("--disable-")));
+    assert!(flags.contains(&"--disable-gpl".to_owned()));
+}
+
+#[test]
+fn switches_and_enables() {
+    let enabled = ["avcodec", "build-lib-x264", "build-lib-placebo", "build-xcb"];
+    let flags = feature_flags(|feature| enabled.contains(&feature));
+
+    assert!(flags.contains(&"--enable-avcodec".to_owned()));
+    assert!(flags.contains(&"--disable-avformat".to_owned()));
+    assert!(flags.contains(&"--enable-libx264".to_owned()));
+    // features that need more than one part of FFmpeg
+    assert!(flags.contains(&"--enable-libplacebo".to_owned()));
+    assert!(flags.contains(&"--enable-vulkan".to_owned()));
+    assert_eq!(flags.iter().filter(|flag| flag.starts_with("--enable-libxcb")).count(), 4);
+    assert_eq!(flags.len(), SWITCHES.len() + 1 + 2 + 4);
+}
+
+#[test]
+fn raspberry_pi() {
+    let flags = enable_flags(RASPBERRY_PI, |feature| feature == "build-mmal");
+    assert_eq!(flags, vec!["--enable-mmal".to_owned()]);
+}
+
+#[test]
+fn features_exist() {
+    let features = &MANIFEST[MANIFEST.find("[features]").unwrap()..];
+    let declared = |feature: &str| {
+        features
+            .lines()
+            .any(|line| line.split('=').next().unwrap().trim() == feature)
+    };
+
+    let mut seen = Vec::new();
+    let tables = SWITCHES
+        .iter()
+        .map(|&(feature, _)| feature)
+        .chain(ENABLES.iter().map(|&(feature, _)| feature))
+        .chain(RASPBERRY_PI.iter().map(|&(feature, _)| feature));
+    for feature in tables {
+        assert!(declared(feature), "`{}` isn't a feature of ffmpeg-sys", feature);
+        assert!(!seen.contains(&feature), "`{}` is in the tables twice", feature);
+        seen.push(feature);
+    }
+}
@@ -0,0 +1,102 @@
+extern crate ffmpeg_sys_build;
+
+use ffmpeg_sys_build::deprecations::{deprecate, DEPRECATIONS};
+
+const BINDINGS: &str = "\
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct AVPicture {
+    pub data: [*mut u8; 8usize],
+    pub linesize: [::std::os::raw::c_int; 8usize],
+}
+#[repr(C)]
+pub struct AVFrame {
+    pub key_frame: ::std::os::raw::c_int,
+    pub pict_type: AVPictureType,
+}
+#[repr(C)]
+pub struct AVCodecContext {
+    pub ticks_per_frame: ::std::os::raw::c_int,
+}
+extern \"C\" {
+    #[doc = \" Initialize optional fields of a packet with default values.\"]
+    pub fn av_init_packet(pkt: *mut AVPacket);
+}
+extern \"C\" {
+    pub fn av_packet_alloc() -> *mut AVPacket;
+}
+";
+
+const NOTE: &str = "#[deprecated(note = \"removed when FF_API_INIT_PACKET elapses; \
+                    see ffmpeg doc/APIchanges\")]";
+
+#[test]
+fn function() {
+    let code = deprecate(BINDINGS, |ff_api| ff_api == "FF_API_INIT_PACKET");
+    let expected = format!(
+        "    {}\n    pub fn av_init_packet(pkt: *mut AVPacket);",
+        NOTE
+    );
+    assert!(code.contains(&expected), "{}", code);
+    assert_eq!(code.matches("#[deprecated").count(), 1);
+    assert_eq!(code.lines().count(), BINDINGS.lines().count() + 1);
+    assert!(code.ends_with("}\n"));
+}
+
+#[test]
+fn struct_after_its_attributes() {
+    let code = deprecate(BINDINGS, |ff_api| ff_api == "FF_API_AVPICTURE");
+    assert!(code.contains(
+        "#[derive(Debug, Copy, Clone)]\n#[deprecated(note = \"removed when FF_API_AVPICTURE \
+         elapses; see ffmpeg doc/APIchanges\")]\npub struct AVPicture {"
+    ));
+    // AVSubtitleRect isn't in the bindings
+    assert_eq!(code.matches("#[deprecated").count(), 1);
+}
+
+#[test]
+fn field_of_its_struct() {
+    let code = deprecate(BINDINGS, |ff_api| {
+        ff_api == "FF_API_FRAME_KEY" || ff_api == "FF_API_TICKS_PER_FRAME"
+    });
+    let lines: Vec<&str> = code.lines().collect();
+
+    let key_frame = lines
+        .iter()
+        .position(|line| line.contains("pub key_frame:"))
+        .unwrap();
+    assert!(
+        lines[key_frame - 1].starts_with("    #[deprecated(note = \"removed when FF_API_FRAME_KEY")
+    );
+    let ticks = lines
+        .iter()
+        .position(|line| line.contains("pub ticks_per_frame:"))
+        .unwrap();
+    assert!(lines[ticks - 1].contains("FF_API_TICKS_PER_FRAME"));
+    assert_eq!(code.matches("#[deprecated").count(), 2);
+}
+
+#[test]
+fn inactive() {
+    assert_eq!(deprecate(BINDINGS, |_| false), BINDINGS);
+}
+
+#[test]
+fn unknown_symbols() {
+    // everything active, only the symbols the bindings have are touched
+    let code = deprecate("pub struct AVPacket {\n    pub pts: i64,\n}\n", |_| true);
+    assert_eq!(code, "pub struct AVPacket {\n    pub pts: i64,\n}\n");
+}
+
+#[test]
+fn table() {
+    for &(symbol, ff_api) in DEPRECATIONS {
+        assert!(ff_api.starts_with("FF_API_"), "{}", ff_api);
+        assert!(symbol.matches("::").count() <= 1, "{}", symbol);
+        let count = DEPRECATIONS
+            .iter()
+            .filter(|&&(other, _)| other == symbol)
+            .count();
+        assert_eq!(count, 1, "{} is listed more than once", symbol);
+    }
+}
@@ -0,0 +1,136 @@
+extern crate ffmpeg_sys_build;
+
+use ffmpeg_sys_build::extralibs::{merge, parse_extralibs, parse_libs_private};
+
+fn strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+// the libraries of the default features
+fn default_libs(lib: &str) -> bool {
+    ["avcodec", "avdevice", "avfilter", "avformat", "swresample", "swscale"].contains(&lib)
+}
+
+#[test]
+fn linux() {
+    let extra = parse_extralibs(
+        include_str!("config_mak/x86_64-unknown-linux-gnu.mak"),
+        "x86_64-unknown-linux-gnu",
+        default_libs,
+    );
+
+    assert_eq!(
+        extra.libs,
+        strings(&[
+            "m", "atomic", "xcb", "xcb-shm", "xcb-shape", "xcb-xfixes", "asound", "pthread", "bz2",
+            "z", "lzma", "x264", "rt",
+        ])
+    );
+    assert_eq!(extra.link_paths, strings(&["/opt/x264/lib"]));
+    assert!(extra.frameworks.is_empty());
+}
+
+#[test]
+fn only_avutil() {
+    let extra = parse_extralibs(
+        include_str!("config_mak/x86_64-unknown-linux-gnu.mak"),
+        "x86_64-unknown-linux-gnu",
+        |_| false,
+    );
+
+    // avutil and the bare EXTRALIBS line are taken regardless, the programs never are
+    assert_eq!(extra.libs, strings(&["pthread", "m", "atomic", "rt"]));
+}
+
+#[test]
+fn armv7() {
+    let extra = parse_extralibs(
+        include_str!("config_mak/armv7-unknown-linux-gnueabihf.mak"),
+        "armv7-unknown-linux-gnueabihf",
+        default_libs,
+    );
+
+    assert_eq!(extra.libs, strings(&["m", "atomic", "pthread", "z"]));
+}
+
+#[test]
+fn macos() {
+    let extra = parse_extralibs(
+        include_str!("config_mak/x86_64-apple-darwin.mak"),
+        "x86_64-apple-darwin",
+        default_libs,
+    );
+
+    assert_eq!(extra.libs, strings(&["m", "xcb", "pthread", "bz2", "z", "iconv"]));
+    assert_eq!(extra.link_paths, strings(&["/usr/lib"]));
+    assert_eq!(
+        extra.frameworks,
+        strings(&[
+            "Foundation",
+            "AudioToolbox",
+            "CoreAudio",
+            "AVFoundation",
+            "CoreVideo",
+            "CoreMedia",
+            "CoreGraphics",
+            "OpenGL",
+            "CoreImage",
+            "AppKit",
+            "CoreFoundation",
+            "Security",
+            "VideoToolbox",
+            "CoreServices",
+        ])
+    );
+}
+
+#[test]
+fn mingw() {
+    let extra = parse_extralibs(
+        include_str!("config_mak/x86_64-pc-windows-gnu.mak"),
+        "x86_64-pc-windows-gnu",
+        default_libs,
+    );
+
+    assert_eq!(
+        extra.libs,
+        strings(&[
+            "psapi", "ole32", "strmiids", "uuid", "oleaut32", "shlwapi", "gdi32", "m", "atomic",
+            "vfw32", "z", "secur32", "ws2_32", "mfuuid", "user32", "bcrypt", "shell32",
+        ])
+    );
+}
+
+#[test]
+fn msvc() {
+    let extra = parse_extralibs(
+        include_str!("config_mak/x86_64-pc-windows-msvc.mak"),
+        "x86_64-pc-windows-msvc",
+        default_libs,
+    );
+
+    assert_eq!(
+        extra.libs,
+        strings(&[
+            "psapi", "ole32", "strmiids", "uuid", "oleaut32", "shlwapi", "gdi32", "vfw32",
+            "secur32", "ws2_32", "mfuuid", "user32", "bcrypt", "shell32",
+        ])
+    );
+}
+
+#[test]
+fn libs_private() {
+    let mut extra = parse_libs_private(
+        include_str!("pkgconfig/libavcodec.pc"),
+        "x86_64-unknown-linux-gnu",
+    );
+    assert_eq!(extra.libs, strings(&["pthread", "m", "atomic", "lzma", "x264", "z"]));
+    assert_eq!(extra.link_paths, strings(&["/opt/x264/lib"]));
+
+    // avutil's libraries are a subset of avcodec's
+    merge(
+        &mut extra,
+        parse_libs_private(include_str!("pkgconfig/libavutil.pc"), "x86_64-unknown-linux-gnu"),
+    );
+    assert_eq!(extra.libs, strings(&["pthread", "m", "atomic", "lzma", "x264", "z"]));
+}
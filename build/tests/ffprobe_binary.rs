@@ -0,0 +1,76 @@
+extern crate ffmpeg_sys_build;
+
+use ffmpeg_sys_build::ffprobe_binary::{binary_for, pinned_sha256, BINARIES};
+
+#[test]
+fn linux() {
+    let binary = binary_for("x86_64-unknown-linux-gnu").unwrap();
+    assert_eq!(binary.archive(), "ffmpeg-7.0.2-amd64-static.tar.xz");
+    assert!(binary.member.ends_with("/ffprobe"));
+
+    let musl = binary_for("x86_64-unknown-linux-musl").unwrap();
+    assert_eq!(musl.url, binary.url);
+
+    let arm = binary_for("aarch64-unknown-linux-gnu").unwrap();
+    assert_eq!(arm.archive(), "ffmpeg-7.0.2-arm64-static.tar.xz");
+}
+
+#[test]
+fn macos() {
+    let intel = binary_for("x86_64-apple-darwin").unwrap();
+    assert_eq!(intel.archive(), "ffprobe-7.0.2.zip");
+    assert_eq!(intel.member, "ffprobe");
+    // the same binary for both, through Rosetta 2 on Apple silicon
+    let arm = binary_for("aarch64-apple-darwin").unwrap();
+    assert_eq!(arm.url, intel.url);
+}
+
+#[test]
+fn windows() {
+    let binary = binary_for("x86_64-pc-windows-msvc").unwrap();
+    assert_eq!(binary.archive(), "ffmpeg-7.0.2-essentials_build.zip");
+    assert!(binary.member.ends_with(".exe"));
+    assert_eq!(binary_for("x86_64-pc-windows-gnu").unwrap().url, binary.url);
+}
+
+#[test]
+fn unsupported() {
+    for host in &[
+        "i686-unknown-linux-gnu",
+        "armv7-unknown-linux-gnueabihf",
+        "aarch64-pc-windows-msvc",
+        "x86_64-unknown-freebsd",
+        "riscv64gc-unknown-linux-gnu",
+    ] {
+        assert!(binary_for(host).is_none(), "{}", host);
+    }
+}
+
+#[test]
+fn urls() {
+    for binary in BINARIES {
+        assert!(binary.url.starts_with("https://"), "{}", binary.url);
+        assert!(!binary.url.contains(char::is_whitespace), "{}", binary.url);
+        // releases/ only has the latest release
+        assert!(!binary.url.contains("/ffmpeg/releases/"), "{}", binary.url);
+    }
+}
+
+#[test]
+fn sums() {
+    let sums = "# the archives of BINARIES\n\
+                0123abcd  ffmpeg-7.0.2-amd64-static.tar.xz\n\
+                4567ef01 *ffmpeg-7.0.2-essentials_build.zip\n";
+
+    assert_eq!(
+        pinned_sha256(sums, "ffmpeg-7.0.2-amd64-static.tar.xz"),
+        Some("0123abcd")
+    );
+    // binary mode, as sha256sum -b writes it
+    assert_eq!(
+        pinned_sha256(sums, "ffmpeg-7.0.2-essentials_build.zip"),
+        Some("4567ef01")
+    );
+    assert_eq!(pinned_sha256(sums, "ffprobe-7.0.2.zip"), None);
+    assert_eq!(pinned_sha256("", "ffprobe-7.0.2.zip"), None);
+}
@@ -0,0 +1,33 @@
+extern crate ffmpeg_sys_build;
+
+use ffmpeg_sys_build::int_kind::{int_macro_kind, MacroKind};
+
+#[test]
+fn masks() {
+    assert_eq!(int_macro_kind("AV_CH_FRONT_LEFT", 0x1), Some(MacroKind::ULongLong));
+    assert_eq!(int_macro_kind("AV_CH_LOW_FREQUENCY_2", 0x800000000), Some(MacroKind::ULongLong));
+    assert_eq!(int_macro_kind("AV_PIX_FMT_FLAG_ALPHA", 1 << 7), Some(MacroKind::ULongLong));
+}
+
+#[test]
+fn flags() {
+    assert_eq!(int_macro_kind("AV_CODEC_CAP_DELAY", 1 << 5), Some(MacroKind::UInt));
+    assert_eq!(int_macro_kind("AV_CODEC_FLAG_GLOBAL_HEADER", 1 << 22), Some(MacroKind::UInt));
+    assert_eq!(int_macro_kind("AV_HWACCEL_FLAG_IGNORE_LEVEL", 1), Some(MacroKind::Int));
+    // doesn't fit an int
+    assert_eq!(int_macro_kind("AV_CPU_FLAG_FORCE", 0x80000000), Some(MacroKind::UInt));
+}
+
+#[test]
+fn sizes() {
+    assert_eq!(int_macro_kind("AV_NUM_DATA_POINTERS", 8), Some(MacroKind::Usize));
+    assert_eq!(int_macro_kind("AV_INPUT_BUFFER_PADDING_SIZE", 64), Some(MacroKind::Usize));
+    // only the listed names, not everything ending in _SIZE
+    assert_eq!(int_macro_kind("AV_INPUT_BUFFER_PADDING_SIZE_2", 64), Some(MacroKind::Int));
+}
+
+#[test]
+fn others() {
+    assert_eq!(int_macro_kind("AV_LOG_ERROR", 16), Some(MacroKind::Int));
+    assert_eq!(int_macro_kind("AV_NOPTS_VALUE", i64::MIN), None);
+}
@@ -0,0 +1,34 @@
+extern crate ffmpeg_sys_build;
+
+use ffmpeg_sys_build::json::{array, object, string};
+
+#[test]
+fn strings() {
+    assert_eq!(string("avcodec"), "\"avcodec\"");
+    assert_eq!(string("C:\\ffmpeg\\lib"), "\"C:\\\\ffmpeg\\\\lib\"");
+    assert_eq!(string("say \"hi\""), "\"say \\\"hi\\\"\"");
+    // configure output ends up in the report with its newlines and tabs
+    assert_eq!(string("a\nb\tc"), "\"a\\u000ab\\u0009c\"");
+    assert_eq!(string("ü"), "\"ü\"");
+}
+
+#[test]
+fn arrays() {
+    assert_eq!(array::<&str>(&[]), "[]");
+    assert_eq!(
+        array(&["--enable-gpl", "--prefix=/usr"]),
+        "[\"--enable-gpl\", \"--prefix=/usr\"]"
+    );
+}
+
+#[test]
+fn objects() {
+    let fields = [
+        ("discovery", string("pkg-config")),
+        ("shared_libs", array::<&str>(&[])),
+    ];
+    assert_eq!(
+        object(&fields),
+        "{\n  \"discovery\": \"pkg-config\",\n  \"shared_libs\": []\n}\n"
+    );
+}
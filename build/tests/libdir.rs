@@ -0,0 +1,57 @@
+extern crate ffmpeg_sys_build;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use ffmpeg_sys_build::libdir::find_lib_dir;
+
+// a prefix of its own for each test, they run in parallel
+fn prefix(name: &str) -> PathBuf {
+    let prefix = env::temp_dir().join(format!("ffmpeg-sys-libdir-{}-{}", name, process::id()));
+    let _ = fs::remove_dir_all(&prefix);
+    prefix
+}
+
+fn install(prefix: &Path, dir: &str, libs: &[&str]) {
+    fs::create_dir_all(prefix.join(dir)).unwrap();
+    for lib in libs {
+        fs::write(prefix.join(dir).join(format!("lib{}.a", lib)), b"!<arch>\n").unwrap();
+    }
+}
+
+#[test]
+fn lib() {
+    let prefix = prefix("lib");
+    install(&prefix, "lib", &["avcodec", "avutil"]);
+
+    assert_eq!(find_lib_dir(&prefix, &["avcodec", "avutil"]), Some(prefix.join("lib")));
+
+    fs::remove_dir_all(&prefix).unwrap();
+}
+
+#[test]
+fn lib64() {
+    let prefix = prefix("lib64");
+    install(&prefix, "lib", &["avcodec", "avutil"]);
+
+    // what a toolchain defaulting to lib64 leaves behind
+    fs::rename(prefix.join("lib"), prefix.join("lib64")).unwrap();
+    assert_eq!(find_lib_dir(&prefix, &["avcodec", "avutil"]), Some(prefix.join("lib64")));
+
+    fs::remove_dir_all(&prefix).unwrap();
+}
+
+#[test]
+fn incomplete() {
+    let prefix = prefix("incomplete");
+    install(&prefix, "lib", &["avutil"]);
+    install(&prefix, "lib64", &["avcodec"]);
+
+    // an interrupted build, neither directory has all of them
+    assert_eq!(find_lib_dir(&prefix, &["avcodec", "avutil"]), None);
+    assert_eq!(find_lib_dir(&prefix, &["avutil"]), Some(prefix.join("lib")));
+
+    fs::remove_dir_all(&prefix).unwrap();
+}
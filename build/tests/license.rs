@@ -0,0 +1,52 @@
+extern crate ffmpeg_sys_build;
+
+use ffmpeg_sys_build::license::{
+    allows, from_config_mak, from_configuration, from_license, from_private_libs,
+};
+
+#[test]
+fn license_strings() {
+    assert_eq!(from_license("LGPL version 2.1 or later"), "lgpl");
+    assert_eq!(from_license("GPL version 2 or later"), "gpl");
+    assert_eq!(from_license("GPL version 3 or later"), "gpl");
+    assert_eq!(from_license("nonfree and unredistributable"), "nonfree");
+}
+
+#[test]
+fn config_mak() {
+    assert_eq!(from_config_mak("CONFIG_GPL=yes\nCONFIG_NONFREE=yes\n"), "nonfree");
+    assert_eq!(from_config_mak("CONFIG_GPL=yes\n!CONFIG_NONFREE=yes\n"), "gpl");
+    assert_eq!(from_config_mak("!CONFIG_GPL=yes\n!CONFIG_NONFREE=yes\n"), "lgpl");
+}
+
+#[test]
+fn configuration() {
+    assert_eq!(
+        from_configuration("configuration: --prefix=/usr --enable-gpl --enable-libx264"),
+        "gpl"
+    );
+    assert_eq!(
+        from_configuration("configuration: --enable-gpl --enable-nonfree --enable-libfdk-aac"),
+        "nonfree"
+    );
+    assert_eq!(from_configuration("configuration: --prefix=/usr"), "lgpl");
+}
+
+#[test]
+fn private_libs() {
+    assert_eq!(from_private_libs(&["m", "z", "x264"]), "gpl");
+    assert_eq!(from_private_libs(&["x264", "fdk-aac"]), "nonfree");
+    // fdk-aac is fine in an LGPL build
+    assert_eq!(from_private_libs(&["fdk-aac", "m"]), "lgpl");
+    assert_eq!(from_private_libs::<&str>(&[]), "lgpl");
+}
+
+#[test]
+fn required() {
+    assert_eq!(allows("lgpl", "lgpl"), Some(true));
+    assert_eq!(allows("lgpl", "gpl"), Some(false));
+    assert_eq!(allows("gpl", "gpl"), Some(true));
+    assert_eq!(allows("gpl", "nonfree"), Some(false));
+    // nonfree isn't something to require
+    assert_eq!(allows("nonfree", "lgpl"), None);
+}
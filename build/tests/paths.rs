@@ -0,0 +1,52 @@
+extern crate ffmpeg_sys_build;
+
+use std::path::PathBuf;
+
+use ffmpeg_sys_build::paths::{compiler_search_dirs, msys_path};
+
+#[test]
+fn msys() {
+    assert_eq!(msys_path(r"C:\build\target\out\dist"), "/c/build/target/out/dist");
+    assert_eq!(msys_path(r"D:\a b\dist"), "/d/a b/dist");
+    // already a POSIX path
+    assert_eq!(msys_path("/home/build/dist"), "/home/build/dist");
+}
+
+#[test]
+fn gcc() {
+    let dirs = compiler_search_dirs(include_str!("compiler_output/gcc-12-x86_64-linux-gnu.txt"));
+
+    // without /usr/lib/gcc/x86_64-linux-gnu/12/include
+    assert_eq!(
+        dirs,
+        vec![
+            PathBuf::from("/usr/local/include"),
+            PathBuf::from("/usr/include/x86_64-linux-gnu"),
+            PathBuf::from("/usr/include"),
+        ]
+    );
+}
+
+#[test]
+fn frameworks() {
+    let stderr = "#include <...> search starts here:\n \
+                  /usr/local/include\n \
+                  /Library/Developer/CommandLineTools/usr/lib/clang/15.0.0/include\n \
+                  /Library/Developer/CommandLineTools/SDKs/MacOSX.sdk/usr/include\n \
+                  /Library/Developer/CommandLineTools/SDKs/MacOSX.sdk/System/Library/Frameworks \
+                  (framework directory)\n\
+                  End of search list.\n";
+
+    assert_eq!(
+        compiler_search_dirs(stderr),
+        vec![
+            PathBuf::from("/usr/local/include"),
+            PathBuf::from("/Library/Developer/CommandLineTools/SDKs/MacOSX.sdk/usr/include"),
+        ]
+    );
+}
+
+#[test]
+fn no_search_list() {
+    assert!(compiler_search_dirs("cc: error: unrecognized command-line option").is_empty());
+}
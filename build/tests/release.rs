@@ -0,0 +1,22 @@
+extern crate ffmpeg_sys_build;
+
+use ffmpeg_sys_build::release::mismatches;
+
+#[test]
+fn one_release() {
+    let majors = [("avutil", 58), ("avcodec", 60), ("avformat", 60), ("swscale", 7)];
+    assert!(mismatches(&majors).is_empty());
+}
+
+#[test]
+fn mixed_releases() {
+    // a libswscale-dev of FFmpeg 4 next to FFmpeg 6
+    let majors = [("avutil", 58), ("avcodec", 60), ("swscale", 5)];
+    assert_eq!(mismatches(&majors), vec![("swscale", 5, 7)]);
+}
+
+#[test]
+fn unknown_release() {
+    let majors = [("avutil", 99), ("avcodec", 1)];
+    assert!(mismatches(&majors).is_empty());
+}
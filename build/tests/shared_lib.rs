@@ -0,0 +1,50 @@
+extern crate ffmpeg_sys_build;
+
+use ffmpeg_sys_build::shared_lib::{is_file_of, link_name, major};
+
+#[test]
+fn majors() {
+    assert_eq!(major("avutil", "libavutil.so.58"), Some("58"));
+    assert_eq!(major("avutil", "libavutil.58.dylib"), Some("58"));
+    assert_eq!(major("avutil", "avutil-58.dll"), Some("58"));
+    assert_eq!(major("avutil", "libavutil.so"), None);
+    assert_eq!(major("avutil", "libavutil.so.58.29.100"), None);
+    assert_eq!(major("avutil", "libavutil.dylib"), None);
+    assert_eq!(major("avcodec", "libavutil.so.58"), None);
+}
+
+#[test]
+fn link_names() {
+    assert_eq!(link_name("x86_64-unknown-linux-gnu", "avutil").unwrap(), "libavutil.so");
+    assert_eq!(link_name("aarch64-apple-darwin", "avutil").unwrap(), "libavutil.dylib");
+    assert_eq!(link_name("x86_64-pc-windows-msvc", "avutil"), None);
+}
+
+#[test]
+fn linux_files() {
+    let target = "x86_64-unknown-linux-gnu";
+    assert!(is_file_of(target, "avutil", "libavutil.so"));
+    assert!(is_file_of(target, "avutil", "libavutil.so.58"));
+    assert!(is_file_of(target, "avutil", "libavutil.so.58.29.100"));
+    assert!(!is_file_of(target, "avutil", "libavutil.a"));
+    assert!(!is_file_of(target, "avutil", "libavutil.so.58.debug"));
+    // the prefix of another library's name
+    assert!(!is_file_of(target, "av", "libavutil.so"));
+}
+
+#[test]
+fn macos_files() {
+    let target = "aarch64-apple-darwin";
+    assert!(is_file_of(target, "avutil", "libavutil.dylib"));
+    assert!(is_file_of(target, "avutil", "libavutil.58.dylib"));
+    assert!(is_file_of(target, "avutil", "libavutil.58.29.100.dylib"));
+    assert!(!is_file_of(target, "avutil", "libavutil.so.58"));
+}
+
+#[test]
+fn windows_files() {
+    let target = "x86_64-pc-windows-gnu";
+    assert!(is_file_of(target, "avutil", "avutil-58.dll"));
+    assert!(!is_file_of(target, "avutil", "avutil.dll"));
+    assert!(!is_file_of(target, "avutil", "avutil-58.lib"));
+}
@@ -0,0 +1,74 @@
+extern crate ffmpeg_sys_build;
+
+use ffmpeg_sys_build::target::{
+    configure_target_os, cxx_runtime, is_bsd_like, rust_runtime_symbols, rust_staticlib_libs,
+};
+
+#[test]
+fn make() {
+    assert!(is_bsd_like("x86_64-unknown-freebsd"));
+    assert!(is_bsd_like("x86_64-unknown-illumos"));
+    assert!(is_bsd_like("sparcv9-sun-solaris"));
+    assert!(!is_bsd_like("x86_64-unknown-linux-gnu"));
+    assert!(!is_bsd_like("x86_64-apple-darwin"));
+}
+
+#[test]
+fn configure() {
+    assert_eq!(configure_target_os("x86_64-unknown-freebsd"), Some("freebsd"));
+    assert_eq!(configure_target_os("aarch64-unknown-openbsd"), Some("openbsd"));
+    assert_eq!(configure_target_os("x86_64-unknown-illumos"), Some("sunos"));
+    assert_eq!(configure_target_os("x86_64-pc-solaris"), Some("sunos"));
+    assert_eq!(configure_target_os("aarch64-unknown-linux-gnu"), None);
+}
+
+#[test]
+fn system_libraries() {
+    assert_eq!(cxx_runtime("linux"), Some("stdc++"));
+    assert_eq!(cxx_runtime("freebsd"), Some("c++"));
+    assert_eq!(cxx_runtime("windows"), None);
+
+    assert_eq!(rust_staticlib_libs("linux"), &["pthread", "dl", "m"]);
+    assert!(rust_staticlib_libs("freebsd").contains(&"execinfo"));
+    assert!(rust_staticlib_libs("illumos").contains(&"socket"));
+    assert!(rust_staticlib_libs("macos").is_empty());
+}
+
+// excerpts of `nm -g --defined-only` on a Rust staticlib exporting rav1e_version_full
+const NM_STATICLIB: &str = "\
+librav1e.lib.c6f551dc431569fb-cgu.0.rcgu.o:
+0000000000000000 V DW.ref.rust_eh_personality
+0000000000000000 T rav1e_version_full
+
+librav1e.8nzksehaabot1t4lzbzhnb3r2.rcgu.o:
+0000000000000000 T _RNvCsfLfy6EI15iL_7___rustc12___rust_alloc
+0000000000000000 T _RNvCsfLfy6EI15iL_7___rustc14___rust_dealloc
+0000000000000000 T _RNvCsfLfy6EI15iL_7___rustc14___rust_realloc
+0000000000000000 T _RNvCsfLfy6EI15iL_7___rustc19___rust_alloc_zeroed
+
+compiler_builtins-27cfc16bdf3bb694.compiler_builtins.4e30281dd23088d2-cgu.000.rcgu.o:
+0000000000000000 T __absvdi2
+0000000000000000 W __compilerrt_abort_impl
+";
+
+#[test]
+fn rust_runtime() {
+    assert_eq!(
+        rust_runtime_symbols(NM_STATICLIB),
+        &[
+            "_RNvCsfLfy6EI15iL_7___rustc12___rust_alloc",
+            "_RNvCsfLfy6EI15iL_7___rustc14___rust_dealloc",
+            "_RNvCsfLfy6EI15iL_7___rustc14___rust_realloc",
+            "_RNvCsfLfy6EI15iL_7___rustc19___rust_alloc_zeroed",
+        ]
+    );
+
+    // older toolchains, and Mach-O with its leading underscore
+    assert_eq!(
+        rust_runtime_symbols(
+            "0000000000000000 T rust_eh_personality\n0000000000000000 T ___rust_alloc\n"
+        ),
+        &["rust_eh_personality", "___rust_alloc"]
+    );
+    assert!(rust_runtime_symbols("                 U __rust_alloc\n").is_empty());
+}
@@ -0,0 +1,38 @@
+extern crate ffmpeg_sys_build;
+
+use ffmpeg_sys_build::version_h::version;
+
+#[test]
+fn version_h() {
+    let code = "#define LIBAVUTIL_VERSION_MAJOR  56\n\
+                #define LIBAVUTIL_VERSION_MINOR  22\n\
+                #define LIBAVUTIL_VERSION_MICRO 100\n\
+                \n\
+                #define LIBAVUTIL_VERSION_INT   AV_VERSION_INT(LIBAVUTIL_VERSION_MAJOR, \\\n";
+    assert_eq!(version(code, "avutil"), Some((56, 22, 100)));
+}
+
+#[test]
+fn version_major_h() {
+    // FFmpeg 5.0 and newer, version_major.h comes first
+    let code = "#define LIBAVCODEC_VERSION_MAJOR  60\n\
+                #define FF_API_INIT_PACKET         (LIBAVCODEC_VERSION_MAJOR < 61)\n\
+                #define LIBAVCODEC_VERSION_MINOR   3\n\
+                #define LIBAVCODEC_VERSION_MICRO 100\n";
+    assert_eq!(version(code, "avcodec"), Some((60, 3, 100)));
+}
+
+#[test]
+fn missing_parts() {
+    let code = "#define LIBAVUTIL_VERSION_MAJOR  56\n";
+    assert_eq!(version(code, "avutil"), None);
+    assert_eq!(version("", "avutil"), None);
+}
+
+#[test]
+fn other_library() {
+    let code = "#define LIBAVUTIL_VERSION_MAJOR 56\n\
+                #define LIBAVUTIL_VERSION_MINOR 22\n\
+                #define LIBAVUTIL_VERSION_MICRO 100\n";
+    assert_eq!(version(code, "avcodec"), None);
+}
@@ -0,0 +1,21 @@
+//! The versions the version.h and version_major.h headers of the libraries define.
+
+// the leading digits of the value of `#define <name> <value>`
+fn define(code: &str, name: &str) -> Option<u32> {
+    code.lines().find_map(|line| {
+        let mut words = line.split_whitespace();
+        if words.next()? != "#define" || words.next()? != name {
+            return None;
+        }
+        let value = words.next()?;
+        let end = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+        value[..end].parse().ok()
+    })
+}
+
+/// The major, minor and micro version of `lib` in `code`, the headers of the library one after
+/// the other. FFmpeg 5.0 moved the major versions to version_major.h.
+pub fn version(code: &str, lib: &str) -> Option<(u32, u32, u32)> {
+    let number = |part: &str| define(code, &format!("LIB{}_VERSION_{}", lib.to_uppercase(), part));
+    Some((number("MAJOR")?, number("MINOR")?, number("MICRO")?))
+}
@@ -0,0 +1,148 @@
+//! Decodes the best video stream of a file and prints how many frames it has.
+//!
+//!     cargo run --example decode_frames -- input.mp4
+
+extern crate ffmpeg_sys;
+extern crate libc;
+
+use std::env;
+use std::ffi::{CStr, CString};
+use std::process;
+use std::ptr;
+
+use ffmpeg_sys::*;
+use libc::{c_char, c_int, EAGAIN};
+
+fn error_string(err: c_int) -> String {
+    let mut buf = [0 as c_char; 64];
+
+    unsafe {
+        av_strerror(err, buf.as_mut_ptr(), buf.len());
+        CStr::from_ptr(buf.as_ptr()).to_string_lossy().into_owned()
+    }
+}
+
+fn check(ret: c_int, what: &str) -> c_int {
+    if ret < 0 {
+        eprintln!("{} failed: {}", what, error_string(ret));
+        process::exit(1);
+    }
+
+    ret
+}
+
+// Takes every frame the decoder has ready. EAGAIN means it needs another packet, EOF that it
+// has been drained after a null packet, anything else is an error.
+unsafe fn receive_frames(decoder: *mut AVCodecContext, frame: *mut AVFrame, count: &mut u64) {
+    loop {
+        let ret = avcodec_receive_frame(decoder, frame);
+        if ret == AVERROR(EAGAIN) || ret == AVERROR_EOF {
+            return;
+        }
+        check(ret, "avcodec_receive_frame");
+
+        *count += 1;
+        println!(
+            "frame {} pts {} {}x{}",
+            count,
+            (*frame).pts,
+            (*frame).width,
+            (*frame).height
+        );
+
+        // the frame references the decoder's buffers until it's unreferenced
+        av_frame_unref(frame);
+    }
+}
+
+fn main() {
+    let path = match env::args().nth(1) {
+        Some(path) => path,
+        None => {
+            eprintln!("usage: decode_frames <input>");
+            process::exit(2);
+        }
+    };
+    let path = CString::new(path).expect("path contains a NUL byte");
+
+    unsafe {
+        let mut format = ptr::null_mut();
+        check(
+            avformat_open_input(&mut format, path.as_ptr(), ptr::null_mut(), ptr::null_mut()),
+            "avformat_open_input",
+        );
+        check(
+            avformat_find_stream_info(format, ptr::null_mut()),
+            "avformat_find_stream_info",
+        );
+
+        let index = check(
+            av_find_best_stream(
+                format,
+                AVMediaType::AVMEDIA_TYPE_VIDEO,
+                -1,
+                -1,
+                ptr::null_mut(),
+                0,
+            ),
+            "av_find_best_stream",
+        );
+        let stream = *(*format).streams.offset(index as isize);
+        let parameters = (*stream).codecpar;
+
+        let codec = avcodec_find_decoder((*parameters).codec_id);
+        if codec.is_null() {
+            eprintln!("no decoder for {:?}", (*parameters).codec_id);
+            process::exit(1);
+        }
+
+        // the decoder context is owned by the caller, unlike the streams which belong to the
+        // format context
+        let mut decoder = avcodec_alloc_context3(codec);
+        check(
+            avcodec_parameters_to_context(decoder, parameters),
+            "avcodec_parameters_to_context",
+        );
+        (*decoder).pkt_timebase = (*stream).time_base;
+        check(
+            avcodec_open2(decoder, codec, ptr::null_mut()),
+            "avcodec_open2",
+        );
+
+        let mut packet = av_packet_alloc();
+        let mut frame = av_frame_alloc();
+        let mut count = 0;
+
+        loop {
+            let ret = av_read_frame(format, packet);
+            if ret == AVERROR_EOF {
+                break;
+            }
+            check(ret, "av_read_frame");
+
+            if (*packet).stream_index == index {
+                // the decoder only returns EAGAIN here when its frames weren't received, which
+                // receive_frames always does after each packet
+                check(avcodec_send_packet(decoder, packet), "avcodec_send_packet");
+                receive_frames(decoder, frame, &mut count);
+            }
+
+            av_packet_unref(packet);
+        }
+
+        // a null packet puts the decoder in draining mode, it then returns the frames it
+        // buffered for reordering and finally AVERROR_EOF
+        check(
+            avcodec_send_packet(decoder, ptr::null()),
+            "avcodec_send_packet",
+        );
+        receive_frames(decoder, frame, &mut count);
+
+        println!("{} frames", count);
+
+        av_frame_free(&mut frame);
+        av_packet_free(&mut packet);
+        avcodec_free_context(&mut decoder);
+        avformat_close_input(&mut format);
+    }
+}
@@ -0,0 +1,212 @@
+//! Encodes two seconds of moving color bars to an MPEG-4 file, with FFmpeg's builtin mpeg4
+//! encoder so no external library is needed.
+//!
+//!     cargo run --example encode_color_bars -- color_bars.mp4
+
+extern crate ffmpeg_sys;
+extern crate libc;
+
+use std::env;
+use std::ffi::{CStr, CString};
+use std::process;
+use std::ptr;
+
+use ffmpeg_sys::*;
+use libc::{c_char, c_int, EAGAIN};
+
+const WIDTH: c_int = 320;
+const HEIGHT: c_int = 240;
+const FRAMES: i64 = 50;
+
+// 75% bars in limited range BT.601: white, yellow, cyan, green, magenta, red, blue
+const BARS: [(u8, u8, u8); 7] = [
+    (180, 128, 128),
+    (162, 44, 142),
+    (131, 156, 44),
+    (112, 72, 58),
+    (84, 184, 198),
+    (65, 100, 212),
+    (35, 212, 114),
+];
+
+fn error_string(err: c_int) -> String {
+    let mut buf = [0 as c_char; 64];
+
+    unsafe {
+        av_strerror(err, buf.as_mut_ptr(), buf.len());
+        CStr::from_ptr(buf.as_ptr()).to_string_lossy().into_owned()
+    }
+}
+
+fn check(ret: c_int, what: &str) -> c_int {
+    if ret < 0 {
+        eprintln!("{} failed: {}", what, error_string(ret));
+        process::exit(1);
+    }
+
+    ret
+}
+
+// Draws the bars shifted by `offset` pixels, the chroma planes are subsampled by two in both
+// directions.
+unsafe fn fill_frame(frame: *mut AVFrame, offset: c_int) {
+    for plane in 0..3 {
+        let (width, height) = if plane == 0 {
+            (WIDTH, HEIGHT)
+        } else {
+            (WIDTH / 2, HEIGHT / 2)
+        };
+        let data = (*frame).data[plane];
+        let linesize = (*frame).linesize[plane];
+
+        for y in 0..height {
+            let row = data.offset((y * linesize) as isize);
+
+            for x in 0..width {
+                let bar = ((x + offset * width / WIDTH) % width * 7 / width) as usize;
+                let (luma, cb, cr) = BARS[bar];
+                *row.offset(x as isize) = match plane {
+                    0 => luma,
+                    1 => cb,
+                    _ => cr,
+                };
+            }
+        }
+    }
+}
+
+// Writes every packet the encoder has ready. EAGAIN means it needs another frame, EOF that it
+// has been drained after a null frame.
+unsafe fn write_packets(
+    format: *mut AVFormatContext,
+    stream: *mut AVStream,
+    encoder: *mut AVCodecContext,
+    packet: *mut AVPacket,
+) {
+    loop {
+        let ret = avcodec_receive_packet(encoder, packet);
+        if ret == AVERROR(EAGAIN) || ret == AVERROR_EOF {
+            return;
+        }
+        check(ret, "avcodec_receive_packet");
+
+        // the muxer may have picked another time base for the stream in avformat_write_header
+        av_packet_rescale_ts(packet, (*encoder).time_base, (*stream).time_base);
+        (*packet).stream_index = (*stream).index;
+
+        // takes the packet's reference and leaves it blank, whether it fails or not
+        check(
+            av_interleaved_write_frame(format, packet),
+            "av_interleaved_write_frame",
+        );
+    }
+}
+
+fn main() {
+    let path = env::args()
+        .nth(1)
+        .unwrap_or_else(|| String::from("color_bars.mp4"));
+    let path = CString::new(path).expect("path contains a NUL byte");
+
+    unsafe {
+        // the muxer is guessed from the extension of the path
+        let mut format = ptr::null_mut();
+        check(
+            avformat_alloc_output_context2(
+                &mut format,
+                ptr::null_mut(),
+                ptr::null(),
+                path.as_ptr(),
+            ),
+            "avformat_alloc_output_context2",
+        );
+
+        let codec = avcodec_find_encoder(AVCodecID::AV_CODEC_ID_MPEG4);
+        if codec.is_null() {
+            eprintln!("FFmpeg was built without the mpeg4 encoder");
+            process::exit(1);
+        }
+
+        // the stream belongs to the format context and is freed with it
+        let stream = avformat_new_stream(format, ptr::null());
+        if stream.is_null() {
+            eprintln!("could not add a stream");
+            process::exit(1);
+        }
+
+        let mut encoder = avcodec_alloc_context3(codec);
+        (*encoder).width = WIDTH;
+        (*encoder).height = HEIGHT;
+        (*encoder).pix_fmt = AVPixelFormat::AV_PIX_FMT_YUV420P;
+        (*encoder).time_base = AVRational { num: 1, den: 25 };
+        (*encoder).framerate = AVRational { num: 25, den: 1 };
+        (*encoder).bit_rate = 400_000;
+        (*encoder).gop_size = 12;
+
+        // mp4 keeps the codec headers in the sample description instead of the bitstream
+        if (*(*format).oformat).flags & AVFMT_GLOBALHEADER as c_int != 0 {
+            (*encoder).flags |= AV_CODEC_FLAG_GLOBAL_HEADER as c_int;
+        }
+
+        check(
+            avcodec_open2(encoder, codec, ptr::null_mut()),
+            "avcodec_open2",
+        );
+        check(
+            avcodec_parameters_from_context((*stream).codecpar, encoder),
+            "avcodec_parameters_from_context",
+        );
+        (*stream).time_base = (*encoder).time_base;
+
+        if (*(*format).oformat).flags & AVFMT_NOFILE as c_int == 0 {
+            check(
+                avio_open(&mut (*format).pb, path.as_ptr(), AVIO_FLAG_WRITE as c_int),
+                "avio_open",
+            );
+        }
+
+        check(
+            avformat_write_header(format, ptr::null_mut()),
+            "avformat_write_header",
+        );
+
+        let mut frame = av_frame_alloc();
+        (*frame).width = WIDTH;
+        (*frame).height = HEIGHT;
+        (*frame).format = AVPixelFormat::AV_PIX_FMT_YUV420P as c_int;
+        check(av_frame_get_buffer(frame, 0), "av_frame_get_buffer");
+
+        let mut packet = av_packet_alloc();
+
+        for pts in 0..FRAMES {
+            // the encoder may still reference the buffers of the previous frame, this copies
+            // them if it does
+            check(av_frame_make_writable(frame), "av_frame_make_writable");
+            fill_frame(frame, (pts * 4) as c_int);
+            (*frame).pts = pts;
+
+            check(avcodec_send_frame(encoder, frame), "avcodec_send_frame");
+            write_packets(format, stream, encoder, packet);
+        }
+
+        // a null frame flushes the encoder, it then returns its delayed packets and finally
+        // AVERROR_EOF
+        check(
+            avcodec_send_frame(encoder, ptr::null()),
+            "avcodec_send_frame",
+        );
+        write_packets(format, stream, encoder, packet);
+
+        check(av_write_trailer(format), "av_write_trailer");
+
+        av_packet_free(&mut packet);
+        av_frame_free(&mut frame);
+        avcodec_free_context(&mut encoder);
+        if (*(*format).oformat).flags & AVFMT_NOFILE as c_int == 0 {
+            avio_closep(&mut (*format).pb);
+        }
+        avformat_free_context(format);
+
+        println!("wrote {} frames to {:?}", FRAMES, path);
+    }
+}
@@ -0,0 +1,83 @@
+//! Prints the streams and metadata of a file, like the header ffprobe prints.
+//!
+//!     cargo run --example metadata -- input.mp4
+
+extern crate ffmpeg_sys;
+extern crate libc;
+
+use std::env;
+use std::ffi::{CStr, CString};
+use std::process;
+use std::ptr;
+
+use ffmpeg_sys::*;
+use libc::{c_char, c_int};
+
+fn error_string(err: c_int) -> String {
+    let mut buf = [0 as c_char; 64];
+
+    unsafe {
+        av_strerror(err, buf.as_mut_ptr(), buf.len());
+        CStr::from_ptr(buf.as_ptr()).to_string_lossy().into_owned()
+    }
+}
+
+fn main() {
+    let path = match env::args().nth(1) {
+        Some(path) => path,
+        None => {
+            eprintln!("usage: metadata <input>");
+            process::exit(2);
+        }
+    };
+    let path = CString::new(path).expect("path contains a NUL byte");
+
+    unsafe {
+        // avformat_open_input allocates the context when it's null, and frees it and sets it
+        // back to null when it fails
+        let mut context = ptr::null_mut();
+        let ret = avformat_open_input(
+            &mut context,
+            path.as_ptr(),
+            ptr::null_mut(),
+            ptr::null_mut(),
+        );
+        if ret < 0 {
+            eprintln!("could not open {:?}: {}", path, error_string(ret));
+            process::exit(1);
+        }
+
+        // formats without a header, like raw streams, only know their streams after decoding
+        // a few packets
+        let ret = avformat_find_stream_info(context, ptr::null_mut());
+        if ret < 0 {
+            eprintln!("could not find the stream info: {}", error_string(ret));
+            avformat_close_input(&mut context);
+            process::exit(1);
+        }
+
+        av_dump_format(context, 0, path.as_ptr(), 0);
+
+        // the format level tags, those of each stream are in AVStream.metadata
+        let mut tag: *mut AVDictionaryEntry = ptr::null_mut();
+        loop {
+            tag = av_dict_get(
+                (*context).metadata,
+                b"\0".as_ptr() as *const c_char,
+                tag,
+                AV_DICT_IGNORE_SUFFIX as c_int,
+            );
+            if tag.is_null() {
+                break;
+            }
+
+            println!(
+                "{}={}",
+                CStr::from_ptr((*tag).key).to_string_lossy(),
+                CStr::from_ptr((*tag).value).to_string_lossy()
+            );
+        }
+
+        avformat_close_input(&mut context);
+    }
+}
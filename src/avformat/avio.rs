@@ -0,0 +1,82 @@
+use libc::{c_int, c_void};
+use {avio_alloc_context, AVIOContext};
+#[cfg(feature = "has_avio_dir")]
+use AVIODirEntryType;
+
+/// The `read_packet` callback of `avio_alloc_context`. Returns the number of bytes read, or a
+/// negative AVERROR, `AVERROR_EOF` at the end of the stream.
+pub type AvioReadFn =
+    unsafe extern "C" fn(opaque: *mut c_void, buf: *mut u8, buf_size: c_int) -> c_int;
+
+/// The `write_packet` callback of `avio_alloc_context`. Returns the number of bytes written, or a
+/// negative AVERROR. The buffer is const since FF_API_AVIO_WRITE_NONCONST (FFmpeg 7.0).
+#[cfg(any(
+    feature = "ff_api_avio_write_nonconst",
+    not(feature = "ff_api_avio_write_nonconst_is_defined")
+))]
+pub type AvioWriteFn =
+    unsafe extern "C" fn(opaque: *mut c_void, buf: *mut u8, buf_size: c_int) -> c_int;
+
+/// The `write_packet` callback of `avio_alloc_context`. Returns the number of bytes written, or a
+/// negative AVERROR. The buffer is const since FF_API_AVIO_WRITE_NONCONST (FFmpeg 7.0).
+#[cfg(all(
+    not(feature = "ff_api_avio_write_nonconst"),
+    feature = "ff_api_avio_write_nonconst_is_defined"
+))]
+pub type AvioWriteFn =
+    unsafe extern "C" fn(opaque: *mut c_void, buf: *const u8, buf_size: c_int) -> c_int;
+
+/// The `seek` callback of `avio_alloc_context`. `whence` is one of `SEEK_SET`, `SEEK_CUR` and
+/// `SEEK_END`, possibly or'ed with `AVSEEK_FORCE`, or `AVSEEK_SIZE` to ask for the size of the
+/// stream without seeking. Returns the new position or the size, or a negative AVERROR.
+pub type AvioSeekFn =
+    unsafe extern "C" fn(opaque: *mut c_void, offset: i64, whence: c_int) -> i64;
+
+/// `avio_alloc_context` with the callbacks spelled out, so a change of their signatures in the
+/// headers fails to compile here instead of in every caller.
+#[inline(always)]
+pub unsafe fn avio_alloc_context_rust(
+    buffer: *mut u8,
+    buffer_size: c_int,
+    write_flag: c_int,
+    opaque: *mut c_void,
+    read_packet: Option<AvioReadFn>,
+    write_packet: Option<AvioWriteFn>,
+    seek: Option<AvioSeekFn>,
+) -> *mut AVIOContext {
+    avio_alloc_context(
+        buffer,
+        buffer_size,
+        write_flag,
+        opaque,
+        read_packet,
+        write_packet,
+        seek,
+    )
+}
+
+#[cfg(feature = "has_avio_dir")]
+impl AVIODirEntryType {
+    /// The type of an `AVIODirEntry`, which is a plain int. Transmuting it would be undefined
+    /// behavior for the types a newer library adds, those are `None`.
+    pub fn from_raw(value: c_int) -> Option<Self> {
+        use self::AVIODirEntryType::*;
+
+        [
+            AVIO_ENTRY_UNKNOWN,
+            AVIO_ENTRY_BLOCK_DEVICE,
+            AVIO_ENTRY_CHARACTER_DEVICE,
+            AVIO_ENTRY_DIRECTORY,
+            AVIO_ENTRY_NAMED_PIPE,
+            AVIO_ENTRY_SYMBOLIC_LINK,
+            AVIO_ENTRY_SOCKET,
+            AVIO_ENTRY_FILE,
+            AVIO_ENTRY_SERVER,
+            AVIO_ENTRY_SHARE,
+            AVIO_ENTRY_WORKGROUP,
+        ]
+        .iter()
+        .cloned()
+        .find(|&entry_type| entry_type as c_int == value)
+    }
+}
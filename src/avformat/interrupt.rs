@@ -0,0 +1,40 @@
+// FFmpeg polls the interrupt callback of an AVFormatContext from the thread that runs the
+// blocking call (avformat_open_input, av_read_frame, ...), and copies it into the contexts it
+// opens itself, like the segments of HLS. Whatever the opaque pointer refers to has to outlive
+// the AVFormatContext and be safe to read from that thread while others write it.
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use libc::{c_int, c_void};
+use AVIOInterruptCB;
+
+/// The `callback` of `AVIOInterruptCB`. Returning non-zero aborts the blocking operation, which
+/// then fails with `AVERROR_EXIT`. It's called often, it has to return quickly.
+pub type AvioInterruptFn = unsafe extern "C" fn(opaque: *mut c_void) -> c_int;
+
+unsafe extern "C" fn interrupt_on_flag(opaque: *mut c_void) -> c_int {
+    (*(opaque as *const AtomicBool)).load(Ordering::Relaxed) as c_int
+}
+
+/// An interrupt callback that aborts the blocking operations once `flag` is set, from any
+/// thread. Set it as `AVFormatContext.interrupt_callback` of a context from
+/// `avformat_alloc_context` before `avformat_open_input`, or pass it to `avio_open2`.
+pub fn interrupt_callback_from_flag(flag: &'static AtomicBool) -> AVIOInterruptCB {
+    AVIOInterruptCB {
+        callback: Some(interrupt_on_flag),
+        opaque: flag as *const AtomicBool as *mut c_void,
+    }
+}
+
+/// An interrupt callback from a function and its opaque pointer. FFmpeg keeps the pointer
+/// without knowing its lifetime, it has to stay valid until the `AVFormatContext` or
+/// `AVIOContext` is closed.
+pub fn interrupt_callback_from_fn(
+    callback: AvioInterruptFn,
+    opaque: *mut c_void,
+) -> AVIOInterruptCB {
+    AVIOInterruptCB {
+        callback: Some(callback),
+        opaque,
+    }
+}
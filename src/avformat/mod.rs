@@ -0,0 +1,5 @@
+mod avio;
+pub use self::avio::*;
+
+mod interrupt;
+pub use self::interrupt::*;
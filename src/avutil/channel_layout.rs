@@ -0,0 +1,142 @@
+#[cfg(feature = "has_av_channel_layout")]
+use core::fmt;
+
+#[cfg(feature = "has_av_channel_layout")]
+use {AVChannelLayout, AVChannelOrder};
+
+// the masks are part of the ABI, but newer headers define them in terms of the AVChannel enum,
+// which bindgen can't evaluate
+
+pub const AV_CH_FRONT_LEFT: u64 = 1 << 0;
+pub const AV_CH_FRONT_RIGHT: u64 = 1 << 1;
+pub const AV_CH_FRONT_CENTER: u64 = 1 << 2;
+pub const AV_CH_LOW_FREQUENCY: u64 = 1 << 3;
+pub const AV_CH_BACK_LEFT: u64 = 1 << 4;
+pub const AV_CH_BACK_RIGHT: u64 = 1 << 5;
+pub const AV_CH_FRONT_LEFT_OF_CENTER: u64 = 1 << 6;
+pub const AV_CH_FRONT_RIGHT_OF_CENTER: u64 = 1 << 7;
+pub const AV_CH_BACK_CENTER: u64 = 1 << 8;
+pub const AV_CH_SIDE_LEFT: u64 = 1 << 9;
+pub const AV_CH_SIDE_RIGHT: u64 = 1 << 10;
+pub const AV_CH_TOP_CENTER: u64 = 1 << 11;
+pub const AV_CH_TOP_FRONT_LEFT: u64 = 1 << 12;
+pub const AV_CH_TOP_FRONT_CENTER: u64 = 1 << 13;
+pub const AV_CH_TOP_FRONT_RIGHT: u64 = 1 << 14;
+pub const AV_CH_TOP_BACK_LEFT: u64 = 1 << 15;
+pub const AV_CH_TOP_BACK_CENTER: u64 = 1 << 16;
+pub const AV_CH_TOP_BACK_RIGHT: u64 = 1 << 17;
+pub const AV_CH_STEREO_LEFT: u64 = 1 << 29;
+pub const AV_CH_STEREO_RIGHT: u64 = 1 << 30;
+pub const AV_CH_WIDE_LEFT: u64 = 1 << 31;
+pub const AV_CH_WIDE_RIGHT: u64 = 1 << 32;
+pub const AV_CH_SURROUND_DIRECT_LEFT: u64 = 1 << 33;
+pub const AV_CH_SURROUND_DIRECT_RIGHT: u64 = 1 << 34;
+pub const AV_CH_LOW_FREQUENCY_2: u64 = 1 << 35;
+pub const AV_CH_TOP_SIDE_LEFT: u64 = 1 << 36;
+pub const AV_CH_TOP_SIDE_RIGHT: u64 = 1 << 37;
+pub const AV_CH_BOTTOM_FRONT_CENTER: u64 = 1 << 38;
+pub const AV_CH_BOTTOM_FRONT_LEFT: u64 = 1 << 39;
+pub const AV_CH_BOTTOM_FRONT_RIGHT: u64 = 1 << 40;
+pub const AV_CH_SIDE_SURROUND_LEFT: u64 = 1 << 41;
+pub const AV_CH_SIDE_SURROUND_RIGHT: u64 = 1 << 42;
+pub const AV_CH_TOP_SURROUND_LEFT: u64 = 1 << 43;
+pub const AV_CH_TOP_SURROUND_RIGHT: u64 = 1 << 44;
+pub const AV_CH_BINAURAL_LEFT: u64 = 1 << 61;
+pub const AV_CH_BINAURAL_RIGHT: u64 = 1 << 62;
+
+pub const AV_CH_LAYOUT_NATIVE: u64 = 0x8000000000000000;
+
+pub const AV_CH_LAYOUT_MONO: u64 = AV_CH_FRONT_CENTER;
+pub const AV_CH_LAYOUT_STEREO: u64 = AV_CH_FRONT_LEFT | AV_CH_FRONT_RIGHT;
+pub const AV_CH_LAYOUT_2POINT1: u64 = AV_CH_LAYOUT_STEREO | AV_CH_LOW_FREQUENCY;
+pub const AV_CH_LAYOUT_2_1: u64 = AV_CH_LAYOUT_STEREO | AV_CH_BACK_CENTER;
+pub const AV_CH_LAYOUT_SURROUND: u64 = AV_CH_LAYOUT_STEREO | AV_CH_FRONT_CENTER;
+pub const AV_CH_LAYOUT_3POINT1: u64 = AV_CH_LAYOUT_SURROUND | AV_CH_LOW_FREQUENCY;
+pub const AV_CH_LAYOUT_4POINT0: u64 = AV_CH_LAYOUT_SURROUND | AV_CH_BACK_CENTER;
+pub const AV_CH_LAYOUT_4POINT1: u64 = AV_CH_LAYOUT_4POINT0 | AV_CH_LOW_FREQUENCY;
+pub const AV_CH_LAYOUT_2_2: u64 = AV_CH_LAYOUT_STEREO | AV_CH_SIDE_LEFT | AV_CH_SIDE_RIGHT;
+pub const AV_CH_LAYOUT_QUAD: u64 = AV_CH_LAYOUT_STEREO | AV_CH_BACK_LEFT | AV_CH_BACK_RIGHT;
+pub const AV_CH_LAYOUT_5POINT0: u64 = AV_CH_LAYOUT_SURROUND | AV_CH_SIDE_LEFT | AV_CH_SIDE_RIGHT;
+pub const AV_CH_LAYOUT_5POINT1: u64 = AV_CH_LAYOUT_5POINT0 | AV_CH_LOW_FREQUENCY;
+pub const AV_CH_LAYOUT_5POINT0_BACK: u64 =
+    AV_CH_LAYOUT_SURROUND | AV_CH_BACK_LEFT | AV_CH_BACK_RIGHT;
+pub const AV_CH_LAYOUT_5POINT1_BACK: u64 = AV_CH_LAYOUT_5POINT0_BACK | AV_CH_LOW_FREQUENCY;
+pub const AV_CH_LAYOUT_6POINT0: u64 = AV_CH_LAYOUT_5POINT0 | AV_CH_BACK_CENTER;
+pub const AV_CH_LAYOUT_6POINT0_FRONT: u64 =
+    AV_CH_LAYOUT_2_2 | AV_CH_FRONT_LEFT_OF_CENTER | AV_CH_FRONT_RIGHT_OF_CENTER;
+pub const AV_CH_LAYOUT_HEXAGONAL: u64 = AV_CH_LAYOUT_5POINT0_BACK | AV_CH_BACK_CENTER;
+pub const AV_CH_LAYOUT_3POINT1POINT2: u64 =
+    AV_CH_LAYOUT_3POINT1 | AV_CH_TOP_FRONT_LEFT | AV_CH_TOP_FRONT_RIGHT;
+pub const AV_CH_LAYOUT_6POINT1: u64 = AV_CH_LAYOUT_5POINT1 | AV_CH_BACK_CENTER;
+pub const AV_CH_LAYOUT_6POINT1_BACK: u64 = AV_CH_LAYOUT_5POINT1_BACK | AV_CH_BACK_CENTER;
+pub const AV_CH_LAYOUT_6POINT1_FRONT: u64 = AV_CH_LAYOUT_6POINT0_FRONT | AV_CH_LOW_FREQUENCY;
+pub const AV_CH_LAYOUT_7POINT0: u64 = AV_CH_LAYOUT_5POINT0 | AV_CH_BACK_LEFT | AV_CH_BACK_RIGHT;
+pub const AV_CH_LAYOUT_7POINT0_FRONT: u64 =
+    AV_CH_LAYOUT_5POINT0 | AV_CH_FRONT_LEFT_OF_CENTER | AV_CH_FRONT_RIGHT_OF_CENTER;
+pub const AV_CH_LAYOUT_7POINT1: u64 = AV_CH_LAYOUT_5POINT1 | AV_CH_BACK_LEFT | AV_CH_BACK_RIGHT;
+pub const AV_CH_LAYOUT_7POINT1_WIDE: u64 =
+    AV_CH_LAYOUT_5POINT1 | AV_CH_FRONT_LEFT_OF_CENTER | AV_CH_FRONT_RIGHT_OF_CENTER;
+pub const AV_CH_LAYOUT_7POINT1_WIDE_BACK: u64 =
+    AV_CH_LAYOUT_5POINT1_BACK | AV_CH_FRONT_LEFT_OF_CENTER | AV_CH_FRONT_RIGHT_OF_CENTER;
+pub const AV_CH_LAYOUT_5POINT1POINT2: u64 =
+    AV_CH_LAYOUT_5POINT1 | AV_CH_TOP_FRONT_LEFT | AV_CH_TOP_FRONT_RIGHT;
+pub const AV_CH_LAYOUT_5POINT1POINT2_BACK: u64 =
+    AV_CH_LAYOUT_5POINT1_BACK | AV_CH_TOP_FRONT_LEFT | AV_CH_TOP_FRONT_RIGHT;
+pub const AV_CH_LAYOUT_7POINT1_TOP_BACK: u64 = AV_CH_LAYOUT_5POINT1POINT2_BACK;
+pub const AV_CH_LAYOUT_OCTAGONAL: u64 =
+    AV_CH_LAYOUT_5POINT0 | AV_CH_BACK_LEFT | AV_CH_BACK_CENTER | AV_CH_BACK_RIGHT;
+pub const AV_CH_LAYOUT_CUBE: u64 = AV_CH_LAYOUT_QUAD
+    | AV_CH_TOP_FRONT_LEFT
+    | AV_CH_TOP_FRONT_RIGHT
+    | AV_CH_TOP_BACK_LEFT
+    | AV_CH_TOP_BACK_RIGHT;
+pub const AV_CH_LAYOUT_5POINT1POINT4_BACK: u64 =
+    AV_CH_LAYOUT_5POINT1POINT2_BACK | AV_CH_TOP_BACK_LEFT | AV_CH_TOP_BACK_RIGHT;
+pub const AV_CH_LAYOUT_7POINT1POINT2: u64 =
+    AV_CH_LAYOUT_7POINT1 | AV_CH_TOP_FRONT_LEFT | AV_CH_TOP_FRONT_RIGHT;
+pub const AV_CH_LAYOUT_7POINT1POINT4_BACK: u64 =
+    AV_CH_LAYOUT_7POINT1POINT2 | AV_CH_TOP_BACK_LEFT | AV_CH_TOP_BACK_RIGHT;
+pub const AV_CH_LAYOUT_7POINT2POINT3: u64 =
+    AV_CH_LAYOUT_7POINT1POINT2 | AV_CH_TOP_BACK_CENTER | AV_CH_LOW_FREQUENCY_2;
+pub const AV_CH_LAYOUT_9POINT1POINT4_BACK: u64 =
+    AV_CH_LAYOUT_7POINT1POINT4_BACK | AV_CH_FRONT_LEFT_OF_CENTER | AV_CH_FRONT_RIGHT_OF_CENTER;
+pub const AV_CH_LAYOUT_9POINT1POINT6: u64 =
+    AV_CH_LAYOUT_9POINT1POINT4_BACK | AV_CH_TOP_SIDE_LEFT | AV_CH_TOP_SIDE_RIGHT;
+pub const AV_CH_LAYOUT_HEXADECAGONAL: u64 = AV_CH_LAYOUT_OCTAGONAL
+    | AV_CH_WIDE_LEFT
+    | AV_CH_WIDE_RIGHT
+    | AV_CH_TOP_BACK_LEFT
+    | AV_CH_TOP_BACK_RIGHT
+    | AV_CH_TOP_BACK_CENTER
+    | AV_CH_TOP_FRONT_CENTER
+    | AV_CH_TOP_FRONT_LEFT
+    | AV_CH_TOP_FRONT_RIGHT;
+pub const AV_CH_LAYOUT_BINAURAL: u64 = AV_CH_BINAURAL_LEFT | AV_CH_BINAURAL_RIGHT;
+pub const AV_CH_LAYOUT_STEREO_DOWNMIX: u64 = AV_CH_STEREO_LEFT | AV_CH_STEREO_RIGHT;
+pub const AV_CH_LAYOUT_22POINT2: u64 = AV_CH_LAYOUT_9POINT1POINT4_BACK
+    | AV_CH_BACK_CENTER
+    | AV_CH_LOW_FREQUENCY_2
+    | AV_CH_TOP_FRONT_CENTER
+    | AV_CH_TOP_CENTER
+    | AV_CH_TOP_SIDE_LEFT
+    | AV_CH_TOP_SIDE_RIGHT
+    | AV_CH_TOP_BACK_CENTER
+    | AV_CH_BOTTOM_FRONT_CENTER
+    | AV_CH_BOTTOM_FRONT_LEFT
+    | AV_CH_BOTTOM_FRONT_RIGHT;
+
+// bindgen can't derive Debug through the union, which of its fields is valid depends on the order
+#[cfg(feature = "has_av_channel_layout")]
+impl fmt::Debug for AVChannelLayout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut debug = f.debug_struct("AVChannelLayout");
+        debug.field("order", &self.order).field("nb_channels", &self.nb_channels);
+        unsafe {
+            match self.order {
+                AVChannelOrder::AV_CHANNEL_ORDER_CUSTOM => debug.field("map", &self.u.map),
+                _ => debug.field("mask", &self.u.mask),
+            };
+        }
+        debug.field("opaque", &self.opaque).finish()
+    }
+}
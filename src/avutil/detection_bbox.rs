@@ -0,0 +1,11 @@
+use libc::c_uint;
+use {AVDetectionBBox, AVDetectionBBoxHeader};
+
+#[inline(always)]
+pub unsafe fn av_get_detection_bbox(
+    header: *const AVDetectionBBoxHeader,
+    idx: c_uint,
+) -> *mut AVDetectionBBox {
+    (header as *mut u8).offset(((*header).bboxes_offset + idx as usize * (*header).bbox_size) as isize)
+        as *mut AVDetectionBBox
+}
@@ -0,0 +1,16 @@
+use {AVDOVIColorMetadata, AVDOVIDataMapping, AVDOVIMetadata, AVDOVIRpuDataHeader};
+
+#[inline(always)]
+pub unsafe fn av_dovi_get_header(data: *const AVDOVIMetadata) -> *mut AVDOVIRpuDataHeader {
+    (data as *mut u8).offset((*data).header_offset as isize) as *mut AVDOVIRpuDataHeader
+}
+
+#[inline(always)]
+pub unsafe fn av_dovi_get_mapping(data: *const AVDOVIMetadata) -> *mut AVDOVIDataMapping {
+    (data as *mut u8).offset((*data).mapping_offset as isize) as *mut AVDOVIDataMapping
+}
+
+#[inline(always)]
+pub unsafe fn av_dovi_get_color(data: *const AVDOVIMetadata) -> *mut AVDOVIColorMetadata {
+    (data as *mut u8).offset((*data).color_offset as isize) as *mut AVDOVIColorMetadata
+}
@@ -0,0 +1,36 @@
+// Opaque Direct3D 11 interfaces, cast pointers to and from the winapi/windows-rs types.
+
+#[repr(C)]
+pub struct ID3D11Device {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct ID3D11DeviceContext {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct ID3D11VideoDevice {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct ID3D11VideoContext {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct ID3D11Texture2D {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct ID3D11VideoDecoder {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct ID3D11VideoDecoderOutputView {
+    _private: [u8; 0],
+}
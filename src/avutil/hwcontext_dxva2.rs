@@ -0,0 +1,18 @@
+// Opaque Direct3D 9/DXVA2 interfaces, cast pointers to and from the winapi/windows-rs types.
+
+#[repr(C)]
+pub struct IDirect3DDeviceManager9 {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct IDirect3DSurface9 {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct IDirectXVideoDecoder {
+    _private: [u8; 0],
+}
+
+pub type LPDIRECT3DSURFACE9 = *mut IDirect3DSurface9;
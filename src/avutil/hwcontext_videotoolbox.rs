@@ -0,0 +1,17 @@
+// Opaque CoreVideo/CoreMedia handles, layout compatible with the core-foundation crates.
+
+#[repr(C)]
+pub struct __CVBuffer {
+    _private: [u8; 0],
+}
+
+pub type CVBufferRef = *mut __CVBuffer;
+pub type CVImageBufferRef = CVBufferRef;
+pub type CVPixelBufferRef = CVImageBufferRef;
+
+#[repr(C)]
+pub struct opaqueCMSampleBuffer {
+    _private: [u8; 0],
+}
+
+pub type CMSampleBufferRef = *mut opaqueCMSampleBuffer;
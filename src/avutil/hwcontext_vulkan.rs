@@ -0,0 +1,37 @@
+// Vulkan handles as declared by ash: dispatchable handles are opaque pointers and
+// non-dispatchable handles are 64-bit integers regardless of the target pointer width.
+
+#[repr(C)]
+pub struct VkInstance_T {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct VkPhysicalDevice_T {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct VkDevice_T {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct VkQueue_T {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct VkCommandBuffer_T {
+    _private: [u8; 0],
+}
+
+pub type VkInstance = *mut VkInstance_T;
+pub type VkPhysicalDevice = *mut VkPhysicalDevice_T;
+pub type VkDevice = *mut VkDevice_T;
+pub type VkQueue = *mut VkQueue_T;
+pub type VkCommandBuffer = *mut VkCommandBuffer_T;
+
+pub type VkImage = u64;
+pub type VkDeviceMemory = u64;
+pub type VkSemaphore = u64;
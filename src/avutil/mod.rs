@@ -12,3 +12,44 @@ pub use self::rational::*;
 
 mod pixfmt;
 pub use self::pixfmt::*;
+
+mod channel_layout;
+pub use self::channel_layout::*;
+
+mod opt;
+pub use self::opt::*;
+
+#[cfg(feature = "has_detection_bbox")]
+mod detection_bbox;
+#[cfg(feature = "has_detection_bbox")]
+pub use self::detection_bbox::*;
+
+#[cfg(feature = "has_dovi_metadata")]
+mod dovi_meta;
+#[cfg(feature = "has_dovi_metadata")]
+pub use self::dovi_meta::*;
+
+#[cfg(feature = "has_video_enc_params")]
+mod video_enc_params;
+#[cfg(feature = "has_video_enc_params")]
+pub use self::video_enc_params::*;
+
+#[cfg(feature = "hwcontext-d3d11")]
+mod hwcontext_d3d11va;
+#[cfg(feature = "hwcontext-d3d11")]
+pub use self::hwcontext_d3d11va::*;
+
+#[cfg(feature = "hwcontext-d3d11")]
+mod hwcontext_dxva2;
+#[cfg(feature = "hwcontext-d3d11")]
+pub use self::hwcontext_dxva2::*;
+
+#[cfg(feature = "hwcontext-videotoolbox")]
+mod hwcontext_videotoolbox;
+#[cfg(feature = "hwcontext-videotoolbox")]
+pub use self::hwcontext_videotoolbox::*;
+
+#[cfg(feature = "hwcontext-vulkan")]
+mod hwcontext_vulkan;
+#[cfg(feature = "hwcontext-vulkan")]
+pub use self::hwcontext_vulkan::*;
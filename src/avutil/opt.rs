@@ -0,0 +1,78 @@
+use core::ptr;
+
+use libc::{c_char, c_int, c_void};
+use {av_d2q, AVOption, AVOptionRange, AVOptionRanges, AVOptionType, AVRational};
+
+// or'ed into the type of the array options of FFmpeg 7, which have no variant of their own
+const TYPE_FLAG_ARRAY: c_int = 1 << 16;
+
+/// The default value of an `AVOption`, read from the member of its `default_val` union that
+/// the type of the option uses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AVOptionDefault {
+    /// The integer types, the flags, the enums like the pixel formats, durations and constants.
+    Int(i64),
+    Double(f64),
+    /// Stored as a double, converted with `av_d2q` like `av_opt_set_defaults` does.
+    Rational(AVRational),
+    /// The string types, also image sizes, video rates, colors, dictionaries and channel
+    /// layouts given by their description. Null for no default, owned by the `AVClass`.
+    Str(*const c_char),
+    /// Points to the `AVOptionArrayDef` of an array option.
+    Array(*const c_void),
+}
+
+impl AVOption {
+    /// The default value of the option.
+    ///
+    /// # Safety
+    ///
+    /// The option has to come from an `AVClass`, like `av_opt_next` or `av_opt_find` return
+    /// them.
+    pub unsafe fn default_value(&self) -> AVOptionDefault {
+        use AVOptionType::*;
+
+        // read as an int, an array option isn't a valid AVOptionType
+        let type_ = ptr::read(&self.type_ as *const AVOptionType as *const c_int);
+        let value = &self.default_val as *const _ as *const u8;
+
+        if type_ & TYPE_FLAG_ARRAY != 0 {
+            return AVOptionDefault::Array(ptr::read(value as *const *const c_void));
+        }
+
+        let strings = [
+            AV_OPT_TYPE_STRING,
+            AV_OPT_TYPE_BINARY,
+            AV_OPT_TYPE_DICT,
+            AV_OPT_TYPE_IMAGE_SIZE,
+            AV_OPT_TYPE_VIDEO_RATE,
+            AV_OPT_TYPE_COLOR,
+            #[cfg(feature = "avutil_version_greater_than_57_23")]
+            AV_OPT_TYPE_CHLAYOUT,
+        ];
+        let is = |types: &[AVOptionType]| types.iter().any(|&t| t as c_int == type_);
+
+        if is(&strings) {
+            AVOptionDefault::Str(ptr::read(value as *const *const c_char))
+        } else if is(&[AV_OPT_TYPE_DOUBLE, AV_OPT_TYPE_FLOAT]) {
+            AVOptionDefault::Double(ptr::read(value as *const f64))
+        } else if is(&[AV_OPT_TYPE_RATIONAL]) {
+            AVOptionDefault::Rational(av_d2q(ptr::read(value as *const f64), c_int::MAX))
+        } else {
+            AVOptionDefault::Int(ptr::read(value as *const i64))
+        }
+    }
+}
+
+/// The range at `index` of `component`, `av_opt_query_ranges` stores them as
+/// `range[nb_ranges * component + index]`. Valid until `av_opt_freep_ranges`.
+#[inline(always)]
+pub unsafe fn av_opt_ranges_get(
+    ranges: *const AVOptionRanges,
+    component: c_int,
+    index: c_int,
+) -> *mut AVOptionRange {
+    *(*ranges)
+        .range
+        .offset(((*ranges).nb_ranges * component + index) as isize)
+}
@@ -0,0 +1,11 @@
+use libc::c_uint;
+use {AVVideoBlockParams, AVVideoEncParams};
+
+#[inline(always)]
+pub unsafe fn av_video_enc_params_block(
+    par: *mut AVVideoEncParams,
+    idx: c_uint,
+) -> *mut AVVideoBlockParams {
+    (par as *mut u8).offset(((*par).blocks_offset + idx as usize * (*par).block_size) as isize)
+        as *mut AVVideoBlockParams
+}
@@ -0,0 +1,91 @@
+// the generated loader has a method for each function, the deprecated ones included
+#![allow(deprecated)]
+
+use std::error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use libloading;
+
+/// Why the libraries couldn't be loaded.
+#[derive(Debug)]
+pub enum Error {
+    /// None of the files tried for the library could be loaded.
+    NotFound {
+        library: &'static str,
+        tried: Vec<(PathBuf, libloading::Error)>,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::NotFound {
+                library,
+                ref tried,
+            } => {
+                write!(f, "couldn't load lib{}, tried", library)?;
+                for &(ref path, ref err) in tried {
+                    write!(f, "\n    {}: {}", path.display(), err)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl Library {
+    /// Loads the libraries of the enabled features from the first of `paths` that has them, or
+    /// through the search path of the system when `paths` is empty.
+    ///
+    /// Only the major versions of the headers are loaded, those of a single FFmpeg release: the
+    /// structs of the bindings don't match the libraries of another one. The methods of a
+    /// function the loaded libraries don't export panic, its field holds the error instead of
+    /// the pointer.
+    pub fn load(paths: &[&Path]) -> Result<Library, Error> {
+        let mut libraries = Vec::new();
+        for &(name, major) in LIBRARIES {
+            libraries.push(open(name, major, paths)?);
+        }
+
+        Ok(Library::resolve(libraries))
+    }
+}
+
+// the file name of lib<name> with the given major version
+fn file_name(name: &str, major: u32) -> String {
+    if cfg!(target_os = "windows") {
+        format!("{}-{}.dll", name, major)
+    } else if cfg!(target_os = "macos") {
+        format!("lib{}.{}.dylib", name, major)
+    } else {
+        format!("lib{}.so.{}", name, major)
+    }
+}
+
+fn open(name: &'static str, major: u32, paths: &[&Path]) -> Result<libloading::Library, Error> {
+    let file_name = file_name(name, major);
+    let candidates: Vec<PathBuf> = if paths.is_empty() {
+        vec![PathBuf::from(&file_name)]
+    } else {
+        paths.iter().map(|dir| dir.join(&file_name)).collect()
+    };
+
+    let mut tried = Vec::new();
+    for path in candidates {
+        // the initializers of FFmpeg's libraries only set up static tables
+        match unsafe { libloading::Library::new(&path) } {
+            Ok(library) => return Ok(library),
+            Err(err) => tried.push((path, err)),
+        }
+    }
+
+    Err(Error::NotFound {
+        library: name,
+        tried,
+    })
+}
+
+include!(concat!(env!("OUT_DIR"), "/dlopen.rs"));
@@ -1,11 +1,80 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![allow(non_upper_case_globals)]
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
 
+// the bindings use core paths, which edition 2015 only resolves with no_std
+#[cfg(any(feature = "std", test))]
+extern crate core;
 extern crate libc;
+#[cfg(feature = "dlopen")]
+extern crate libloading;
 
-include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+// with `tools-only` only the programs are built, the crate is left with the FFMPEG_SOURCE_ID of
+// versions.rs and everything else is configured out
+include!(concat!(env!("OUT_DIR"), "/versions.rs"));
+#[cfg(all(feature = "ffprobe", not(feature = "build")))]
+include!(concat!(env!("OUT_DIR"), "/ffprobe.rs"));
+#[cfg(not(feature = "tools-only"))]
+pub use bindings::*;
+#[cfg(not(feature = "tools-only"))]
+include!(concat!(env!("OUT_DIR"), "/inline_shims.rs"));
+// hwcontext_vulkan.h and the parts of the Vulkan API it uses, generated on their own
+#[cfg(all(feature = "hwcontext-vulkan", not(feature = "tools-only")))]
+include!(concat!(env!("OUT_DIR"), "/hwcontext_vulkan.rs"));
+// the sizes of a few structs as the C compiler sees them, checked against the bindings
+#[cfg(not(feature = "tools-only"))]
+include!(concat!(env!("OUT_DIR"), "/layout_checks.rs"));
 
+// the bindings of APIs in their FF_API_* deprecation window are #[deprecated] for downstream
+// crates, bindgen's layout tests still use them. A lint attribute on the include! itself would be
+// ignored, hence the module
+#[cfg(not(feature = "tools-only"))]
+mod bindings {
+    #![allow(deprecated)]
+
+    use super::*;
+
+    include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+}
+
+/// The `FF_API_*` deprecation macros of the headers the bindings were generated from, only
+/// those of the enabled libraries.
+///
+/// Each one is also available as cfgs named after the lowercased macro:
+/// `feature = "ff_api_init_packet"` when `FF_API_INIT_PACKET` is 1 and
+/// `feature = "ff_api_init_packet_is_defined"` when the headers define it at all.
+#[cfg(not(feature = "tools-only"))]
+pub mod ff_api {
+    include!(concat!(env!("OUT_DIR"), "/ff_api.rs"));
+}
+
+/// What the build script found, for the build scripts of downstream crates and packaging.
+#[cfg(not(feature = "tools-only"))]
+pub mod build_info {
+    include!(concat!(env!("OUT_DIR"), "/build_info.rs"));
+}
+
+#[cfg(not(feature = "tools-only"))]
 #[macro_use]
 mod avutil;
+#[cfg(not(feature = "tools-only"))]
 pub use avutil::*;
+
+#[cfg(all(feature = "avformat", not(feature = "tools-only")))]
+mod avformat;
+#[cfg(all(feature = "avformat", not(feature = "tools-only")))]
+pub use avformat::*;
+
+/// FFmpeg's documented thread-safety rules for the context types, as constants and marker traits
+/// for wrapper crates to build on.
+#[cfg(not(feature = "tools-only"))]
+pub mod thread_safety;
+
+/// Runtime loading of the FFmpeg libraries, with the `dlopen` feature.
+///
+/// `dlopen::Library` has a field for each enabled library, e.g. `library.avcodec`, with a method
+/// for each of its functions. The functions of the bindings are still declared at the crate root,
+/// calling those instead fails to link.
+#[cfg(feature = "dlopen")]
+pub mod dlopen;
@@ -0,0 +1,167 @@
+// FFmpeg's documented thread-safety rules, nothing here is checked or enforced by FFmpeg
+// itself. Every type gets a decision in the table below, tests/thread_safety.rs fails when a
+// `*Context` struct of the bindings has none. AVBitStreamFilterContext is deprecated but still
+// gets its decision while FF_API_OLD_BSF keeps it around.
+#![allow(deprecated)]
+
+#[cfg(feature = "avresample")]
+use AVAudioResampleContext;
+#[cfg(all(feature = "avcodec", feature = "ff_api_old_bsf"))]
+use AVBitStreamFilterContext;
+#[cfg(feature = "avcodec")]
+use {AVCodecContext, AVCodecParserContext, AVPacket};
+#[cfg(feature = "avcodec-avfft")]
+use {DCTContext, FFTContext, RDFTContext};
+#[cfg(all(feature = "avcodec", feature = "has_vorbis_parser_h"))]
+use AVVorbisParseContext;
+#[cfg(feature = "has_bsf")]
+use AVBSFContext;
+#[cfg(feature = "avfilter")]
+use AVFilterGraph;
+#[cfg(feature = "avformat")]
+use {AVFormatContext, AVIOContext};
+#[cfg(all(feature = "avformat", feature = "has_avio_dir"))]
+use AVIODirContext;
+#[cfg(feature = "has_tx")]
+use AVTXContext;
+#[cfg(feature = "swresample")]
+use SwrContext;
+#[cfg(feature = "swscale")]
+use SwsContext;
+use {AVAudioFifo, AVBufferRef, AVDictionary, AVFrame, AVHWDeviceContext, AVHWFramesContext};
+use AVHashContext;
+
+/// Implemented for the types FFmpeg allows to be used from another thread than the one that
+/// created them, as long as only one thread uses them at a time.
+pub unsafe trait ThreadSafeSend {}
+
+/// Implemented for the types FFmpeg allows to be used from several threads at once.
+pub unsafe trait ThreadSafeSync {}
+
+/// A pointer that can be moved to another thread, only for the types implementing
+/// `ThreadSafeSend`.
+pub struct SendWrapper<T: ThreadSafeSend>(pub *mut T);
+
+unsafe impl<T: ThreadSafeSend> Send for SendWrapper<T> {}
+
+/// A pointer that can be shared between threads, only for the types implementing
+/// `ThreadSafeSync`.
+pub struct SyncWrapper<T: ThreadSafeSync>(pub *const T);
+
+unsafe impl<T: ThreadSafeSync> Send for SyncWrapper<T> {}
+unsafe impl<T: ThreadSafeSync> Sync for SyncWrapper<T> {}
+
+macro_rules! thread_safety {
+    (@impl $marker:ident, $ty:ident, true) => {
+        unsafe impl $marker for $ty {}
+    };
+    (@impl $marker:ident, $ty:ident, false) => {};
+    ($($(#[$cfg:meta])* $ty:ident: $send_name:ident = $send:tt, $sync_name:ident = $sync:tt;)*) => {
+        $(
+            $(#[$cfg])*
+            #[doc = concat!("Whether `", stringify!($ty), "` may be used from another thread.")]
+            pub const $send_name: bool = $send;
+            $(#[$cfg])*
+            #[doc = concat!("Whether `", stringify!($ty), "` may be used from several threads at once.")]
+            pub const $sync_name: bool = $sync;
+
+            $(#[$cfg])*
+            thread_safety!(@impl ThreadSafeSend, $ty, $send);
+            $(#[$cfg])*
+            thread_safety!(@impl ThreadSafeSync, $ty, $sync);
+        )*
+
+        /// Every type with a decision, as `(name, send, sync)`, only those of the enabled
+        /// libraries.
+        #[cfg(feature = "std")]
+        pub fn thread_safety() -> Vec<(&'static str, bool, bool)> {
+            let mut table = Vec::new();
+            $(
+                $(#[$cfg])*
+                table.push((stringify!($ty), $send, $sync));
+            )*
+            table
+        }
+    };
+}
+
+thread_safety! {
+    AVFrame: AVFRAME_THREAD_SAFE_SEND = true, AVFRAME_THREAD_SAFE_SYNC = false;
+    // the reference counts are atomic, the reference itself is not
+    AVBufferRef: AVBUFFERREF_THREAD_SAFE_SEND = true, AVBUFFERREF_THREAD_SAFE_SYNC = false;
+    AVDictionary: AVDICTIONARY_THREAD_SAFE_SEND = true, AVDICTIONARY_THREAD_SAFE_SYNC = false;
+    AVAudioFifo: AVAUDIOFIFO_THREAD_SAFE_SEND = true, AVAUDIOFIFO_THREAD_SAFE_SYNC = false;
+    // shared through AVBufferRefs, the contexts themselves still need external locking
+    AVHWDeviceContext:
+        AVHWDEVICECONTEXT_THREAD_SAFE_SEND = true,
+        AVHWDEVICECONTEXT_THREAD_SAFE_SYNC = false;
+    AVHWFramesContext:
+        AVHWFRAMESCONTEXT_THREAD_SAFE_SEND = true,
+        AVHWFRAMESCONTEXT_THREAD_SAFE_SYNC = false;
+    AVHashContext: AVHASHCONTEXT_THREAD_SAFE_SEND = true, AVHASHCONTEXT_THREAD_SAFE_SYNC = false;
+    #[cfg(feature = "has_tx")]
+    AVTXContext: AVTXCONTEXT_THREAD_SAFE_SEND = true, AVTXCONTEXT_THREAD_SAFE_SYNC = false;
+
+    #[cfg(feature = "avcodec")]
+    AVCodecContext: AVCODECCONTEXT_THREAD_SAFE_SEND = true, AVCODECCONTEXT_THREAD_SAFE_SYNC = false;
+    #[cfg(feature = "avcodec")]
+    AVCodecParserContext:
+        AVCODECPARSERCONTEXT_THREAD_SAFE_SEND = true,
+        AVCODECPARSERCONTEXT_THREAD_SAFE_SYNC = false;
+    #[cfg(feature = "avcodec")]
+    AVPacket: AVPACKET_THREAD_SAFE_SEND = true, AVPACKET_THREAD_SAFE_SYNC = false;
+    #[cfg(feature = "has_bsf")]
+    AVBSFContext: AVBSFCONTEXT_THREAD_SAFE_SEND = true, AVBSFCONTEXT_THREAD_SAFE_SYNC = false;
+    #[cfg(all(feature = "avcodec", feature = "ff_api_old_bsf"))]
+    AVBitStreamFilterContext:
+        AVBITSTREAMFILTERCONTEXT_THREAD_SAFE_SEND = true,
+        AVBITSTREAMFILTERCONTEXT_THREAD_SAFE_SYNC = false;
+    #[cfg(all(feature = "avcodec", feature = "has_vorbis_parser_h"))]
+    AVVorbisParseContext:
+        AVVORBISPARSECONTEXT_THREAD_SAFE_SEND = true,
+        AVVORBISPARSECONTEXT_THREAD_SAFE_SYNC = false;
+    #[cfg(feature = "avcodec-avfft")]
+    FFTContext: FFTCONTEXT_THREAD_SAFE_SEND = true, FFTCONTEXT_THREAD_SAFE_SYNC = false;
+    #[cfg(feature = "avcodec-avfft")]
+    RDFTContext: RDFTCONTEXT_THREAD_SAFE_SEND = true, RDFTCONTEXT_THREAD_SAFE_SYNC = false;
+    #[cfg(feature = "avcodec-avfft")]
+    DCTContext: DCTCONTEXT_THREAD_SAFE_SEND = true, DCTCONTEXT_THREAD_SAFE_SYNC = false;
+
+    #[cfg(feature = "avformat")]
+    AVFormatContext:
+        AVFORMATCONTEXT_THREAD_SAFE_SEND = true,
+        AVFORMATCONTEXT_THREAD_SAFE_SYNC = false;
+    #[cfg(feature = "avformat")]
+    AVIOContext: AVIOCONTEXT_THREAD_SAFE_SEND = true, AVIOCONTEXT_THREAD_SAFE_SYNC = false;
+    #[cfg(all(feature = "avformat", feature = "has_avio_dir"))]
+    AVIODirContext:
+        AVIODIRCONTEXT_THREAD_SAFE_SEND = true,
+        AVIODIRCONTEXT_THREAD_SAFE_SYNC = false;
+
+    #[cfg(feature = "avfilter")]
+    AVFilterGraph: AVFILTERGRAPH_THREAD_SAFE_SEND = true, AVFILTERGRAPH_THREAD_SAFE_SYNC = false;
+    // owned by its graph, which may run it on the graph's worker threads
+    #[cfg(feature = "avfilter")]
+    AVFilterContext:
+        AVFILTERCONTEXT_THREAD_SAFE_SEND = false,
+        AVFILTERCONTEXT_THREAD_SAFE_SYNC = false;
+
+    #[cfg(feature = "swscale")]
+    SwsContext: SWSCONTEXT_THREAD_SAFE_SEND = true, SWSCONTEXT_THREAD_SAFE_SYNC = false;
+    #[cfg(feature = "swresample")]
+    SwrContext: SWRCONTEXT_THREAD_SAFE_SEND = true, SWRCONTEXT_THREAD_SAFE_SYNC = false;
+    #[cfg(feature = "avresample")]
+    AVAudioResampleContext:
+        AVAUDIORESAMPLECONTEXT_THREAD_SAFE_SEND = true,
+        AVAUDIORESAMPLECONTEXT_THREAD_SAFE_SYNC = false;
+}
+
+/// Whether `avcodec_open2` may be called from several threads at once without registering a
+/// lock manager, since libavcodec 58.9 (FFmpeg 4.0).
+#[cfg(feature = "avcodec")]
+pub const AVCODEC_OPEN2_THREAD_SAFE: bool = cfg!(feature = "avcodec_version_greater_than_58_8");
+
+/// Whether `avcodec_register_all` and `av_register_all` have to run before the codecs and
+/// formats are looked up, `av_codec_iterate` replaced the registration in libavcodec 58.10.
+#[cfg(feature = "avcodec")]
+pub const REGISTER_ALL_REQUIRED: bool = !cfg!(feature = "avcodec_version_greater_than_58_9");
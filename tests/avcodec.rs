@@ -0,0 +1,432 @@
+#![cfg(feature = "avcodec")]
+
+extern crate ffmpeg_sys;
+
+#[test]
+fn version() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        assert_eq!((avcodec_version() >> 16) as i32, LIBAVCODEC_VERSION_MAJOR);
+    }
+}
+
+#[test]
+fn version_constant() {
+    use ffmpeg_sys::*;
+
+    let (major, minor, micro) = FFMPEG_SYS_AVCODEC_VERSION;
+    unsafe {
+        assert_eq!(avcodec_version(), major << 16 | minor << 8 | micro);
+    }
+}
+
+#[test]
+fn packet_alloc() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let mut packet = av_packet_alloc();
+        assert!(!packet.is_null());
+        assert_eq!((*packet).size, 0);
+
+        av_packet_free(&mut packet);
+        assert!(packet.is_null());
+    }
+}
+
+#[test]
+fn packet_padding() {
+    use std::slice;
+
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let mut packet = av_packet_alloc();
+        assert_eq!(av_new_packet(packet, 64), 0);
+
+        // av_new_packet zeroes the padding after the data
+        let padding = slice::from_raw_parts((*packet).data.add(64), AV_INPUT_BUFFER_PADDING_SIZE);
+        assert!(padding.iter().all(|&byte| byte == 0));
+
+        av_packet_free(&mut packet);
+    }
+}
+
+#[test]
+fn hwaccel_flags() {
+    use std::ptr;
+
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let mut context = avcodec_alloc_context3(ptr::null());
+        assert!(!context.is_null());
+
+        (*context).hwaccel_flags |= AV_HWACCEL_FLAG_IGNORE_LEVEL
+            | AV_HWACCEL_FLAG_ALLOW_HIGH_DEPTH
+            | AV_HWACCEL_FLAG_ALLOW_PROFILE_MISMATCH;
+        #[cfg(feature = "has_hwaccel_flag_unsafe_output")]
+        {
+            (*context).hwaccel_flags |= AV_HWACCEL_FLAG_UNSAFE_OUTPUT;
+        }
+        assert_ne!((*context).hwaccel_flags & AV_HWACCEL_FLAG_IGNORE_LEVEL, 0);
+
+        avcodec_free_context(&mut context);
+    }
+}
+
+// av_bsf_iterate is 58.10, FFmpeg 4.0, the bsf API itself is older
+#[cfg(feature = "avcodec_version_greater_than_58_9")]
+#[test]
+fn bsf_iterate() {
+    use std::ffi::CStr;
+    use std::ptr;
+
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let mut opaque = ptr::null_mut();
+        let mut names = Vec::new();
+
+        loop {
+            let filter = av_bsf_iterate(&mut opaque);
+            if filter.is_null() {
+                break;
+            }
+
+            names.push(CStr::from_ptr((*filter).name).to_str().unwrap());
+        }
+
+        assert!(names.contains(&"null"));
+        assert!(!av_bsf_get_by_name(b"null\0".as_ptr() as *const _).is_null());
+    }
+}
+
+#[test]
+fn codec_descriptor() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let descriptor = avcodec_descriptor_get_by_name(b"h264\0".as_ptr() as *const _);
+        assert!(!descriptor.is_null());
+        assert_eq!((*descriptor).id, AVCodecID::AV_CODEC_ID_H264);
+        assert_eq!((*descriptor).type_, AVMediaType::AVMEDIA_TYPE_VIDEO);
+        assert_eq!(avcodec_descriptor_get(AVCodecID::AV_CODEC_ID_H264), descriptor);
+    }
+}
+
+#[test]
+fn codec_parameters() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let mut parameters: *mut AVCodecParameters = avcodec_parameters_alloc();
+        assert!(!parameters.is_null());
+        assert_eq!((*parameters).codec_id, AVCodecID::AV_CODEC_ID_NONE);
+
+        avcodec_parameters_free(&mut parameters);
+        assert!(parameters.is_null());
+    }
+}
+
+// the packet inspection case, which only needs the parsers of an FFmpeg built with
+// --disable-everything --enable-parser=h264
+#[test]
+fn h264_parser() {
+    use std::ptr;
+    use std::slice;
+
+    use ffmpeg_sys::*;
+
+    // three access units of an Annex B stream, each an access unit delimiter and a slice whose
+    // first_mb_in_slice is 0. the parser splits on those without needing an SPS
+    let access_units: [&[u8]; 3] = [
+        &[0, 0, 0, 1, 0x09, 0xf0, 0, 0, 0, 1, 0x65, 0x88, 0x84, 0x21, 0xa0],
+        &[0, 0, 0, 1, 0x09, 0xf0, 0, 0, 0, 1, 0x41, 0x9a, 0x02, 0x04],
+        &[0, 0, 0, 1, 0x09, 0xf0, 0, 0, 0, 1, 0x41, 0x9a, 0x04, 0x08],
+    ];
+    let stream = access_units.concat();
+    let mut input = stream.clone();
+    input.resize(stream.len() + AV_INPUT_BUFFER_PADDING_SIZE, 0);
+
+    unsafe {
+        let parser = av_parser_init(AVCodecID::AV_CODEC_ID_H264 as i32);
+        assert!(!parser.is_null());
+
+        // there's no decoder to allocate it for in such a build
+        let mut context = avcodec_alloc_context3(ptr::null());
+        (*context).codec_type = AVMediaType::AVMEDIA_TYPE_VIDEO;
+        (*context).codec_id = AVCodecID::AV_CODEC_ID_H264;
+
+        let mut packets = Vec::new();
+        let mut offset = 0;
+        loop {
+            // an empty buffer flushes the last packet
+            let remaining = stream.len() - offset;
+            let mut data = ptr::null_mut();
+            let mut size = 0;
+            let used = av_parser_parse2(
+                parser,
+                context,
+                &mut data,
+                &mut size,
+                input[offset..].as_ptr(),
+                remaining as i32,
+                AV_NOPTS_VALUE,
+                AV_NOPTS_VALUE,
+                0,
+            );
+            assert!(used >= 0);
+            offset += used as usize;
+
+            if size > 0 {
+                packets.push(slice::from_raw_parts(data, size as usize).to_vec());
+            }
+            if remaining == 0 {
+                break;
+            }
+        }
+
+        assert_eq!(packets, access_units.iter().map(|unit| unit.to_vec()).collect::<Vec<_>>());
+
+        let descriptor = avcodec_descriptor_get(AVCodecID::AV_CODEC_ID_H264);
+        assert_eq!((*descriptor).type_, AVMediaType::AVMEDIA_TYPE_VIDEO);
+
+        av_parser_close(parser);
+        avcodec_free_context(&mut context);
+    }
+}
+
+#[test]
+fn subrip_subtitle() {
+    use std::ffi::CStr;
+    use std::mem;
+    use std::ptr;
+
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let codec = avcodec_find_decoder(AVCodecID::AV_CODEC_ID_SUBRIP);
+        assert!(!codec.is_null());
+
+        let mut context = avcodec_alloc_context3(codec);
+        (*context).pkt_timebase = AVRational { num: 1, den: 1000 };
+        assert_eq!(avcodec_open2(context, codec, ptr::null_mut()), 0);
+
+        let text = b"Hello\n";
+        let mut packet = av_packet_alloc();
+        assert_eq!(av_new_packet(packet, text.len() as i32), 0);
+        ptr::copy_nonoverlapping(text.as_ptr(), (*packet).data, text.len());
+        (*packet).pts = 0;
+        (*packet).duration = 1000;
+
+        let mut subtitle: AVSubtitle = mem::zeroed();
+        let mut got_subtitle = 0;
+        assert!(avcodec_decode_subtitle2(context, &mut subtitle, &mut got_subtitle, packet) >= 0);
+        assert_eq!(got_subtitle, 1);
+        assert_eq!(subtitle.num_rects, 1);
+
+        let rect = *subtitle.rects;
+        assert_eq!((*rect).type_, AVSubtitleType::SUBTITLE_ASS);
+        assert!(CStr::from_ptr((*rect).ass).to_string_lossy().contains("Hello"));
+
+        avsubtitle_free(&mut subtitle);
+        assert!(subtitle.rects.is_null());
+        assert_eq!(subtitle.num_rects, 0);
+
+        av_packet_free(&mut packet);
+        avcodec_free_context(&mut context);
+    }
+}
+
+#[test]
+fn option_defaults() {
+    use std::ffi::CStr;
+    use std::os::raw::c_void;
+    use std::ptr;
+
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let codec = avcodec_find_encoder_by_name(b"aac\0".as_ptr() as *const _);
+        assert!(!codec.is_null());
+        assert!(!(*codec).priv_class.is_null());
+
+        // av_opt_next takes an object that starts with its AVClass
+        let obj = &(*codec).priv_class as *const _ as *const c_void;
+        let mut option = ptr::null();
+        let mut count = 0;
+        loop {
+            option = av_opt_next(obj, option);
+            if option.is_null() {
+                break;
+            }
+
+            count += 1;
+            if let AVOptionDefault::Str(value) = (*option).default_value() {
+                if !value.is_null() {
+                    CStr::from_ptr(value).to_str().unwrap();
+                }
+            }
+        }
+        assert!(count > 0);
+
+        // every codec context defaults to 200 kb/s
+        let class = avcodec_get_class();
+        let bit_rate = av_opt_find(
+            &class as *const _ as *mut c_void,
+            b"b\0".as_ptr() as *const _,
+            ptr::null(),
+            0,
+            AV_OPT_SEARCH_FAKE_OBJ as _,
+        );
+        assert!(!bit_rate.is_null());
+        assert_eq!((*bit_rate).default_value(), AVOptionDefault::Int(200_000));
+    }
+}
+
+#[test]
+fn option_ranges() {
+    use std::os::raw::c_void;
+    use std::ptr;
+
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let mut context = avcodec_alloc_context3(ptr::null());
+        let mut ranges: *mut AVOptionRanges = ptr::null_mut();
+        assert!(
+            av_opt_query_ranges(
+                &mut ranges,
+                context as *mut c_void,
+                b"b\0".as_ptr() as *const _,
+                0
+            ) >= 0
+        );
+        assert_eq!((*ranges).nb_ranges, 1);
+        assert_eq!((*ranges).nb_components, 1);
+        assert_eq!((*av_opt_ranges_get(ranges, 0, 0)).value_min, 0.0);
+
+        av_opt_freep_ranges(&mut ranges);
+        assert!(ranges.is_null());
+        avcodec_free_context(&mut context);
+    }
+}
+
+#[cfg(feature = "build-lib-dav1d")]
+#[test]
+fn dav1d_decoder() {
+    use std::ptr;
+
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let codec = avcodec_find_decoder_by_name(b"libdav1d\0".as_ptr() as *const _);
+        assert!(!codec.is_null());
+        assert_eq!((*codec).id, AVCodecID::AV_CODEC_ID_AV1);
+
+        let mut context = avcodec_alloc_context3(codec);
+        assert!(!context.is_null());
+        assert_eq!(avcodec_open2(context, codec, ptr::null_mut()), 0);
+
+        avcodec_free_context(&mut context);
+    }
+}
+
+#[cfg(feature = "build-lib-aom")]
+#[test]
+fn aom_encode_black_frame() {
+    use std::ptr;
+
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let codec = avcodec_find_encoder_by_name(b"libaom-av1\0".as_ptr() as *const _);
+        assert!(!codec.is_null());
+        assert_eq!((*codec).id, AVCodecID::AV_CODEC_ID_AV1);
+
+        let mut context = avcodec_alloc_context3(codec);
+        assert!(!context.is_null());
+        (*context).width = 64;
+        (*context).height = 64;
+        (*context).pix_fmt = AVPixelFormat::AV_PIX_FMT_YUV420P;
+        (*context).time_base = AVRational { num: 1, den: 25 };
+        assert_eq!(avcodec_open2(context, codec, ptr::null_mut()), 0);
+
+        let mut frame = av_frame_alloc();
+        (*frame).width = 64;
+        (*frame).height = 64;
+        (*frame).format = AVPixelFormat::AV_PIX_FMT_YUV420P as i32;
+        (*frame).pts = 0;
+        assert_eq!(av_frame_get_buffer(frame, 0), 0);
+
+        for (plane, &value) in [16u8, 128, 128].iter().enumerate() {
+            let height = if plane == 0 { 64 } else { 32 };
+            ptr::write_bytes(
+                (*frame).data[plane],
+                value,
+                ((*frame).linesize[plane] * height) as usize,
+            );
+        }
+
+        assert_eq!(avcodec_send_frame(context, frame), 0);
+        assert_eq!(avcodec_send_frame(context, ptr::null()), 0);
+
+        let mut packet = av_packet_alloc();
+        assert_eq!(avcodec_receive_packet(context, packet), 0);
+        assert!((*packet).size > 0);
+
+        av_packet_free(&mut packet);
+        av_frame_free(&mut frame);
+        avcodec_free_context(&mut context);
+    }
+}
+
+#[cfg(feature = "build-lib-codec2")]
+#[test]
+fn codec2_codecs() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let decoder = avcodec_find_decoder_by_name(b"libcodec2\0".as_ptr() as *const _);
+        assert!(!decoder.is_null());
+        assert_eq!((*decoder).id, AVCodecID::AV_CODEC_ID_CODEC2);
+
+        let encoder = avcodec_find_encoder_by_name(b"libcodec2\0".as_ptr() as *const _);
+        assert!(!encoder.is_null());
+    }
+}
+
+#[cfg(feature = "build-lib-jxl")]
+#[test]
+fn jxl_codecs() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let decoder = avcodec_find_decoder_by_name(b"libjxl\0".as_ptr() as *const _);
+        assert!(!decoder.is_null());
+        assert_eq!((*decoder).id, AVCodecID::AV_CODEC_ID_JPEGXL);
+
+        let encoder = avcodec_find_encoder_by_name(b"libjxl\0".as_ptr() as *const _);
+        assert!(!encoder.is_null());
+    }
+}
+
+#[test]
+fn macro_types() {
+    use ffmpeg_sys::*;
+
+    // the types IntCallbacks in build.rs gives the macros, a change fails to compile
+    let codec_flags: u32 = AV_CODEC_FLAG_GLOBAL_HEADER | AV_CODEC_CAP_DELAY;
+    let hwaccel_flags: i32 = AV_HWACCEL_FLAG_IGNORE_LEVEL;
+    let padding: usize = AV_INPUT_BUFFER_PADDING_SIZE;
+    let pix_fmt_flags: u64 = AV_PIX_FMT_FLAG_RGB;
+    let packet_flags: i32 = AV_PKT_FLAG_KEY;
+
+    assert_ne!(codec_flags, 0);
+    assert_ne!(hwaccel_flags, 0);
+    assert!(padding >= 32);
+    assert_ne!(pix_fmt_flags, 0);
+    assert_eq!(packet_flags, 1);
+}
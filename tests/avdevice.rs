@@ -0,0 +1,64 @@
+#![cfg(feature = "avdevice")]
+
+extern crate ffmpeg_sys;
+
+#[test]
+fn input_audio_devices() {
+    use std::ptr;
+
+    use ffmpeg_sys::*;
+
+    unsafe {
+        avdevice_register_all();
+
+        // the iterator takes and returns const pointers since FFmpeg 5.0
+        let mut device = av_input_audio_device_next(ptr::null_mut::<AVInputFormat>() as _);
+        while !device.is_null() {
+            assert!(!(*device).name.is_null());
+            device = av_input_audio_device_next(device as _);
+        }
+    }
+}
+
+#[cfg(any(
+    feature = "build-indev-alsa",
+    feature = "build-indev-pulse",
+    feature = "build-libv4l2",
+    feature = "build-xcb"
+))]
+fn has_input_device(name: &str) -> bool {
+    use std::ffi::CString;
+
+    use ffmpeg_sys::*;
+
+    unsafe {
+        avdevice_register_all();
+
+        let name = CString::new(name).unwrap();
+        !av_find_input_format(name.as_ptr()).is_null()
+    }
+}
+
+#[cfg(feature = "build-indev-alsa")]
+#[test]
+fn alsa_indev() {
+    assert!(has_input_device("alsa"));
+}
+
+#[cfg(feature = "build-indev-pulse")]
+#[test]
+fn pulse_indev() {
+    assert!(has_input_device("pulse"));
+}
+
+#[cfg(feature = "build-libv4l2")]
+#[test]
+fn v4l2_indev() {
+    assert!(has_input_device("v4l2"));
+}
+
+#[cfg(feature = "build-xcb")]
+#[test]
+fn x11grab_indev() {
+    assert!(has_input_device("x11grab"));
+}
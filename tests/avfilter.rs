@@ -0,0 +1,160 @@
+#![cfg(feature = "avfilter")]
+
+extern crate ffmpeg_sys;
+
+#[cfg(feature = "build-lib-zimg")]
+#[test]
+fn zscale_filter() {
+    use std::ffi::CStr;
+
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let filter = avfilter_get_by_name(b"zscale\0".as_ptr() as *const _);
+        assert!(!filter.is_null());
+        assert_eq!(CStr::from_ptr((*filter).name).to_str().unwrap(), "zscale");
+    }
+}
+
+#[cfg(feature = "build-lib-zmq")]
+#[test]
+fn zmq_filters() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        assert!(!avfilter_get_by_name(b"zmq\0".as_ptr() as *const _).is_null());
+        assert!(!avfilter_get_by_name(b"azmq\0".as_ptr() as *const _).is_null());
+    }
+}
+
+#[cfg(feature = "build-lib-vidstab")]
+#[test]
+fn vidstab_filters() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        assert!(!avfilter_get_by_name(b"vidstabdetect\0".as_ptr() as *const _).is_null());
+        assert!(!avfilter_get_by_name(b"vidstabtransform\0".as_ptr() as *const _).is_null());
+    }
+}
+
+#[cfg(feature = "build-lib-rubberband")]
+#[test]
+fn rubberband_filter() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        assert!(!avfilter_get_by_name(b"rubberband\0".as_ptr() as *const _).is_null());
+    }
+}
+
+#[cfg(feature = "build-lib-placebo")]
+#[test]
+fn placebo_filter() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        assert!(!avfilter_get_by_name(b"libplacebo\0".as_ptr() as *const _).is_null());
+    }
+}
+
+#[test]
+fn buffer_format_buffersink() {
+    use std::ffi::CString;
+    use std::ptr;
+
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let mut graph = avfilter_graph_alloc();
+        assert!(!graph.is_null());
+
+        let create = |name: &str, args: Option<&str>| {
+            let filter = avfilter_get_by_name(CString::new(name).unwrap().as_ptr());
+            assert!(!filter.is_null());
+
+            let args = args.map(|args| CString::new(args).unwrap());
+            let mut context = ptr::null_mut();
+            assert_eq!(
+                avfilter_graph_create_filter(
+                    &mut context,
+                    filter,
+                    CString::new(name).unwrap().as_ptr(),
+                    args.as_ref().map_or(ptr::null(), |args| args.as_ptr()),
+                    ptr::null_mut(),
+                    graph,
+                ),
+                0
+            );
+            context
+        };
+        let source = create(
+            "buffer",
+            Some("video_size=4x4:pix_fmt=yuv420p:time_base=1/25:pixel_aspect=1/1"),
+        );
+        let format = create("format", Some("pix_fmts=yuv420p"));
+        let sink = create("buffersink", None);
+
+        assert_eq!(avfilter_link(source, 0, format, 0), 0);
+        assert_eq!(avfilter_link(format, 0, sink, 0), 0);
+        assert!(avfilter_graph_config(graph, ptr::null_mut()) >= 0);
+
+        let mut frame = av_frame_alloc();
+        (*frame).width = 4;
+        (*frame).height = 4;
+        (*frame).format = AVPixelFormat::AV_PIX_FMT_YUV420P as i32;
+        (*frame).pts = 7;
+        assert_eq!(av_frame_get_buffer(frame, 0), 0);
+
+        assert_eq!(
+            av_buffersrc_add_frame_flags(source, frame, AV_BUFFERSRC_FLAG_KEEP_REF),
+            0
+        );
+        assert_eq!(av_buffersrc_add_frame(source, ptr::null_mut()), 0);
+
+        let mut filtered = av_frame_alloc();
+        assert_eq!(
+            av_buffersink_get_frame_flags(sink, filtered, AV_BUFFERSINK_FLAG_PEEK),
+            0
+        );
+        av_frame_unref(filtered);
+        assert_eq!(av_buffersink_get_frame(sink, filtered), 0);
+        assert_eq!((*filtered).width, 4);
+        assert_eq!((*filtered).height, 4);
+        assert_eq!((*filtered).format, AVPixelFormat::AV_PIX_FMT_YUV420P as i32);
+        assert_eq!((*filtered).pts, 7);
+
+        av_frame_free(&mut filtered);
+        av_frame_free(&mut frame);
+        avfilter_graph_free(&mut graph);
+        assert!(graph.is_null());
+    }
+}
+
+#[cfg(feature = "has_graph_segment")]
+#[test]
+fn graph_segment() {
+    use std::ptr;
+
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let mut graph = avfilter_graph_alloc();
+        let mut segment = ptr::null_mut();
+        assert_eq!(
+            avfilter_graph_segment_parse(
+                graph,
+                b"format=yuv420p,null\0".as_ptr() as *const _,
+                0,
+                &mut segment,
+            ),
+            0
+        );
+        assert_eq!((*segment).nb_chains, 1);
+        assert_eq!((**(*segment).chains).nb_filters, 2);
+
+        avfilter_graph_segment_free(&mut segment);
+        assert!(segment.is_null());
+        avfilter_graph_free(&mut graph);
+    }
+}
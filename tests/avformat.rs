@@ -0,0 +1,320 @@
+#![cfg(feature = "avformat")]
+
+extern crate ffmpeg_sys;
+
+#[test]
+fn version() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        assert_eq!((avformat_version() >> 16) as i32, LIBAVFORMAT_VERSION_MAJOR);
+    }
+}
+
+#[test]
+fn network_init() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        assert_eq!(avformat_network_init(), 0);
+        assert_eq!(avformat_network_deinit(), 0);
+    }
+}
+
+#[test]
+fn null_muxer() {
+    use std::ptr;
+
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let mut context = ptr::null_mut();
+        let ret = avformat_alloc_output_context2(
+            &mut context,
+            ptr::null_mut(),
+            b"null\0".as_ptr() as *const _,
+            ptr::null(),
+        );
+        assert!(ret >= 0);
+        assert!(!context.is_null());
+        assert!(!(*context).oformat.is_null());
+
+        avformat_free_context(context);
+    }
+}
+
+struct Reader {
+    data: Vec<u8>,
+    position: usize,
+}
+
+unsafe extern "C" fn read_packet(
+    opaque: *mut std::os::raw::c_void,
+    buf: *mut u8,
+    buf_size: i32,
+) -> i32 {
+    use std::cmp;
+    use std::ptr;
+
+    let reader = &mut *(opaque as *mut Reader);
+    let len = cmp::min(buf_size as usize, reader.data.len() - reader.position);
+    if len == 0 {
+        return ffmpeg_sys::AVERROR_EOF;
+    }
+
+    ptr::copy_nonoverlapping(reader.data[reader.position..].as_ptr(), buf, len);
+    reader.position += len;
+    len as i32
+}
+
+unsafe extern "C" fn seek(opaque: *mut std::os::raw::c_void, offset: i64, whence: i32) -> i64 {
+    use ffmpeg_sys::*;
+
+    let reader = &mut *(opaque as *mut Reader);
+    let position = match whence & !AVSEEK_FORCE {
+        AVSEEK_SIZE => return reader.data.len() as i64,
+        0 => offset,
+        1 => reader.position as i64 + offset,
+        2 => reader.data.len() as i64 + offset,
+        _ => return -1,
+    };
+    if position < 0 || position > reader.data.len() as i64 {
+        return -1;
+    }
+
+    reader.position = position as usize;
+    position
+}
+
+#[test]
+fn custom_io() {
+    use std::ffi::CStr;
+    use std::ptr;
+
+    use ffmpeg_sys::*;
+
+    // a WAV file with a second of 16-bit mono silence at 8 kHz
+    let samples = 8000 * 2;
+    let mut data = Vec::new();
+    data.extend_from_slice(b"RIFF");
+    data.extend_from_slice(&(36 + samples as u32).to_le_bytes());
+    data.extend_from_slice(b"WAVEfmt ");
+    data.extend_from_slice(&16u32.to_le_bytes());
+    data.extend_from_slice(&1u16.to_le_bytes());
+    data.extend_from_slice(&1u16.to_le_bytes());
+    data.extend_from_slice(&8000u32.to_le_bytes());
+    data.extend_from_slice(&16000u32.to_le_bytes());
+    data.extend_from_slice(&2u16.to_le_bytes());
+    data.extend_from_slice(&16u16.to_le_bytes());
+    data.extend_from_slice(b"data");
+    data.extend_from_slice(&(samples as u32).to_le_bytes());
+    data.resize(data.len() + samples, 0);
+    let mut reader = Reader { data, position: 0 };
+
+    unsafe {
+        let buffer_size = 4096;
+        let buffer = av_malloc(buffer_size) as *mut u8;
+        assert!(!buffer.is_null());
+
+        let read: AvioReadFn = read_packet;
+        let seek: AvioSeekFn = seek;
+        let mut pb = avio_alloc_context_rust(
+            buffer,
+            buffer_size as i32,
+            0,
+            &mut reader as *mut Reader as *mut _,
+            Some(read),
+            None,
+            Some(seek),
+        );
+        assert!(!pb.is_null());
+
+        let mut context = avformat_alloc_context();
+        (*context).pb = pb;
+        let ret = avformat_open_input(&mut context, ptr::null(), ptr::null_mut(), ptr::null_mut());
+        assert_eq!(ret, 0);
+        assert_eq!(CStr::from_ptr((*(*context).iformat).name).to_str(), Ok("wav"));
+
+        assert!(avformat_find_stream_info(context, ptr::null_mut()) >= 0);
+        assert_eq!((*context).nb_streams, 1);
+        let parameters = (**(*context).streams).codecpar;
+        assert_eq!((*parameters).sample_rate, 8000);
+
+        avformat_close_input(&mut context);
+        av_freep(&mut (*pb).buffer as *mut *mut u8 as *mut _);
+        // avio_context_free is from libavformat 57.80 (FFmpeg 3.4), before it the context was
+        // freed like any other allocation
+        #[cfg(feature = "avformat_version_greater_than_57_79")]
+        avio_context_free(&mut pb);
+        #[cfg(not(feature = "avformat_version_greater_than_57_79"))]
+        av_freep(&mut pb as *mut *mut AVIOContext as *mut _);
+    }
+}
+
+#[cfg(any(
+    feature = "build-lib-srt",
+    feature = "build-lib-rist",
+    feature = "build-lib-rtmp",
+    feature = "build-lib-bluray"
+))]
+fn protocols(output: bool) -> Vec<String> {
+    use std::ffi::CStr;
+    use std::ptr;
+
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let mut opaque = ptr::null_mut();
+        let mut names = Vec::new();
+
+        loop {
+            let name = avio_enum_protocols(&mut opaque, output as i32);
+            if name.is_null() {
+                break;
+            }
+
+            names.push(CStr::from_ptr(name).to_string_lossy().into_owned());
+        }
+
+        names
+    }
+}
+
+#[cfg(feature = "build-lib-srt")]
+#[test]
+fn srt_protocol() {
+    assert!(protocols(false).contains(&"srt".to_owned()));
+    assert!(protocols(true).contains(&"srt".to_owned()));
+}
+
+#[cfg(feature = "build-lib-rist")]
+#[test]
+fn rist_protocol() {
+    assert!(protocols(false).contains(&"rist".to_owned()));
+    assert!(protocols(true).contains(&"rist".to_owned()));
+}
+
+#[cfg(feature = "build-lib-rtmp")]
+#[test]
+fn librtmp_protocols() {
+    // librtmp replaces the native rtmp protocols and adds rtmpe/rtmpte
+    assert!(protocols(false).contains(&"rtmp".to_owned()));
+    assert!(protocols(false).contains(&"rtmpe".to_owned()));
+}
+
+#[cfg(feature = "build-lib-xml2")]
+#[test]
+fn dash_demuxer() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        assert!(!av_find_input_format(b"dash\0".as_ptr() as *const _).is_null());
+    }
+}
+
+#[cfg(feature = "build-lib-bluray")]
+#[test]
+fn bluray_protocol() {
+    assert!(protocols(false).contains(&"bluray".to_owned()));
+}
+
+#[cfg(feature = "build-lib-gme")]
+#[test]
+fn gme_demuxer() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        assert!(!av_find_input_format(b"libgme\0".as_ptr() as *const _).is_null());
+    }
+}
+
+#[cfg(feature = "build-lib-openmpt")]
+#[test]
+fn openmpt_demuxer() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        assert!(!av_find_input_format(b"libopenmpt\0".as_ptr() as *const _).is_null());
+    }
+}
+
+#[cfg(feature = "has_avio_dir")]
+#[test]
+fn list_directory() {
+    use std::ffi::{CStr, CString};
+    use std::{env, fs, process, ptr};
+
+    use ffmpeg_sys::*;
+
+    let dir = env::temp_dir().join(format!("ffmpeg-sys-avio-dir-{}", process::id()));
+    fs::create_dir_all(dir.join("directory")).unwrap();
+    fs::write(dir.join("file"), b"file").unwrap();
+
+    let url = CString::new(format!("file:{}", dir.to_str().unwrap())).unwrap();
+    let mut entries = Vec::new();
+    unsafe {
+        let mut context = ptr::null_mut();
+        assert_eq!(avio_open_dir(&mut context, url.as_ptr(), ptr::null_mut()), 0);
+
+        loop {
+            let mut entry = ptr::null_mut();
+            assert_eq!(avio_read_dir(context, &mut entry), 0);
+            if entry.is_null() {
+                break;
+            }
+
+            let name = CStr::from_ptr((*entry).name).to_str().unwrap().to_owned();
+            entries.push((name, AVIODirEntryType::from_raw((*entry).type_)));
+            avio_free_directory_entry(&mut entry);
+        }
+
+        assert_eq!(avio_close_dir(&mut context), 0);
+    }
+    fs::remove_dir_all(&dir).unwrap();
+
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        entries,
+        vec![
+            ("directory".to_owned(), Some(AVIODirEntryType::AVIO_ENTRY_DIRECTORY)),
+            ("file".to_owned(), Some(AVIODirEntryType::AVIO_ENTRY_FILE)),
+        ]
+    );
+}
+
+#[test]
+fn interrupt_callback() {
+    use std::net::TcpListener;
+    use std::ptr;
+    use std::sync::atomic::AtomicBool;
+    use std::time::{Duration, Instant};
+
+    use ffmpeg_sys::*;
+
+    static INTERRUPT: AtomicBool = AtomicBool::new(true);
+
+    // FFmpeg can't listen on port 0, borrow a free port from the system instead
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let url = format!("tcp://127.0.0.1:{}?listen=1\0", port);
+
+    unsafe {
+        let mut context = avformat_alloc_context();
+        (*context).interrupt_callback = interrupt_callback_from_flag(&INTERRUPT);
+
+        // waits for a connection that never comes without the callback
+        let start = Instant::now();
+        let ret = avformat_open_input(
+            &mut context,
+            url.as_ptr() as *const _,
+            ptr::null_mut(),
+            ptr::null_mut(),
+        );
+        assert_eq!(ret, AVERROR_EXIT);
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(context.is_null());
+    }
+}
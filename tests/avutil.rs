@@ -0,0 +1,682 @@
+#[macro_use]
+extern crate ffmpeg_sys;
+
+#[test]
+fn version() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        assert_eq!((avutil_version() >> 16) as i32, LIBAVUTIL_VERSION_MAJOR);
+    }
+}
+
+#[test]
+fn frame_alloc() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let mut frame = av_frame_alloc();
+        assert!(!frame.is_null());
+
+        av_frame_free(&mut frame);
+        assert!(frame.is_null());
+    }
+}
+
+#[test]
+fn malloc() {
+    use std::slice;
+
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let buf = av_malloc(64) as *mut u8;
+        assert!(!buf.is_null());
+
+        let data = slice::from_raw_parts_mut(buf, 64);
+        data.copy_from_slice(&[0x5a; 64]);
+        assert!(data.iter().all(|&b| b == 0x5a));
+
+        av_free(buf as *mut _);
+    }
+}
+
+#[test]
+fn rescale_q() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let ms = AVRational { num: 1, den: 1000 };
+        let mpeg = AVRational { num: 1, den: 90000 };
+        assert_eq!(av_rescale_q(1500, ms, mpeg), 135000);
+        assert_eq!(av_rescale_q(135000, mpeg, ms), 1500);
+    }
+}
+
+#[test]
+fn pix_fmt_desc_flags() {
+    use std::ffi::CStr;
+
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let desc = av_pix_fmt_desc_get(AVPixelFormat::AV_PIX_FMT_YUV420P);
+        assert!(!desc.is_null());
+        assert_eq!(CStr::from_ptr((*desc).name).to_bytes(), b"yuv420p");
+        assert_eq!((*desc).nb_components, 3);
+        assert_eq!((*desc).log2_chroma_w, 1);
+
+        assert!((*desc).flags & AV_PIX_FMT_FLAG_PLANAR != 0);
+        assert_eq!(
+            (*desc).flags & (AV_PIX_FMT_FLAG_RGB | AV_PIX_FMT_FLAG_PAL | AV_PIX_FMT_FLAG_ALPHA),
+            0
+        );
+    }
+}
+
+#[test]
+fn cpu_flags() {
+    use ffmpeg_sys::*;
+
+    assert_eq!(AV_CPU_FLAG_FORCE, 0x8000_0000u32);
+
+    unsafe {
+        // the flags are unsigned, the API takes and returns int
+        let flags = av_get_cpu_flags() as u32;
+        assert_eq!(flags & AV_CPU_FLAG_FORCE, 0);
+
+        av_force_cpu_flags(flags as i32);
+        assert_eq!(av_get_cpu_flags() as u32, flags);
+        av_force_cpu_flags(-1);
+    }
+}
+
+#[test]
+fn hash_max_size() {
+    use std::ptr;
+
+    use ffmpeg_sys::*;
+
+    let mut digest = [0u8; AV_HASH_MAX_SIZE];
+
+    unsafe {
+        let mut context = ptr::null_mut();
+        assert_eq!(av_hash_alloc(&mut context, b"MD5\0".as_ptr() as *const _), 0);
+        assert_eq!(av_hash_get_size(context), 16);
+
+        av_hash_init(context);
+        av_hash_final(context, digest.as_mut_ptr());
+        av_hash_freep(&mut context);
+    }
+
+    // the MD5 of nothing
+    assert_eq!(digest[..4], [0xd4, 0x1d, 0x8c, 0xd9]);
+}
+
+#[test]
+fn image_fill_arrays() {
+    use std::ptr;
+
+    use ffmpeg_sys::*;
+
+    let mut data = [ptr::null_mut::<u8>(); AV_NUM_DATA_POINTERS];
+    let mut linesize = [0; AV_NUM_DATA_POINTERS];
+    let buf = [0u8; 4 * 4 + 2 * 2 * 2];
+
+    unsafe {
+        let format = AVPixelFormat::AV_PIX_FMT_YUV420P;
+        assert_eq!(av_image_get_buffer_size(format, 4, 4, 1), buf.len() as i32);
+        assert_eq!(
+            av_image_fill_arrays(
+                data.as_mut_ptr(),
+                linesize.as_mut_ptr(),
+                buf.as_ptr(),
+                format,
+                4,
+                4,
+                1,
+            ),
+            buf.len() as i32
+        );
+
+        let mut frame = av_frame_alloc();
+        let frame_data: [*mut u8; AV_NUM_DATA_POINTERS] = (*frame).data;
+        assert!(frame_data.iter().all(|plane| plane.is_null()));
+        av_frame_free(&mut frame);
+    }
+
+    assert_eq!(linesize[..4], [4, 2, 2, 0]);
+    assert_eq!(data[1] as usize - data[0] as usize, 16);
+    assert_eq!(data[2] as usize - data[1] as usize, 4);
+    assert!(data[3].is_null());
+}
+
+#[test]
+fn mastering_display_metadata() {
+    use std::mem;
+
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let mut frame = av_frame_alloc();
+
+        let metadata = av_mastering_display_metadata_create_side_data(frame);
+        assert!(!metadata.is_null());
+        (*metadata).max_luminance = av_make_q(1000, 1);
+        (*metadata).has_luminance = 1;
+
+        let side_data = av_frame_get_side_data(
+            frame,
+            AVFrameSideDataType::AV_FRAME_DATA_MASTERING_DISPLAY_METADATA,
+        );
+        assert!(!side_data.is_null());
+        assert_eq!(
+            (*side_data).size as usize,
+            mem::size_of::<AVMasteringDisplayMetadata>()
+        );
+
+        let metadata = (*side_data).data as *const AVMasteringDisplayMetadata;
+        assert_eq!((*metadata).has_luminance, 1);
+        assert_eq!(av_cmp_q((*metadata).max_luminance, av_make_q(1000, 1)), 0);
+
+        av_frame_free(&mut frame);
+    }
+}
+
+#[cfg(feature = "has_hdr_dynamic_metadata")]
+#[test]
+fn dynamic_hdr_plus() {
+    use std::mem;
+
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let mut frame = av_frame_alloc();
+
+        let hdr_plus = av_dynamic_hdr_plus_create_side_data(frame);
+        assert!(!hdr_plus.is_null());
+        (*hdr_plus).num_windows = 1;
+        (*hdr_plus).params[0].num_distribution_maxrgb_percentiles = 2;
+        (*hdr_plus).params[0].distribution_maxrgb[1].percentage = 99;
+
+        let side_data =
+            av_frame_get_side_data(frame, AVFrameSideDataType::AV_FRAME_DATA_DYNAMIC_HDR_PLUS);
+        assert!(!side_data.is_null());
+        assert_eq!((*side_data).size as usize, mem::size_of::<AVDynamicHDRPlus>());
+
+        let hdr_plus = (*side_data).data as *const AVDynamicHDRPlus;
+        assert_eq!((*hdr_plus).num_windows, 1);
+        assert_eq!((*hdr_plus).params[0].distribution_maxrgb[1].percentage, 99);
+
+        av_frame_free(&mut frame);
+    }
+}
+
+#[cfg(feature = "has_spherical")]
+#[test]
+fn spherical_mapping() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let mut size = 0;
+        let mapping = av_spherical_alloc(&mut size);
+        assert!(!mapping.is_null());
+
+        (*mapping).projection = AVSphericalProjection::AV_SPHERICAL_EQUIRECTANGULAR_TILE;
+        (*mapping).bound_left = u32::max_value() / 4;
+        (*mapping).bound_right = u32::max_value() / 4;
+
+        let (mut left, mut top, mut right, mut bottom) = (0, 0, 0, 0);
+        av_spherical_tile_bounds(
+            mapping, 100, 100, &mut left, &mut top, &mut right, &mut bottom,
+        );
+        assert!(left > 0 && right > 0);
+        assert!(left + right >= 98 && left + right <= 100);
+        assert_eq!((top, bottom), (0, 0));
+
+        let name = av_spherical_projection_name((*mapping).projection);
+        assert_eq!(
+            av_spherical_from_name(name),
+            AVSphericalProjection::AV_SPHERICAL_EQUIRECTANGULAR_TILE as i32
+        );
+
+        av_free(mapping as *mut _);
+    }
+}
+
+#[test]
+fn encryption_info_side_data() {
+    use std::slice;
+
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let info = av_encryption_info_alloc(1, 16, 8);
+        assert!(!info.is_null());
+        (*info).scheme = MKBETAG!(b'c', b'e', b'n', b'c') as u32;
+        slice::from_raw_parts_mut((*info).key_id, 16).copy_from_slice(&[0xab; 16]);
+        slice::from_raw_parts_mut((*info).iv, 8).copy_from_slice(&[0xcd; 8]);
+        (*(*info).subsamples).bytes_of_clear_data = 5;
+        (*(*info).subsamples).bytes_of_protected_data = 100;
+
+        let mut size = 0;
+        let side_data = av_encryption_info_add_side_data(info, &mut size);
+        assert!(!side_data.is_null());
+
+        let parsed = av_encryption_info_get_side_data(side_data, size);
+        assert!(!parsed.is_null());
+        assert_eq!((*parsed).scheme, (*info).scheme);
+        assert_eq!((*parsed).key_id_size, 16);
+        assert_eq!(slice::from_raw_parts((*parsed).key_id, 16), &[0xab; 16]);
+        assert_eq!((*parsed).iv_size, 8);
+        assert_eq!(slice::from_raw_parts((*parsed).iv, 8), &[0xcd; 8]);
+        assert_eq!((*parsed).subsample_count, 1);
+        assert_eq!((*(*parsed).subsamples).bytes_of_clear_data, 5);
+        assert_eq!((*(*parsed).subsamples).bytes_of_protected_data, 100);
+
+        av_encryption_info_free(parsed);
+        av_encryption_info_free(info);
+        av_free(side_data as *mut _);
+    }
+}
+
+#[cfg(feature = "has_dovi_metadata")]
+#[test]
+fn dovi_metadata() {
+    use std::mem;
+
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let mut size = 0;
+        let config = av_dovi_alloc(&mut size);
+        assert!(!config.is_null());
+        assert_eq!(size, mem::size_of::<AVDOVIDecoderConfigurationRecord>());
+        av_free(config as *mut _);
+
+        let metadata = av_dovi_metadata_alloc(&mut size);
+        assert!(!metadata.is_null());
+
+        let base = metadata as usize;
+        let header = av_dovi_get_header(metadata);
+        let mapping = av_dovi_get_mapping(metadata);
+        let color = av_dovi_get_color(metadata);
+        for &(offset, len) in &[
+            (header as usize - base, mem::size_of::<AVDOVIRpuDataHeader>()),
+            (mapping as usize - base, mem::size_of::<AVDOVIDataMapping>()),
+            (color as usize - base, mem::size_of::<AVDOVIColorMetadata>()),
+        ] {
+            assert!(offset >= mem::size_of::<AVDOVIMetadata>());
+            assert!(offset + len <= size);
+        }
+
+        (*header).rpu_format = 18;
+        (*color).dm_metadata_id = 1;
+        assert_eq!((*av_dovi_get_header(metadata)).rpu_format, 18);
+        assert_eq!((*av_dovi_get_color(metadata)).dm_metadata_id, 1);
+
+        av_free(metadata as *mut _);
+    }
+}
+
+#[cfg(feature = "has_tx")]
+#[test]
+fn tx_forward_fft() {
+    use std::mem;
+    use std::ptr;
+
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let mut context = ptr::null_mut();
+        let mut transform: av_tx_fn = None;
+        let scale = 1.0f32;
+
+        assert_eq!(
+            av_tx_init(
+                &mut context,
+                &mut transform,
+                AVTXType::AV_TX_FLOAT_FFT,
+                0,
+                4,
+                &scale as *const f32 as *const _,
+                0,
+            ),
+            0
+        );
+
+        let mut input = [
+            AVComplexFloat { re: 1.0, im: 0.0 },
+            AVComplexFloat { re: 2.0, im: 0.0 },
+            AVComplexFloat { re: 3.0, im: 0.0 },
+            AVComplexFloat { re: 4.0, im: 0.0 },
+        ];
+        let mut output = [AVComplexFloat { re: 0.0, im: 0.0 }; 4];
+
+        transform.unwrap()(
+            context,
+            output.as_mut_ptr() as *mut _,
+            input.as_mut_ptr() as *mut _,
+            mem::size_of::<AVComplexFloat>() as isize,
+        );
+
+        let close = |c: &AVComplexFloat, re: f32, im: f32| {
+            (c.re - re).abs() < 1e-5 && (c.im - im).abs() < 1e-5
+        };
+        assert!(close(&output[0], 10.0, 0.0));
+        assert!(close(&output[2], -2.0, 0.0));
+        assert!(
+            close(&output[1], -2.0, 2.0) && close(&output[3], -2.0, -2.0)
+                || close(&output[1], -2.0, -2.0) && close(&output[3], -2.0, 2.0)
+        );
+
+        av_tx_uninit(&mut context);
+        assert!(context.is_null());
+    }
+}
+
+#[cfg(feature = "has_video_enc_params")]
+#[test]
+fn video_enc_params_blocks() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let mut frame = av_frame_alloc();
+
+        let params = av_video_enc_params_create_side_data(
+            frame,
+            AVVideoEncParamsType::AV_VIDEO_ENC_PARAMS_H264,
+            3,
+        );
+        assert!(!params.is_null());
+        assert_eq!((*params).nb_blocks, 3);
+
+        for i in 0..(*params).nb_blocks {
+            let block = av_video_enc_params_block(params, i);
+            (*block).src_x = 16 * i as i32;
+            (*block).delta_qp = -(i as i32);
+        }
+
+        let side_data =
+            av_frame_get_side_data(frame, AVFrameSideDataType::AV_FRAME_DATA_VIDEO_ENC_PARAMS);
+        assert!(!side_data.is_null());
+
+        let params = (*side_data).data as *mut AVVideoEncParams;
+        for i in 0..(*params).nb_blocks {
+            let block = av_video_enc_params_block(params, i);
+            assert_eq!((*block).src_x, 16 * i as i32);
+            assert_eq!((*block).delta_qp, -(i as i32));
+        }
+
+        av_frame_free(&mut frame);
+    }
+}
+
+#[cfg(feature = "has_detection_bbox")]
+#[test]
+fn detection_bboxes() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let mut frame = av_frame_alloc();
+
+        let header = av_detection_bbox_create_side_data(frame, 2);
+        assert!(!header.is_null());
+        assert_eq!((*header).nb_bboxes, 2);
+
+        for i in 0..(*header).nb_bboxes {
+            let bbox = av_get_detection_bbox(header, i);
+            (*bbox).x = 10 * i as i32;
+            (*bbox).w = 32;
+            (*bbox).detect_confidence = av_make_q(i as i32 + 1, 2);
+        }
+
+        let side_data =
+            av_frame_get_side_data(frame, AVFrameSideDataType::AV_FRAME_DATA_DETECTION_BBOXES);
+        assert!(!side_data.is_null());
+
+        let header = (*side_data).data as *const AVDetectionBBoxHeader;
+        for i in 0..(*header).nb_bboxes {
+            let bbox = av_get_detection_bbox(header, i);
+            assert_eq!((*bbox).x, 10 * i as i32);
+            assert_eq!((*bbox).w, 32);
+            assert_eq!((*bbox).detect_confidence.num, i as i32 + 1);
+        }
+
+        av_frame_free(&mut frame);
+    }
+}
+
+#[test]
+fn pixelutils_sad() {
+    use ffmpeg_sys::*;
+    use std::ptr;
+
+    unsafe {
+        // None when FFmpeg is configured without pixelutils, which CI always builds
+        let sad = av_pixelutils_get_sad_fn(3, 3, 0, ptr::null_mut())
+            .expect("no 8x8 SAD function, FFmpeg is configured without pixelutils");
+
+        let a = [10u8; 64];
+        let mut b = [10u8; 64];
+        b[0] = 14;
+        b[63] = 4;
+
+        assert_eq!(sad(a.as_ptr(), 8, b.as_ptr(), 8), 10);
+        assert_eq!(sad(a.as_ptr(), 8, a.as_ptr(), 8), 0);
+    }
+}
+
+#[cfg(feature = "has_csp")]
+#[test]
+fn csp_layout() {
+    use ffmpeg_sys::*;
+    use std::mem::size_of;
+
+    assert_eq!(size_of::<AVCIExy>(), 2 * size_of::<AVRational>());
+    assert_eq!(size_of::<AVLumaCoefficients>(), 3 * size_of::<AVRational>());
+    assert_eq!(size_of::<AVPrimaryCoefficients>(), 3 * size_of::<AVCIExy>());
+    assert_eq!(
+        size_of::<AVColorPrimariesDesc>(),
+        size_of::<AVWhitepointCoefficients>() + size_of::<AVPrimaryCoefficients>()
+    );
+}
+
+#[cfg(feature = "has_csp")]
+#[test]
+fn csp_bt709() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let luma = av_csp_luma_coeffs_from_avcsp(AVColorSpace::AVCOL_SPC_BT709);
+        assert!(!luma.is_null());
+        assert!((av_q2d((*luma).cr) - 0.2126).abs() < 1e-6);
+        assert!((av_q2d((*luma).cg) - 0.7152).abs() < 1e-6);
+        assert!((av_q2d((*luma).cb) - 0.0722).abs() < 1e-6);
+
+        let desc = av_csp_primaries_desc_from_id(AVColorPrimaries::AVCOL_PRI_BT709);
+        assert!(!desc.is_null());
+        assert!((av_q2d((*desc).wp.x) - 0.3127).abs() < 1e-6);
+        assert!((av_q2d((*desc).wp.y) - 0.3290).abs() < 1e-6);
+        assert!((av_q2d((*desc).prim.r.x) - 0.640).abs() < 1e-6);
+        assert!((av_q2d((*desc).prim.b.y) - 0.060).abs() < 1e-6);
+    }
+}
+
+#[cfg(feature = "has_ambient_viewing_environment")]
+#[test]
+fn ambient_viewing_environment() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let mut frame = av_frame_alloc();
+
+        let env = av_ambient_viewing_environment_create_side_data(frame);
+        assert!(!env.is_null());
+        (*env).ambient_illuminance = av_make_q(314, 10000);
+        (*env).ambient_light_x = av_make_q(15635, 50000);
+        (*env).ambient_light_y = av_make_q(16450, 50000);
+
+        let side_data = av_frame_get_side_data(
+            frame,
+            AVFrameSideDataType::AV_FRAME_DATA_AMBIENT_VIEWING_ENVIRONMENT,
+        );
+        assert!(!side_data.is_null());
+
+        let env = (*side_data).data as *const AVAmbientViewingEnvironment;
+        assert_eq!((*env).ambient_illuminance.num, 314);
+        assert_eq!((*env).ambient_light_x.num, 15635);
+        assert_eq!((*env).ambient_light_y.den, 50000);
+
+        av_frame_free(&mut frame);
+    }
+}
+
+#[cfg(feature = "has_uuid")]
+#[test]
+fn uuid_round_trip() {
+    use ffmpeg_sys::*;
+    use std::ffi::{CStr, CString};
+    use std::os::raw::c_char;
+
+    unsafe {
+        let input = CString::new("6ba7b810-9dad-11d1-80b4-00c04fd430c8").unwrap();
+
+        let mut uuid: AVUUID = [0u8; 16];
+        assert_eq!(av_uuid_parse(input.as_ptr(), uuid.as_mut_ptr()), 0);
+        assert_eq!(uuid[0], 0x6b);
+        assert_eq!(uuid[15], 0xc8);
+
+        let mut output = [0 as c_char; 37];
+        av_uuid_unparse(uuid.as_ptr(), output.as_mut_ptr());
+        assert_eq!(CStr::from_ptr(output.as_ptr()), input.as_c_str());
+    }
+}
+
+#[test]
+fn channel_layout_masks() {
+    use std::ffi::CString;
+    #[cfg(feature = "avutil_version_greater_than_57_23")]
+    use std::mem;
+
+    use ffmpeg_sys::*;
+
+    assert_eq!(AV_CH_LAYOUT_STEREO, 0x3);
+    assert_eq!(AV_CH_LAYOUT_5POINT1, 0x60f);
+
+    for &(name, mask) in &[
+        ("mono", AV_CH_LAYOUT_MONO),
+        ("stereo", AV_CH_LAYOUT_STEREO),
+        ("quad", AV_CH_LAYOUT_QUAD),
+        ("5.1", AV_CH_LAYOUT_5POINT1),
+        ("7.1", AV_CH_LAYOUT_7POINT1),
+    ] {
+        let name = CString::new(name).unwrap();
+
+        #[cfg(feature = "avutil_version_greater_than_57_23")]
+        unsafe {
+            let mut layout: AVChannelLayout = mem::zeroed();
+            assert_eq!(av_channel_layout_from_string(&mut layout, name.as_ptr()), 0);
+            assert_eq!(layout.u.mask, mask);
+            av_channel_layout_uninit(&mut layout);
+        }
+
+        #[cfg(not(feature = "avutil_version_greater_than_57_23"))]
+        unsafe {
+            assert_eq!(av_get_channel_layout(name.as_ptr()), mask);
+        }
+    }
+}
+
+#[test]
+fn frame_channel_layout() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let mut frame = av_frame_alloc();
+        assert!(!frame.is_null());
+
+        #[cfg(feature = "has_av_channel_layout")]
+        {
+            use std::ffi::CStr;
+            use std::os::raw::c_char;
+
+            av_channel_layout_default(&mut (*frame).ch_layout, 2);
+            assert_eq!((*frame).ch_layout.nb_channels, 2);
+
+            let mut buf = [0 as c_char; 64];
+            let layout = &(*frame).ch_layout;
+            assert!(av_channel_layout_describe(layout, buf.as_mut_ptr(), buf.len()) > 0);
+            assert_eq!(CStr::from_ptr(buf.as_ptr()).to_str().unwrap(), "stereo");
+            assert!(format!("{:?}", (*frame).ch_layout).contains("mask: 3"));
+        }
+
+        #[cfg(not(feature = "has_av_channel_layout"))]
+        {
+            (*frame).channel_layout = AV_CH_LAYOUT_STEREO;
+            assert_eq!(av_get_channel_layout_nb_channels((*frame).channel_layout), 2);
+        }
+
+        av_frame_free(&mut frame);
+    }
+}
+
+#[test]
+fn fifo() {
+    use ffmpeg_sys::*;
+
+    let input = [1u32, 2, 3, 4];
+    let mut output = [0u32; 4];
+
+    #[cfg(feature = "has_avfifo2")]
+    unsafe {
+        let mut fifo = av_fifo_alloc2(input.len(), 4, 0);
+        assert!(!fifo.is_null());
+        assert_eq!(av_fifo_elem_size(fifo), 4);
+
+        assert_eq!(av_fifo_write(fifo, input.as_ptr() as *const _, input.len()), 0);
+        assert_eq!(av_fifo_can_read(fifo), input.len());
+
+        assert_eq!(av_fifo_read(fifo, output.as_mut_ptr() as *mut _, output.len()), 0);
+        assert_eq!(av_fifo_can_read(fifo), 0);
+
+        av_fifo_freep2(&mut fifo);
+        assert!(fifo.is_null());
+    }
+
+    #[cfg(not(feature = "has_avfifo2"))]
+    unsafe {
+        let size = input.len() * 4;
+        let mut fifo = av_fifo_alloc(size as u32);
+        assert!(!fifo.is_null());
+
+        let written = av_fifo_generic_write(fifo, input.as_ptr() as *mut _, size as i32, None);
+        assert_eq!(written, size as i32);
+        assert_eq!(av_fifo_size(fifo), size as i32);
+
+        let read = av_fifo_generic_read(fifo, output.as_mut_ptr() as *mut _, size as i32, None);
+        assert_eq!(read, 0);
+        assert_eq!(av_fifo_size(fifo), 0);
+
+        av_fifo_freep(&mut fifo);
+        assert!(fifo.is_null());
+    }
+
+    assert_eq!(output, input);
+}
+
+#[test]
+fn shared_libraries() {
+    use std::path::Path;
+
+    use ffmpeg_sys::build_info;
+
+    for path in build_info::shared_libraries() {
+        assert!(Path::new(path).is_absolute(), "{} isn't absolute", path);
+        assert!(Path::new(path).exists(), "{} doesn't exist", path);
+    }
+
+    // builds from source are linked statically
+    #[cfg(feature = "build")]
+    assert!(build_info::shared_libraries().is_empty());
+}
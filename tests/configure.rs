@@ -0,0 +1,43 @@
+#![cfg(feature = "build")]
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+#[test]
+fn configure_flags() {
+    let out = PathBuf::from(env!("OUT_DIR"));
+    let source = out.join(format!(
+        "ffmpeg-{}.{}",
+        env!("CARGO_PKG_VERSION_MAJOR"),
+        env!("CARGO_PKG_VERSION_MINOR")
+    ));
+
+    let help = Command::new("sh")
+        .arg("configure")
+        .arg("--help")
+        .current_dir(&source)
+        .output()
+        .expect("failed to run configure --help");
+    let help = String::from_utf8_lossy(&help.stdout);
+
+    // configure lists most options only in the opposite form of the default
+    let known: HashSet<_> = help
+        .split_whitespace()
+        .filter(|word| word.starts_with("--enable-") || word.starts_with("--disable-"))
+        .map(|word| {
+            let word = word.splitn(2, '=').next().unwrap();
+            word.splitn(2, "able-").nth(1).unwrap().to_owned()
+        })
+        .collect();
+
+    let args = fs::read_to_string(out.join("configure-args")).unwrap();
+    let unknown: Vec<_> = args
+        .lines()
+        .filter(|arg| arg.starts_with("--enable-") || arg.starts_with("--disable-"))
+        .filter(|arg| !known.contains(arg.splitn(2, "able-").nth(1).unwrap()))
+        .collect();
+
+    assert!(unknown.is_empty(), "unknown configure flags: {:?}", unknown);
+}
@@ -0,0 +1,47 @@
+#![cfg(feature = "dlopen")]
+
+extern crate ffmpeg_sys;
+
+#[test]
+fn load() {
+    use ffmpeg_sys::dlopen::Library;
+    use ffmpeg_sys::*;
+
+    let library = Library::load(&[]).unwrap();
+    unsafe {
+        assert_eq!(
+            (library.avutil.avutil_version() >> 16) as i32,
+            LIBAVUTIL_VERSION_MAJOR
+        );
+        assert_eq!(
+            (library.avcodec.avcodec_version() >> 16) as i32,
+            LIBAVCODEC_VERSION_MAJOR
+        );
+
+        let buf = library.avutil.av_malloc(64);
+        assert!(!buf.is_null());
+        library.avutil.av_free(buf);
+    }
+}
+
+#[test]
+fn not_found() {
+    use std::path::Path;
+
+    use ffmpeg_sys::dlopen::{Error, Library};
+    use ffmpeg_sys::*;
+
+    // given directories, the search path of the system isn't tried
+    match Library::load(&[Path::new("/nonexistent")]) {
+        Ok(_) => panic!("loaded the libraries from /nonexistent"),
+        Err(Error::NotFound { library, tried }) => {
+            // the dependencies are loaded first
+            assert_eq!(library, "avutil");
+            assert_eq!(tried.len(), 1);
+
+            let file_name = tried[0].0.file_name().unwrap().to_string_lossy().into_owned();
+            assert!(tried[0].0.starts_with("/nonexistent"));
+            assert!(file_name.contains(&LIBAVUTIL_VERSION_MAJOR.to_string()), "{}", file_name);
+        }
+    }
+}
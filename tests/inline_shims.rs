@@ -0,0 +1,85 @@
+#![cfg(feature = "inline-shims")]
+
+extern crate ffmpeg_sys;
+
+#[test]
+fn common() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        assert_eq!(av_clip(300, 0, 255), 255);
+        assert_eq!(av_clip_uint8(-1), 0);
+        assert_eq!(av_clip_int16(40000), 32767);
+        assert_eq!(av_clipf(1.5, 0.0, 1.0), 1.0);
+        assert_eq!(av_sat_add32(i32::max_value(), 1), i32::max_value());
+        assert_eq!(av_ceil_log2(5), 3);
+        assert_eq!(av_popcount(0xf0f0), 8);
+    }
+}
+
+#[test]
+fn bswap() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        assert_eq!(av_bswap16(0x1234), 0x3412);
+        assert_eq!(av_bswap32(0x12345678), 0x78563412);
+        assert_eq!(av_bswap64(0x0102030405060708), 0x0807060504030201);
+    }
+}
+
+#[test]
+fn size_mult() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let mut size = 0;
+        assert_eq!(av_size_mult(3, 4, &mut size), 0);
+        assert_eq!(size, 12);
+        assert!(av_size_mult(usize::max_value(), 2, &mut size) < 0);
+    }
+}
+
+#[test]
+fn x_if_null() {
+    use std::os::raw::c_void;
+    use std::ptr;
+
+    use ffmpeg_sys::*;
+
+    let x = 0u8;
+    let x = &x as *const u8 as *const c_void;
+    unsafe {
+        assert_eq!(av_x_if_null(ptr::null(), x) as *const c_void, x);
+    }
+}
+
+#[test]
+fn strings() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        assert_eq!(av_strnlen(b"ffmpeg\0".as_ptr() as *const _, 3), 3);
+        assert!(av_isdigit('7' as i32) != 0);
+        assert_eq!(av_isdigit('x' as i32), 0);
+    }
+}
+
+#[test]
+fn timestamps() {
+    use std::ffi::CStr;
+    use std::os::raw::c_char;
+
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let mut buf = [0 as c_char; 32];
+
+        av_ts_make_string(buf.as_mut_ptr(), 42);
+        assert_eq!(CStr::from_ptr(buf.as_ptr()).to_bytes(), b"42");
+
+        let mut time_base = AVRational { num: 1, den: 2 };
+        av_ts_make_time_string(buf.as_mut_ptr(), 3, &mut time_base);
+        assert_eq!(CStr::from_ptr(buf.as_ptr()).to_bytes(), b"1.5");
+    }
+}
@@ -0,0 +1,28 @@
+#![cfg(feature = "postproc")]
+
+extern crate ffmpeg_sys;
+
+#[test]
+fn version() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        assert_eq!((postproc_version() >> 16) as i32, LIBPOSTPROC_VERSION_MAJOR);
+    }
+}
+
+#[test]
+fn context() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let mode = pp_get_mode_by_name_and_quality(b"de\0".as_ptr() as *const _, PP_QUALITY_MAX);
+        assert!(!mode.is_null());
+
+        let context = pp_get_context(16, 16, PP_FORMAT_420 | PP_CPU_CAPS_AUTO);
+        assert!(!context.is_null());
+
+        pp_free_context(context);
+        pp_free_mode(mode);
+    }
+}
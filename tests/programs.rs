@@ -0,0 +1,37 @@
+#![cfg(all(feature = "build", feature = "ffprobe"))]
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+#[test]
+fn ffprobe_version() {
+    let out = PathBuf::from(env!("OUT_DIR"));
+    // left in the dist it was installed into, next to the datadir compiled into it
+    let dist = out.join("dist");
+    let ffprobe = if cfg!(windows) {
+        dist.join("bin/ffprobe.exe")
+    } else {
+        dist.join("bin/ffprobe")
+    };
+
+    let output = Command::new(&ffprobe)
+        .arg("-version")
+        .output()
+        .expect("failed to run ffprobe");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let configuration = stdout
+        .lines()
+        .find(|line| line.starts_with("configuration:"))
+        .expect("no configuration line");
+    let args = fs::read_to_string(out.join("configure-args")).unwrap();
+    // configure quotes the arguments with spaces in them, and the build writes OUT_DIR as .
+    for arg in args.lines().filter(|arg| !arg.contains(' ')) {
+        let arg = arg.replace(env!("OUT_DIR"), ".");
+        assert!(configuration.contains(&arg), "{} missing from {}", arg, configuration);
+    }
+    assert!(!configuration.contains(env!("OUT_DIR")));
+    assert!(dist.join("share/ffmpeg").is_dir());
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "swresample")]
+
+extern crate ffmpeg_sys;
+
+#[test]
+fn version() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        assert_eq!((swresample_version() >> 16) as i32, LIBSWRESAMPLE_VERSION_MAJOR);
+    }
+}
+
+#[test]
+fn options() {
+    use ffmpeg_sys::*;
+
+    assert_eq!(SWR_CH_MAX, 32);
+
+    unsafe {
+        let mut context = swr_alloc();
+        assert!(!context.is_null());
+
+        for &(name, value) in &[
+            (&b"dither_method\0"[..], SwrDitherType::SWR_DITHER_TRIANGULAR as i64),
+            (&b"resampler\0"[..], SwrEngine::SWR_ENGINE_SWR as i64),
+            (&b"filter_type\0"[..], SwrFilterType::SWR_FILTER_TYPE_KAISER as i64),
+        ] {
+            let name = name.as_ptr() as *const _;
+            assert_eq!(av_opt_set_int(context as *mut _, name, value, 0), 0);
+
+            let mut read = 0;
+            assert_eq!(av_opt_get_int(context as *mut _, name, 0, &mut read), 0);
+            assert_eq!(read, value);
+        }
+
+        swr_free(&mut context);
+        assert!(context.is_null());
+    }
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "swscale")]
+
+extern crate ffmpeg_sys;
+
+#[test]
+fn version() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        assert_eq!((swscale_version() >> 16) as i32, LIBSWSCALE_VERSION_MAJOR);
+    }
+}
+
+#[test]
+fn scale() {
+    use std::ptr;
+
+    use ffmpeg_sys::*;
+
+    unsafe {
+        let context = sws_getContext(
+            4,
+            4,
+            AVPixelFormat::AV_PIX_FMT_GRAY8,
+            2,
+            2,
+            AVPixelFormat::AV_PIX_FMT_GRAY8,
+            SWS_POINT,
+            ptr::null_mut(),
+            ptr::null_mut(),
+            ptr::null(),
+        );
+        assert!(!context.is_null());
+
+        let src = [100u8; 16];
+        let mut dst = [0u8; 4];
+
+        let src_data = [src.as_ptr()];
+        let src_stride = [4];
+        let dst_data = [dst.as_mut_ptr()];
+        let dst_stride = [2];
+
+        let height = sws_scale(
+            context,
+            src_data.as_ptr(),
+            src_stride.as_ptr(),
+            0,
+            4,
+            dst_data.as_ptr(),
+            dst_stride.as_ptr(),
+        );
+        assert_eq!(height, 2);
+        assert_eq!(dst, [100u8; 4]);
+
+        sws_freeContext(context);
+    }
+}
+
+#[test]
+fn constants() {
+    use ffmpeg_sys::*;
+
+    // a float macro, bindgen keeps those as f64
+    let cutoff: f64 = SWS_MAX_REDUCE_CUTOFF;
+    assert!(cutoff > 0.0 && cutoff < 1.0);
+    assert_eq!(SWS_PARAM_DEFAULT, 123456);
+}
+
+#[test]
+fn coefficients() {
+    use ffmpeg_sys::*;
+
+    unsafe {
+        // crv, cbu, cgu and cgv of the YUV to RGB conversion
+        let table = sws_getCoefficients(SWS_CS_ITU709);
+        assert!(!table.is_null());
+        assert!(*table > 0);
+    }
+}
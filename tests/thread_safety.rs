@@ -0,0 +1,53 @@
+extern crate ffmpeg_sys;
+
+#[cfg(feature = "std")]
+const BINDINGS: &str = include_str!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+
+#[cfg(feature = "std")]
+#[test]
+fn covers_contexts() {
+    use ffmpeg_sys::thread_safety::*;
+
+    let table = thread_safety();
+    let names: Vec<_> = table.iter().map(|&(name, _, _)| name).collect();
+
+    // every `*Context` struct FFmpeg's headers gave the bindings, except the hardware-specific
+    // ones, which only live behind the `hwctx` of an AVHWDeviceContext or AVHWFramesContext or
+    // the `hwaccel_context` of an AVCodecContext and go with those
+    let hardware = ["D3D11VA", "DXVA2", "QSV", "VAAPI", "VDPAU", "Videotoolbox", "Vulkan"];
+    let contexts: Vec<_> = BINDINGS
+        .lines()
+        .filter_map(|line| line.trim_start().strip_prefix("pub struct "))
+        .filter_map(|line| line.split(|c: char| !c.is_alphanumeric() && c != '_').next())
+        .filter(|name| name.ends_with("Context"))
+        // FFmpeg's own types, not those of the system headers the hardware contexts pull in
+        .filter(|name| ["AV", "Sw", "FFT", "RDFT", "DCT"].iter().any(|p| name.starts_with(p)))
+        .filter(|name| !hardware.iter().any(|h| name.starts_with(&format!("AV{}", h))))
+        .collect();
+    assert!(contexts.contains(&"AVHWDeviceContext"), "no contexts found in the bindings");
+
+    let missing: Vec<_> = contexts.iter().filter(|name| !names.contains(name)).collect();
+    assert!(missing.is_empty(), "no thread-safety decision for {:?}", missing);
+
+    // anything shareable can also be moved
+    for &(name, send, sync) in &table {
+        assert!(send || !sync, "{} is Sync but not Send", name);
+    }
+}
+
+#[cfg(feature = "avcodec")]
+#[test]
+fn send_wrapper() {
+    use std::ptr;
+    use std::thread;
+
+    use ffmpeg_sys::thread_safety::*;
+    use ffmpeg_sys::*;
+
+    assert!(AVCODECCONTEXT_THREAD_SAFE_SEND);
+    assert!(!AVCODECCONTEXT_THREAD_SAFE_SYNC);
+
+    let context = SendWrapper::<AVCodecContext>(ptr::null_mut());
+    let context = thread::spawn(move || context).join().unwrap();
+    assert!(context.0.is_null());
+}
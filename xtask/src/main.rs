@@ -0,0 +1,163 @@
+//! Maintenance tasks of ffmpeg-sys, run with `cargo xtask <task>`.
+//!
+//! `snapshot [cargo build arguments]` rewrites snapshots/ffmpeg-<major>.txt from the bindings
+//! of the FFmpeg the build script finds, for `FFMPEG_VERIFY_BINDINGS=1` to compare against. The
+//! build script writes it to its OUT_DIR, the task copies it from there.
+//!
+//! `generate-bindings [--check] [cargo build arguments]` writes the whole bindings of the FFmpeg
+//! the build script finds to generated/ffmpeg-<major>.rs, to review what a change of the builder
+//! does to them. With `--check` the build fails when they differ from that file instead. The
+//! bindings are written to OUT_DIR and copied from there, like the snapshots.
+//!
+//! `pin-ffprobe` downloads the archives of the static ffprobe binaries in
+//! build/ffprobe_binary.rs and rewrites build/ffprobe.sha256 with their checksums, after
+//! changing the pinned release.
+
+extern crate ffmpeg_sys_build;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+
+// the build script's modules, for the table of binaries and the checksums
+use ffmpeg_sys_build::{ffprobe_binary, sha256};
+
+fn cargo(root: &Path) -> Command {
+    let mut command = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".into()));
+    command.current_dir(root);
+    command
+}
+
+fn usage() -> ! {
+    eprintln!("usage: cargo xtask snapshot [cargo build arguments]");
+    eprintln!("       cargo xtask generate-bindings [--check] [cargo build arguments]");
+    eprintln!("       cargo xtask pin-ffprobe");
+    process::exit(2);
+}
+
+// runs the build script again with `var` set, it only reruns by itself when `var` changes, and
+// returns the OUT_DIR it ran in
+fn build_with(root: &Path, args: Vec<String>, var: &str, value: &str) -> PathBuf {
+    let output = cargo(root)
+        .arg("build")
+        .arg("--message-format=json-render-diagnostics")
+        .args(&args)
+        .env(var, value)
+        .stderr(Stdio::inherit())
+        .output()
+        .expect("failed to run cargo build");
+    if !output.status.success() {
+        process::exit(output.status.code().unwrap_or(1));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match stdout.lines().rev().find_map(ffmpeg_sys_out_dir) {
+        Some(out_dir) => out_dir,
+        None => {
+            eprintln!("cargo build didn't run the build script of ffmpeg-sys");
+            process::exit(1);
+        }
+    }
+}
+
+// the out_dir of the build-script-executed message of ffmpeg-sys, cargo writes one JSON object
+// per line
+fn ffmpeg_sys_out_dir(message: &str) -> Option<PathBuf> {
+    if !message.contains(r#""reason":"build-script-executed""#) || !message.contains("ffmpeg-sys")
+    {
+        return None;
+    }
+
+    let start = message.find(r#""out_dir":""#)? + r#""out_dir":""#.len();
+    let mut out_dir = String::new();
+    let mut chars = message[start..].chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => return Some(PathBuf::from(out_dir)),
+            '\\' => out_dir.extend(chars.next()),
+            c => out_dir.push(c),
+        }
+    }
+    None
+}
+
+// copies what the build script wrote to OUT_DIR/<dir> into the crate
+fn copy_out(root: &Path, out_dir: &Path, dir: &str) {
+    fs::create_dir_all(root.join(dir)).unwrap();
+    for entry in fs::read_dir(out_dir.join(dir)).expect("the build script wrote nothing") {
+        let entry = entry.unwrap();
+        let target = root.join(dir).join(entry.file_name());
+        fs::copy(entry.path(), &target).expect("failed to copy from OUT_DIR");
+        println!("wrote {}", target.display());
+    }
+}
+
+fn snapshot(root: &Path, args: Vec<String>) {
+    let out_dir = build_with(root, args, "FFMPEG_VERIFY_BINDINGS", "update");
+    copy_out(root, &out_dir, "snapshots");
+}
+
+fn generate_bindings(root: &Path, mut args: Vec<String>) {
+    let mode = match args.iter().position(|arg| arg == "--check") {
+        Some(check) => {
+            args.remove(check);
+            "check"
+        }
+        None => "update",
+    };
+
+    let out_dir = build_with(root, args, "FFMPEG_DUMP_BINDINGS", mode);
+    if mode == "update" {
+        copy_out(root, &out_dir, "generated");
+    }
+}
+
+fn pin_ffprobe(root: &Path) {
+    let download = env::temp_dir().join("ffmpeg-sys-pin-ffprobe");
+    fs::create_dir_all(&download).expect("failed to create the download directory");
+
+    let mut sums = String::from(
+        "# the sha256 of the archives of BINARIES in build/ffprobe_binary.rs, in the format of \
+         sha256sum.\n# `cargo xtask pin-ffprobe` downloads them and rewrites this file\n",
+    );
+    let mut pinned: Vec<&str> = Vec::new();
+    for binary in ffprobe_binary::BINARIES {
+        // several hosts share an archive
+        if pinned.contains(&binary.archive()) {
+            continue;
+        }
+
+        let archive = download.join(binary.archive());
+        let status = Command::new("curl")
+            .args(["--fail", "--silent", "--show-error", "--location", "--output"])
+            .arg(&archive)
+            .arg(binary.url)
+            .status()
+            .expect("failed to run curl");
+        if !status.success() {
+            eprintln!("failed to download {}", binary.url);
+            process::exit(1);
+        }
+
+        let data = fs::read(&archive).unwrap();
+        sums.push_str(&format!("{}  {}\n", sha256::sha256_hex(&data), binary.archive()));
+        pinned.push(binary.archive());
+    }
+
+    fs::write(root.join("build").join("ffprobe.sha256"), sums)
+        .expect("failed to write build/ffprobe.sha256");
+    fs::remove_dir_all(&download).unwrap();
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+
+    match args.next().as_deref() {
+        Some("snapshot") => snapshot(root, args.collect()),
+        Some("generate-bindings") => generate_bindings(root, args.collect()),
+        Some("pin-ffprobe") => pin_ffprobe(root),
+        _ => usage(),
+    }
+}